@@ -8,8 +8,12 @@ use std::collections::HashMap;
 use tokio::sync::Mutex as TokioMutex;
 
 use crate::db::Database;
+use crate::error::{AppError, Result};
+use crate::filter::{self, Filter};
 use crate::models::*;
+use crate::scim::{ListResponse, Schema};
 use crate::scim_client::ScimClient;
+use crate::traffic::TrafficRecorder;
 use crate::validation::ValidationEngine;
 use crate::load_test::LoadTestEngine;
 use crate::export::ExportEngine;
@@ -19,10 +23,20 @@ pub struct AppState {
     pub cancel_flags: TokioMutex<HashMap<String, Arc<AtomicBool>>>,
 }
 
+/// A recorder for runs that opted in to traffic capture. The server's custom API
+/// key header is redacted along with the standard credential headers.
+fn traffic_recorder(capture: Option<bool>, test_run_id: &str, server_config: &ServerConfig) -> Option<Arc<TrafficRecorder>> {
+    if !capture.unwrap_or(false) {
+        return None;
+    }
+    let api_key_header = server_config.api_key_header.as_deref().unwrap_or("");
+    Some(Arc::new(TrafficRecorder::new(test_run_id, &[api_key_header])))
+}
+
 // ── Server Config Commands ──
 
 #[tauri::command]
-pub async fn save_server_config(state: State<'_, AppState>, config: ServerConfig) -> Result<ServerConfig, String> {
+pub async fn save_server_config(state: State<'_, AppState>, config: ServerConfig) -> Result<ServerConfig> {
     let mut config = config;
     let is_new = config.id.is_empty();
     if is_new {
@@ -30,7 +44,7 @@ pub async fn save_server_config(state: State<'_, AppState>, config: ServerConfig
         config.created_at = Utc::now().to_rfc3339();
     }
     config.updated_at = Utc::now().to_rfc3339();
-    state.db.save_server_config(&config).map_err(|e| e.to_string())?;
+    state.db.save_server_config(&config)?;
 
     // Seed default sample data for new servers
     if is_new {
@@ -41,47 +55,69 @@ pub async fn save_server_config(state: State<'_, AppState>, config: ServerConfig
 }
 
 #[tauri::command]
-pub async fn get_server_configs(state: State<'_, AppState>) -> Result<Vec<ServerConfig>, String> {
-    state.db.get_server_configs().map_err(|e| e.to_string())
+pub async fn get_server_configs(state: State<'_, AppState>) -> Result<Vec<ServerConfig>> {
+    state.db.get_server_configs()
 }
 
 #[tauri::command]
-pub async fn get_server_config(state: State<'_, AppState>, id: String) -> Result<Option<ServerConfig>, String> {
-    state.db.get_server_config(&id).map_err(|e| e.to_string())
+pub async fn get_server_config(state: State<'_, AppState>, id: String) -> Result<Option<ServerConfig>> {
+    state.db.get_server_config(&id)
 }
 
 #[tauri::command]
-pub async fn delete_server_config(state: State<'_, AppState>, id: String) -> Result<(), String> {
-    state.db.delete_server_config(&id).map_err(|e| e.to_string())
+pub async fn delete_server_config(state: State<'_, AppState>, id: String) -> Result<()> {
+    state.db.delete_server_config(&id)
 }
 
 // ── Test Connection ──
 
 #[tauri::command]
-pub async fn test_connection(state: State<'_, AppState>, server_config_id: String) -> Result<TestConnectionResult, String> {
+pub async fn test_connection(state: State<'_, AppState>, server_config_id: String) -> Result<TestConnectionResult> {
     let config = state.db.get_server_config(&server_config_id)
-        .map_err(|e| e.to_string())?
-        .ok_or("Server config not found")?;
+        ?
+        .ok_or_else(|| AppError::NotFound("Server config not found".to_string()))?;
 
-    let client = ScimClient::new(&config)?;
     let start = Instant::now();
+    // A bad client certificate is a connection problem, not a command failure
+    let client = match ScimClient::new(&config) {
+        Ok(c) => c,
+        Err(e) => {
+            return Ok(TestConnectionResult {
+                success: false,
+                status_code: None,
+                response_body: None,
+                error: Some(e.to_string()),
+                error_code: Some(e.code().to_string()),
+                duration_ms: 0,
+            });
+        }
+    };
 
     match client.get("/ServiceProviderConfig").await {
         Ok(resp) => {
+            let scim_error = resp.scim_error();
             Ok(TestConnectionResult {
                 success: resp.status == 200,
                 status_code: Some(resp.status),
+                error: scim_error.as_ref().map(|e| e.to_string()),
+                error_code: scim_error.map(|_| "scim".to_string()),
                 response_body: Some(resp.body),
-                error: None,
                 duration_ms: start.elapsed().as_millis() as i64,
             })
         }
         Err(e) => {
+            let uses_client_cert = config.client_cert.as_deref().is_some_and(|c| !c.trim().is_empty());
+            let error = if uses_client_cert && matches!(e, AppError::Tls(_)) {
+                format!("mTLS handshake failed — the server may not trust the client certificate, or the certificate and key do not match. {}", e)
+            } else {
+                e.to_string()
+            };
             Ok(TestConnectionResult {
                 success: false,
                 status_code: None,
                 response_body: None,
-                error: Some(e),
+                error: Some(error),
+                error_code: Some(e.code().to_string()),
                 duration_ms: start.elapsed().as_millis() as i64,
             })
         }
@@ -95,13 +131,23 @@ pub async fn run_validation(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
     config: ValidationRunConfig,
-) -> Result<String, String> {
+) -> Result<String> {
     let server_config = state.db.get_server_config(&config.server_config_id)
-        .map_err(|e| e.to_string())?
-        .ok_or("Server config not found")?;
+        ?
+        .ok_or_else(|| AppError::NotFound("Server config not found".to_string()))?;
 
-    let client = ScimClient::new(&server_config)?;
     let test_run_id = Uuid::new_v4().to_string();
+    let recorder = traffic_recorder(config.capture_traffic, &test_run_id, &server_config);
+    let mut client = ScimClient::new(&server_config)?
+        .with_retry_policy(config.retry_policy.clone().unwrap_or_default());
+    if let Some(ref recorder) = recorder {
+        client = client.with_recorder(recorder.clone());
+    }
+
+    // What the server says it supports decides which tests are skipped, and is
+    // kept with the run for the report
+    let capabilities = ValidationEngine::fetch_service_provider_config(&client).await;
+    let capabilities_json = capabilities.as_ref().and_then(|c| serde_json::to_string(c).ok());
 
     // Create test run record
     let test_run = TestRun {
@@ -112,12 +158,13 @@ pub async fn run_validation(
         started_at: Utc::now().to_rfc3339(),
         completed_at: None,
         summary_json: None,
+        capabilities_json: capabilities_json.clone(),
     };
-    state.db.save_test_run(&test_run).map_err(|e| e.to_string())?;
+    state.db.save_test_run(&test_run)?;
 
     // Load field mapping rules for this server
     let field_mapping_rules = state.db.get_field_mapping_rules(&config.server_config_id)
-        .map_err(|e| e.to_string())?;
+        ?;
 
     let user_jp = config.user_joining_property.as_deref().unwrap_or("userName");
     let group_jp = config.group_joining_property.as_deref().unwrap_or("displayName");
@@ -128,11 +175,14 @@ pub async fn run_validation(
         flags.insert(test_run_id.clone(), cancel_flag.clone());
     }
 
-    let results = ValidationEngine::run(&app, &client, &test_run_id, &config.categories, &field_mapping_rules, user_jp, group_jp, cancel_flag.clone()).await;
+    let results = ValidationEngine::run(&app, &client, &test_run_id, &config.categories, capabilities.as_ref(), &field_mapping_rules, user_jp, group_jp, cancel_flag.clone()).await;
 
     // Save results
     for r in &results {
-        state.db.save_validation_result(r).map_err(|e| e.to_string())?;
+        state.db.save_validation_result(r)?;
+    }
+    if let Some(ref recorder) = recorder {
+        state.db.save_http_exchanges(&recorder.take())?;
     }
 
     // Compute and save summary
@@ -147,9 +197,9 @@ pub async fn run_validation(
         status: status.to_string(),
         started_at: test_run.started_at,
         completed_at: Some(Utc::now().to_rfc3339()),
-        summary_json: Some(summary_json),
+        summary_json: Some(summary_json),        capabilities_json,
     };
-    state.db.save_test_run(&completed_run).map_err(|e| e.to_string())?;
+    state.db.save_test_run(&completed_run)?;
 
     {
         let mut flags = state.cancel_flags.lock().await;
@@ -163,13 +213,13 @@ pub async fn run_validation(
 pub async fn stop_validation(
     state: State<'_, AppState>,
     test_run_id: String,
-) -> Result<(), String> {
+) -> Result<()> {
     let flags = state.cancel_flags.lock().await;
     if let Some(flag) = flags.get(&test_run_id) {
         flag.store(true, Ordering::Relaxed);
         Ok(())
     } else {
-        Err("Validation run not found or already completed".to_string())
+        Err(AppError::NotFound("Validation run not found or already completed".to_string()))
     }
 }
 
@@ -177,8 +227,8 @@ pub async fn stop_validation(
 pub async fn get_validation_results(
     state: State<'_, AppState>,
     test_run_id: String,
-) -> Result<Vec<ValidationResult>, String> {
-    state.db.get_validation_results(&test_run_id).map_err(|e| e.to_string())
+) -> Result<Vec<ValidationResult>> {
+    state.db.get_validation_results(&test_run_id)
 }
 
 // ── Load Test Commands ──
@@ -188,13 +238,23 @@ pub async fn start_load_test(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
     config: LoadTestConfig,
-) -> Result<String, String> {
+) -> Result<String> {
     let server_config = state.db.get_server_config(&config.server_config_id)
-        .map_err(|e| e.to_string())?
-        .ok_or("Server config not found")?;
+        ?
+        .ok_or_else(|| AppError::NotFound("Server config not found".to_string()))?;
 
-    let client = Arc::new(ScimClient::new_with_concurrency(&server_config, config.concurrency)?);
     let test_run_id = Uuid::new_v4().to_string();
+    let recorder = traffic_recorder(config.capture_traffic, &test_run_id, &server_config);
+    let mut client = ScimClient::new_with_concurrency(&server_config, config.concurrency)?
+        .with_retry_policy(config.retry_policy.clone().unwrap_or_default());
+    if let Some(rps) = config.target_rps.filter(|r| *r > 0.0) {
+        // No burst: requests are spaced evenly at exactly the target rate
+        client = client.with_rate_limit(rps, 1);
+    }
+    if let Some(ref recorder) = recorder {
+        client = client.with_recorder(recorder.clone());
+    }
+    let client = Arc::new(client);
     let cancel_flag = Arc::new(AtomicBool::new(false));
 
     // Store cancel flag
@@ -211,16 +271,19 @@ pub async fn start_load_test(
         status: "running".to_string(),
         started_at: Utc::now().to_rfc3339(),
         completed_at: None,
-        summary_json: None,
+        summary_json: None,        capabilities_json: None,
     };
-    state.db.save_test_run(&test_run).map_err(|e| e.to_string())?;
+    state.db.save_test_run(&test_run)?;
 
     let start = Instant::now();
     let results = LoadTestEngine::run_scenario(&app, client, &test_run_id, &config, cancel_flag.clone()).await;
     let total_duration_ms = start.elapsed().as_millis() as i64;
 
     // Save results in batches
-    state.db.save_load_test_results(&results).map_err(|e| e.to_string())?;
+    state.db.save_load_test_results(&results)?;
+    if let Some(ref recorder) = recorder {
+        state.db.save_http_exchanges(&recorder.take())?;
+    }
 
     // Compute summary
     let summary = LoadTestEngine::compute_summary(&results, total_duration_ms);
@@ -234,9 +297,9 @@ pub async fn start_load_test(
         status: status.to_string(),
         started_at: test_run.started_at,
         completed_at: Some(Utc::now().to_rfc3339()),
-        summary_json: Some(summary_json),
+        summary_json: Some(summary_json),        capabilities_json: None,
     };
-    state.db.save_test_run(&completed_run).map_err(|e| e.to_string())?;
+    state.db.save_test_run(&completed_run)?;
 
     // Cleanup cancel flag
     {
@@ -251,13 +314,13 @@ pub async fn start_load_test(
 pub async fn stop_load_test(
     state: State<'_, AppState>,
     test_run_id: String,
-) -> Result<(), String> {
+) -> Result<()> {
     let flags = state.cancel_flags.lock().await;
     if let Some(flag) = flags.get(&test_run_id) {
         flag.store(true, Ordering::Relaxed);
         Ok(())
     } else {
-        Err("Test run not found or already completed".to_string())
+        Err(AppError::NotFound("Test run not found or already completed".to_string()))
     }
 }
 
@@ -265,8 +328,16 @@ pub async fn stop_load_test(
 pub async fn get_load_test_results(
     state: State<'_, AppState>,
     test_run_id: String,
-) -> Result<Vec<LoadTestResult>, String> {
-    state.db.get_load_test_results(&test_run_id).map_err(|e| e.to_string())
+) -> Result<Vec<LoadTestResult>> {
+    state.db.get_load_test_results(&test_run_id)
+}
+
+#[tauri::command]
+pub async fn get_http_exchanges(
+    state: State<'_, AppState>,
+    test_run_id: String,
+) -> Result<Vec<HttpExchange>> {
+    state.db.get_http_exchanges(&test_run_id)
 }
 
 // ── Test Run Commands ──
@@ -276,27 +347,27 @@ pub async fn get_test_runs(
     state: State<'_, AppState>,
     server_config_id: Option<String>,
     run_type: Option<String>,
-) -> Result<Vec<TestRun>, String> {
+) -> Result<Vec<TestRun>> {
     state.db.get_test_runs(
         server_config_id.as_deref(),
         run_type.as_deref(),
-    ).map_err(|e| e.to_string())
+    )
 }
 
 #[tauri::command]
 pub async fn get_test_run(
     state: State<'_, AppState>,
     id: String,
-) -> Result<Option<TestRun>, String> {
-    state.db.get_test_run(&id).map_err(|e| e.to_string())
+) -> Result<Option<TestRun>> {
+    state.db.get_test_run(&id)
 }
 
 #[tauri::command]
 pub async fn delete_test_run(
     state: State<'_, AppState>,
     id: String,
-) -> Result<(), String> {
-    state.db.delete_test_run(&id).map_err(|e| e.to_string())
+) -> Result<()> {
+    state.db.delete_test_run(&id)
 }
 
 // ── Export Commands ──
@@ -306,14 +377,23 @@ pub async fn export_report(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
     request: ExportRequest,
-) -> Result<(), String> {
+) -> Result<()> {
     let test_run = state.db.get_test_run(&request.test_run_id)
-        .map_err(|e| e.to_string())?
-        .ok_or("Test run not found")?;
+        ?
+        .ok_or_else(|| AppError::NotFound("Test run not found".to_string()))?;
+
+    // HAR is a trace of the captured traffic, the same for either run type
+    if request.format == "har" {
+        let exchanges = state.db.get_http_exchanges(&request.test_run_id)?;
+        if exchanges.is_empty() {
+            return Err(AppError::Export("No HTTP traffic was captured for this run. Enable traffic capture before running it.".to_string()));
+        }
+        return ExportEngine::export_har(&exchanges, &request.output_path);
+    }
 
     match test_run.run_type.as_str() {
         "validation" => {
-            let results = state.db.get_validation_results(&request.test_run_id).map_err(|e| e.to_string())?;
+            let results = state.db.get_validation_results(&request.test_run_id)?;
             let summary: ValidationSummary = test_run.summary_json
                 .as_ref()
                 .and_then(|s| serde_json::from_str(s).ok())
@@ -328,7 +408,7 @@ pub async fn export_report(
             }
         }
         "loadtest" => {
-            let results = state.db.get_load_test_results(&request.test_run_id).map_err(|e| e.to_string())?;
+            let results = state.db.get_load_test_results(&request.test_run_id)?;
             let total_duration: i64 = results.last().map_or(0, |r| r.duration_ms);
             let summary: LoadTestSummary = test_run.summary_json
                 .as_ref()
@@ -344,14 +424,14 @@ pub async fn export_report(
             }
         }
         _ => Err("Unknown test run type".to_string()),
-    }?;
+    }.map_err(AppError::Export)?;
 
     // PDF → open in browser (user prints to PDF). Excel → open in default spreadsheet app.
     if request.format == "pdf" || request.format == "excel" {
         use tauri_plugin_opener::OpenerExt;
         app.opener()
             .open_path(&request.output_path, None::<&str>)
-            .map_err(|e| format!("Failed to open report: {}", e))?;
+            .map_err(|e| AppError::Export(format!("Failed to open report: {}", e)))?;
     }
 
     Ok(())
@@ -360,8 +440,8 @@ pub async fn export_report(
 // ── Utility Commands ──
 
 #[tauri::command]
-pub async fn clear_all_data(state: State<'_, AppState>) -> Result<(), String> {
-    state.db.clear_all_data().map_err(|e| e.to_string())
+pub async fn clear_all_data(state: State<'_, AppState>) -> Result<()> {
+    state.db.clear_all_data()
 }
 
 // ── Custom Schema Discovery ──
@@ -370,10 +450,10 @@ pub async fn clear_all_data(state: State<'_, AppState>) -> Result<(), String> {
 pub async fn discover_custom_schema(
     state: State<'_, AppState>,
     server_config_id: String,
-) -> Result<Vec<DiscoveredSchemaAttribute>, String> {
+) -> Result<Vec<DiscoveredSchemaAttribute>> {
     let config = state.db.get_server_config(&server_config_id)
-        .map_err(|e| e.to_string())?
-        .ok_or("Server config not found")?;
+        ?
+        .ok_or_else(|| AppError::NotFound("Server config not found".to_string()))?;
     let client = ScimClient::new(&config)?;
     Ok(ValidationEngine::discover_custom_attributes(&client).await)
 }
@@ -384,31 +464,25 @@ pub async fn discover_custom_schema(
 pub async fn get_scim_schemas(
     state: State<'_, AppState>,
     server_config_id: String,
-) -> Result<Vec<serde_json::Value>, String> {
+) -> Result<Vec<Schema>> {
     let config = state.db.get_server_config(&server_config_id)
-        .map_err(|e| e.to_string())?
-        .ok_or("Server config not found")?;
+        ?
+        .ok_or_else(|| AppError::NotFound("Server config not found".to_string()))?;
     let client = ScimClient::new(&config)?;
 
-    let resp = client.get("/Schemas").await
-        .map_err(|e| format!("Failed to fetch /Schemas: {}", e))?;
+    let resp = client.get("/Schemas").await?;
 
     if resp.status != 200 {
-        return Err(format!("/Schemas returned status {}", resp.status));
+        return Err(match resp.scim_error() {
+            Some(e) => AppError::Scim(e),
+            None => AppError::Other(format!("/Schemas returned status {}", resp.status)),
+        });
     }
 
-    let json: serde_json::Value = serde_json::from_str(&resp.body)
-        .map_err(|e| format!("Failed to parse /Schemas response: {}", e))?;
-
     // Handle both ListResponse wrapper and direct array
-    let schemas: Vec<serde_json::Value> = if let Some(resources) = json.get("Resources").or_else(|| json.get("resources")) {
-        resources.as_array().cloned().unwrap_or_default()
-    } else if let Some(arr) = json.as_array() {
-        arr.clone()
-    } else {
-        // Single schema object
-        vec![json]
-    };
+    let schemas = ListResponse::<Schema>::parse_lenient(&resp.body)
+        .map_err(|e| AppError::Other(format!("Failed to parse /Schemas response: {}", e)))?
+        .into_resources();
 
     Ok(schemas)
 }
@@ -416,71 +490,89 @@ pub async fn get_scim_schemas(
 // ── Field Mapping Commands ──
 
 #[tauri::command]
-pub async fn save_field_mapping_rule(state: State<'_, AppState>, rule: FieldMappingRule) -> Result<FieldMappingRule, String> {
+pub async fn save_field_mapping_rule(state: State<'_, AppState>, rule: FieldMappingRule) -> Result<FieldMappingRule> {
     let mut rule = rule;
     if rule.id.is_empty() {
         rule.id = Uuid::new_v4().to_string();
         rule.created_at = Utc::now().to_rfc3339();
     }
     rule.updated_at = Utc::now().to_rfc3339();
-    state.db.save_field_mapping_rule(&rule).map_err(|e| e.to_string())?;
+    state.db.save_field_mapping_rule(&rule)?;
     Ok(rule)
 }
 
 #[tauri::command]
-pub async fn get_field_mapping_rules(state: State<'_, AppState>, server_config_id: String) -> Result<Vec<FieldMappingRule>, String> {
-    state.db.get_field_mapping_rules(&server_config_id).map_err(|e| e.to_string())
+pub async fn get_field_mapping_rules(state: State<'_, AppState>, server_config_id: String) -> Result<Vec<FieldMappingRule>> {
+    state.db.get_field_mapping_rules(&server_config_id)
 }
 
 #[tauri::command]
-pub async fn delete_field_mapping_rule(state: State<'_, AppState>, id: String) -> Result<(), String> {
-    state.db.delete_field_mapping_rule(&id).map_err(|e| e.to_string())
+pub async fn delete_field_mapping_rule(state: State<'_, AppState>, id: String) -> Result<()> {
+    state.db.delete_field_mapping_rule(&id)
 }
 
 // ── Sample Data Commands ──
 
 #[tauri::command]
-pub async fn get_sample_data(state: State<'_, AppState>, server_config_id: String) -> Result<Vec<SampleData>, String> {
-    state.db.get_sample_data(&server_config_id).map_err(|e| e.to_string())
+pub async fn get_sample_data(state: State<'_, AppState>, server_config_id: String) -> Result<Vec<SampleData>> {
+    state.db.get_sample_data(&server_config_id)
 }
 
 #[tauri::command]
-pub async fn save_sample_data(state: State<'_, AppState>, item: SampleData) -> Result<SampleData, String> {
+pub async fn save_sample_data(state: State<'_, AppState>, item: SampleData) -> Result<SampleData> {
     let mut item = item;
     if item.id.is_empty() {
         item.id = Uuid::new_v4().to_string();
         item.created_at = Utc::now().to_rfc3339();
     }
     item.updated_at = Utc::now().to_rfc3339();
-    state.db.save_sample_data(&item).map_err(|e| e.to_string())?;
+    state.db.save_sample_data(&item)?;
     Ok(item)
 }
 
 #[tauri::command]
-pub async fn delete_sample_data(state: State<'_, AppState>, id: String) -> Result<(), String> {
-    state.db.delete_sample_data(&id).map_err(|e| e.to_string())
+pub async fn delete_sample_data(state: State<'_, AppState>, id: String) -> Result<()> {
+    state.db.delete_sample_data(&id)
 }
 
 #[tauri::command]
-pub async fn seed_sample_data(state: State<'_, AppState>, server_config_id: String) -> Result<(), String> {
-    state.db.seed_default_sample_data(&server_config_id).map_err(|e| e.to_string())
+pub async fn seed_sample_data(state: State<'_, AppState>, server_config_id: String) -> Result<()> {
+    state.db.seed_default_sample_data(&server_config_id)
 }
 
 // ── App Settings Commands ──
 
 #[tauri::command]
-pub async fn get_app_setting(state: State<'_, AppState>, key: String) -> Result<Option<String>, String> {
-    state.db.get_setting(&key).map_err(|e| e.to_string())
+pub async fn get_app_setting(state: State<'_, AppState>, key: String) -> Result<Option<String>> {
+    state.db.get_setting(&key)
+}
+
+#[tauri::command]
+pub async fn save_app_setting(state: State<'_, AppState>, key: String, value: String) -> Result<()> {
+    state.db.save_setting(&key, &value)
+}
+
+#[tauri::command]
+pub async fn delete_app_setting(state: State<'_, AppState>, key: String) -> Result<()> {
+    state.db.delete_setting(&key)
+}
+
+// ── Credential Encryption ──
+
+#[tauri::command]
+pub async fn get_credential_protection(state: State<'_, AppState>) -> Result<CredentialProtection> {
+    state.db.credential_protection()
 }
 
 #[tauri::command]
-pub async fn save_app_setting(state: State<'_, AppState>, key: String, value: String) -> Result<(), String> {
-    state.db.save_setting(&key, &value).map_err(|e| e.to_string())
+pub async fn unlock_credentials(state: State<'_, AppState>, passphrase: String) -> Result<()> {
+    state.db.unlock_credentials(&passphrase)
 }
 
+/// `None` removes the passphrase and goes back to a key file.
 #[tauri::command]
-pub async fn delete_app_setting(state: State<'_, AppState>, key: String) -> Result<(), String> {
-    state.db.delete_setting(&key).map_err(|e| e.to_string())
+pub async fn set_credential_passphrase(state: State<'_, AppState>, passphrase: Option<String>) -> Result<()> {
+    state.db.set_credential_passphrase(passphrase.as_deref())
 }
 
 // ── SCIM Explorer Commands ──
@@ -489,10 +581,10 @@ pub async fn delete_app_setting(state: State<'_, AppState>, key: String) -> Resu
 pub async fn execute_scim_request(
     state: State<'_, AppState>,
     request: ExplorerRequest,
-) -> Result<ExplorerResponse, String> {
+) -> Result<ExplorerResponse> {
     let config = state.db.get_server_config(&request.server_config_id)
-        .map_err(|e| e.to_string())?
-        .ok_or("Server config not found")?;
+        ?
+        .ok_or_else(|| AppError::NotFound("Server config not found".to_string()))?;
 
     let client = ScimClient::new(&config)?;
 
@@ -502,19 +594,26 @@ pub async fn execute_scim_request(
         "PUT" => reqwest::Method::PUT,
         "PATCH" => reqwest::Method::PATCH,
         "DELETE" => reqwest::Method::DELETE,
-        _ => return Err(format!("Unsupported HTTP method: {}", request.method)),
+        _ => return Err(AppError::Other(format!("Unsupported HTTP method: {}", request.method))),
     };
 
-    let mut path = request.path.clone();
-    if let Some(ref qp) = request.query_params {
-        if !qp.is_empty() {
-            if path.contains('?') {
-                path.push_str(&format!("&{}", qp));
-            } else {
-                path.push_str(&format!("?{}", qp));
-            }
-        }
-    }
+    // Query strings are typed by hand (`filter=userName eq "bjensen"`), so they
+    // are re-encoded here rather than sent with raw spaces and quotes.
+    let (base_path, path_query) = match request.path.split_once('?') {
+        Some((base, query)) => (base, Some(query)),
+        None => (request.path.as_str(), None),
+    };
+    let query = [path_query, request.query_params.as_deref()]
+        .into_iter()
+        .flatten()
+        .filter(|q| !q.is_empty())
+        .collect::<Vec<_>>()
+        .join("&");
+    let path = if query.is_empty() {
+        base_path.to_string()
+    } else {
+        format!("{}?{}", base_path, filter::encode_query_string(&query))
+    };
 
     let result = client.request_full(method, &path, request.body.as_deref()).await?;
 
@@ -524,20 +623,29 @@ pub async fn execute_scim_request(
         headers: result.headers,
         body: result.body,
         duration_ms: result.duration_ms,
+        timing: result.timing,
         request_url: result.request_url,
     })
 }
 
+/// Parse a SCIM filter so the Explorer can flag syntax errors before sending.
+/// Invalid filters can still be sent, e.g. to check the server's 400 response.
+#[tauri::command]
+pub fn validate_scim_filter(filter: String) -> Result<()> {
+    Filter::parse(&filter)?;
+    Ok(())
+}
+
 // ── AI Data Generation ──
 
 #[tauri::command]
 pub async fn generate_scim_data(
     state: State<'_, AppState>,
     operation: String,
-) -> Result<String, String> {
+) -> Result<String> {
     let api_key = state.db.get_setting("openai_api_key")
-        .map_err(|e| e.to_string())?
-        .ok_or("OpenAI API key not configured. Go to Settings to add it.")?;
+        ?
+        .ok_or_else(|| AppError::Config("OpenAI API key not configured. Go to Settings to add it.".to_string()))?;
 
     let system_prompt = "You are a SCIM 2.0 data generator. Return ONLY valid JSON, no markdown, no explanation. Generate realistic, diverse data each time. Use common real-world names, email addresses, and department names. Never use 'John Doe' or 'test@example.com'.";
 
@@ -547,7 +655,7 @@ pub async fn generate_scim_data(
         "change_user_name" => r#"Generate a SCIM 2.0 PatchOp JSON to change a user's name. Include schemas array with "urn:ietf:params:scim:api:messages:2.0:PatchOp", and Operations array with a single "replace" operation that updates name.givenName, name.familyName, and displayName to a new realistic name."#,
         "update_user" => r#"Generate a complete SCIM 2.0 User resource JSON for a full PUT update. Include schemas, userName (email), name with givenName/familyName/formatted, displayName, emails array, title, active: true, and phoneNumbers. Use realistic data for a different person than typical examples."#,
         "test" => r#"Return {"status":"ok","message":"OpenAI connection successful"}"#,
-        _ => return Err(format!("Unknown operation for AI generation: {}", operation)),
+        _ => return Err(AppError::Other(format!("Unknown operation for AI generation: {}", operation))),
     };
 
     let client = reqwest::Client::new();
@@ -567,21 +675,24 @@ pub async fn generate_scim_data(
         }))
         .send()
         .await
-        .map_err(|e| format!("OpenAI request failed: {}", e))?;
+        .map_err(|e| AppError::request("OpenAI request failed", &e))?;
 
     let status = resp.status().as_u16();
-    let body = resp.text().await.map_err(|e| format!("Failed to read OpenAI response: {}", e))?;
+    let body = resp.text().await.map_err(|e| AppError::request("Failed to read OpenAI response", &e))?;
 
+    if status == 401 {
+        return Err(AppError::Auth(format!("OpenAI rejected the API key: {}", body)));
+    }
     if status != 200 {
-        return Err(format!("OpenAI API error ({}): {}", status, body));
+        return Err(AppError::Other(format!("OpenAI API error ({}): {}", status, body)));
     }
 
     let parsed: serde_json::Value = serde_json::from_str(&body)
-        .map_err(|e| format!("Failed to parse OpenAI response: {}", e))?;
+        .map_err(|e| AppError::Other(format!("Failed to parse OpenAI response: {}", e)))?;
 
     let content = parsed["choices"][0]["message"]["content"]
         .as_str()
-        .ok_or("No content in OpenAI response")?
+        .ok_or_else(|| AppError::Other("No content in OpenAI response".to_string()))?
         .to_string();
 
     Ok(content)
@@ -0,0 +1,244 @@
+//! Response conformance against the server's own `/Schemas` (RFC 7643 §2, §7).
+//!
+//! Each attribute a server publishes comes with a type, a cardinality and the
+//! characteristics `required`, `mutability`, `returned`, `caseExact` and
+//! `canonicalValues`. Servers regularly contradict their own definitions:
+//! numbers sent as strings, a single object where an array is declared,
+//! `password` echoed back. The catalog walks returned resources through the
+//! full attribute tree, sub-attributes included, and reports every mismatch.
+
+use chrono::DateTime;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use crate::models::SchemaViolation;
+use crate::scim::{Schema, SchemaAttribute};
+
+/// Attributes common to every resource (RFC 7643 §3.1). A PUT body carries the
+/// resource's own `id`, so these are not held to `mutability`.
+const COMMON_ATTRIBUTES: [&str; 3] = ["id", "externalId", "meta"];
+
+/// The schemas a server publishes at `/Schemas`, by URN.
+pub struct SchemaCatalog {
+    schemas: HashMap<String, Schema>,
+}
+
+impl SchemaCatalog {
+    pub fn new(schemas: Vec<Schema>) -> Self {
+        let schemas = schemas.into_iter()
+            .filter(|s| !s.id.is_empty())
+            .map(|s| (s.id.to_ascii_lowercase(), s))
+            .collect();
+        SchemaCatalog { schemas }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.schemas.is_empty()
+    }
+
+    fn get(&self, urn: &str) -> Option<&Schema> {
+        self.schemas.get(&urn.to_ascii_lowercase())
+    }
+
+    /// Check a response body: a single resource, or each resource of a
+    /// ListResponse. `sent` is the request body when it was a resource (POST or
+    /// PUT), for the `mutability` and `caseExact` checks. `projected` means
+    /// `attributes` or `excludedAttributes` limited the response, so missing
+    /// required attributes are expected.
+    pub fn check_response(&self, body: &Value, sent: Option<&Value>, projected: bool) -> Vec<SchemaViolation> {
+        let mut violations = Vec::new();
+        let resources = body.as_object()
+            .and_then(|obj| get_attr(obj, "Resources"))
+            .and_then(Value::as_array);
+        match resources {
+            Some(resources) => {
+                for (i, resource) in resources.iter().enumerate() {
+                    self.check_resource(resource, None, projected, &format!("Resources[{}].", i), &mut violations);
+                }
+            }
+            None => self.check_resource(body, sent, projected, "", &mut violations),
+        }
+        violations
+    }
+
+    fn check_resource(&self, resource: &Value, sent: Option<&Value>, projected: bool, prefix: &str, out: &mut Vec<SchemaViolation>) {
+        let obj = match resource.as_object() {
+            Some(obj) => obj,
+            None => return,
+        };
+        let urns = schema_urns(obj);
+        // Only compare against a request body for the same kind of resource
+        let sent = sent.and_then(Value::as_object)
+            .filter(|s| schema_urns(s).iter().any(|u| urns.iter().any(|r| r.eq_ignore_ascii_case(u))));
+
+        // Extensions keep their attributes under their URN; the first schema
+        // without its own object is the core schema.
+        let core = urns.iter().find(|urn| get_attr(obj, urn).is_none());
+        for urn in &urns {
+            let schema = match self.get(urn) {
+                Some(schema) => schema,
+                None => continue,
+            };
+            if Some(urn) == core {
+                check_attributes(&schema.attributes, obj, sent, projected, prefix, out);
+                continue;
+            }
+            let path = format!("{}{}", prefix, urn);
+            match get_attr(obj, urn) {
+                Some(Value::Object(ext)) => {
+                    let sent_ext = sent.and_then(|s| get_attr(s, urn)).and_then(Value::as_object);
+                    check_attributes(&schema.attributes, ext, sent_ext, projected, &format!("{}:", path), out);
+                }
+                Some(other) => out.push(violation(&path, "type", format!("Expected the extension as an object, got {}", describe(other)))),
+                None => {}
+            }
+        }
+    }
+}
+
+fn check_attributes(
+    attrs: &[SchemaAttribute],
+    obj: &Map<String, Value>,
+    sent: Option<&Map<String, Value>>,
+    projected: bool,
+    prefix: &str,
+    out: &mut Vec<SchemaViolation>,
+) {
+    for attr in attrs {
+        if attr.name.is_empty() {
+            continue;
+        }
+        let path = format!("{}{}", prefix, attr.name);
+        let returned = attr.returned.as_deref().unwrap_or("default");
+        let write_only = attr.mutability.as_deref().is_some_and(|m| m.eq_ignore_ascii_case("writeOnly"));
+
+        let value = match get_attr(obj, &attr.name).filter(|v| !is_unassigned(v)) {
+            Some(value) => value,
+            None => {
+                let on_request = returned.eq_ignore_ascii_case("never") || returned.eq_ignore_ascii_case("request");
+                if attr.required && !projected && !on_request && !write_only {
+                    out.push(violation(&path, "required", "Required attribute is missing".to_string()));
+                }
+                continue;
+            }
+        };
+        if returned.eq_ignore_ascii_case("never") || write_only {
+            out.push(violation(&path, "returned", "Attribute is never to be returned, but the response contains it".to_string()));
+            continue;
+        }
+
+        match (attr.multi_valued, value) {
+            (true, Value::Array(items)) => {
+                for (i, item) in items.iter().enumerate() {
+                    check_value(attr, item, None, projected, &format!("{}[{}]", path, i), out);
+                }
+            }
+            (true, other) => {
+                out.push(violation(&path, "multiValued", format!("Multi-valued attribute returned as a single value: {}", describe(other))));
+            }
+            (false, Value::Array(_)) => {
+                out.push(violation(&path, "multiValued", "Single-valued attribute returned as an array".to_string()));
+            }
+            (false, value) => {
+                let sent_value = sent.and_then(|s| get_attr(s, &attr.name)).filter(|v| !is_unassigned(v));
+                if let Some(sent_value) = sent_value {
+                    check_sent(attr, value, sent_value, &path, out);
+                }
+                check_value(attr, value, sent_value, projected, &path, out);
+            }
+        }
+    }
+}
+
+/// Type, canonical values, and sub-attributes of complex values.
+fn check_value(
+    attr: &SchemaAttribute,
+    value: &Value,
+    sent: Option<&Value>,
+    projected: bool,
+    path: &str,
+    out: &mut Vec<SchemaViolation>,
+) {
+    if !type_matches(&attr.kind, value) {
+        out.push(violation(path, "type", format!("Expected {}, got {}", attr.kind, describe(value))));
+        return;
+    }
+    if let Value::Object(sub) = value {
+        let sent_sub = sent.and_then(Value::as_object);
+        check_attributes(&attr.sub_attributes, sub, sent_sub, projected, &format!("{}.", path), out);
+        return;
+    }
+    if let Value::String(s) = value {
+        let canonical: Vec<&str> = attr.canonical_values.iter().filter_map(Value::as_str).collect();
+        let listed = canonical.iter().any(|c| if attr.case_exact { c == s } else { c.eq_ignore_ascii_case(s) });
+        if !canonical.is_empty() && !listed {
+            out.push(violation(path, "canonicalValues", format!("\"{}\" is not one of the canonical values ({})", s, canonical.join(", "))));
+        }
+    }
+}
+
+/// What the server did with a value from our request.
+fn check_sent(attr: &SchemaAttribute, returned: &Value, sent: &Value, path: &str, out: &mut Vec<SchemaViolation>) {
+    let read_only = attr.mutability.as_deref().is_some_and(|m| m.eq_ignore_ascii_case("readOnly"));
+    let common = COMMON_ATTRIBUTES.iter().any(|c| c.eq_ignore_ascii_case(&attr.name));
+    if read_only && !common && returned == sent {
+        out.push(violation(path, "mutability", "Read-only value from the request was stored instead of ignored".to_string()));
+    }
+    if let (Value::String(r), Value::String(s)) = (returned, sent) {
+        if attr.case_exact && r != s && r.to_lowercase() == s.to_lowercase() {
+            out.push(violation(path, "caseExact", format!("Case-exact value was sent as \"{}\" but returned as \"{}\"", s, r)));
+        }
+    }
+}
+
+fn type_matches(kind: &str, value: &Value) -> bool {
+    match kind.to_ascii_lowercase().as_str() {
+        "string" | "reference" | "binary" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "decimal" => value.is_number(),
+        "datetime" => value.as_str().is_some_and(|s| DateTime::parse_from_rfc3339(s).is_ok()),
+        "complex" => value.is_object(),
+        _ => true,
+    }
+}
+
+/// Null and `[]` mean "unassigned" (RFC 7643 §2.5).
+fn is_unassigned(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Array(items) => items.is_empty(),
+        _ => false,
+    }
+}
+
+/// Attribute names are case-insensitive (RFC 7643 §2.1).
+fn get_attr<'v>(obj: &'v Map<String, Value>, name: &str) -> Option<&'v Value> {
+    obj.iter().find(|(k, _)| k.eq_ignore_ascii_case(name)).map(|(_, v)| v)
+}
+
+fn schema_urns(obj: &Map<String, Value>) -> Vec<String> {
+    get_attr(obj, "schemas")
+        .and_then(Value::as_array)
+        .map(|urns| urns.iter().filter_map(Value::as_str).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => format!("boolean {}", b),
+        Value::Number(n) => format!("number {}", n),
+        Value::String(s) => format!("string \"{}\"", s),
+        Value::Array(_) => "an array".to_string(),
+        Value::Object(_) => "an object".to_string(),
+    }
+}
+
+fn violation(attribute: &str, rule: &str, message: String) -> SchemaViolation {
+    SchemaViolation {
+        attribute: attribute.to_string(),
+        rule: rule.to_string(),
+        message,
+    }
+}
@@ -0,0 +1,89 @@
+use reqwest::{ClientBuilder, Proxy};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::time::Duration;
+
+use crate::models::ServerConfig;
+
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Timeouts, proxy and static headers for one server config, resolved once and
+/// applied to every HTTP client built for it, alongside its `TlsSettings`.
+#[derive(Clone)]
+pub struct ConnectionSettings {
+    connect_timeout: Duration,
+    read_timeout: Duration,
+    request_timeout: Duration,
+    proxy: Option<Proxy>,
+    headers: HeaderMap,
+}
+
+impl ConnectionSettings {
+    pub fn from_config(config: &ServerConfig) -> Result<Self, String> {
+        Ok(ConnectionSettings {
+            connect_timeout: timeout_or(config.connect_timeout_secs, DEFAULT_CONNECT_TIMEOUT),
+            read_timeout: timeout_or(config.read_timeout_secs, DEFAULT_READ_TIMEOUT),
+            request_timeout: timeout_or(config.request_timeout_secs, DEFAULT_REQUEST_TIMEOUT),
+            proxy: build_proxy(config)?,
+            headers: build_headers(config)?,
+        })
+    }
+
+    /// `read_timeout` limits how long the client waits for each read from the
+    /// server, so a stalled response fails fast; `request_timeout` caps the whole
+    /// request, so a server that trickles bytes cannot hold it open forever.
+    pub fn apply(&self, builder: ClientBuilder) -> ClientBuilder {
+        let builder = builder
+            .connect_timeout(self.connect_timeout)
+            .read_timeout(self.read_timeout)
+            .timeout(self.request_timeout);
+        match self.proxy {
+            Some(ref proxy) => builder.proxy(proxy.clone()),
+            None => builder,
+        }
+    }
+
+    /// Custom headers sent with every SCIM request.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+}
+
+/// `None` and 0 both mean "use the default".
+fn timeout_or(seconds: Option<u32>, default: Duration) -> Duration {
+    seconds.filter(|s| *s > 0)
+        .map(|s| Duration::from_secs(s as u64))
+        .unwrap_or(default)
+}
+
+/// The proxy handles both http and https URLs; credentials are sent as
+/// `Proxy-Authorization: Basic`.
+fn build_proxy(config: &ServerConfig) -> Result<Option<Proxy>, String> {
+    let url = config.proxy_url.as_deref().map(str::trim).unwrap_or("");
+    if url.is_empty() {
+        return Ok(None);
+    }
+    let mut proxy = Proxy::all(url).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+    let username = config.proxy_username.as_deref().map(str::trim).unwrap_or("");
+    if !username.is_empty() {
+        proxy = proxy.basic_auth(username, config.proxy_password.as_deref().unwrap_or(""));
+    }
+    Ok(Some(proxy))
+}
+
+fn build_headers(config: &ServerConfig) -> Result<HeaderMap, String> {
+    let mut headers = HeaderMap::new();
+    for h in config.custom_headers.iter().flatten() {
+        let name = h.name.trim();
+        if name.is_empty() {
+            continue;
+        }
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| format!("Invalid custom header name: {}", name))?;
+        let header_value = HeaderValue::from_str(h.value.trim())
+            .map_err(|_| format!("Invalid value for custom header {}", name))?;
+        headers.append(header_name, header_value);
+    }
+    Ok(headers)
+}
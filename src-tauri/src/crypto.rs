@@ -0,0 +1,198 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use std::num::NonZeroU32;
+use std::path::Path;
+
+/// Marks a stored value as ciphertext. Anything without it is a plaintext value
+/// written by an older version.
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+pub const KEY_LEN: usize = 32;
+pub const SALT_LEN: usize = 16;
+pub const PBKDF2_ITERATIONS: u32 = 600_000;
+
+/// AES-256-GCM key for the secret fields stored in the database. Each value gets
+/// a random nonce and is stored as `enc:v1:` + base64(nonce || ciphertext || tag).
+pub struct SecretKey(LessSafeKey);
+
+impl SecretKey {
+    pub fn from_bytes(bytes: &[u8; KEY_LEN]) -> Self {
+        let key = UnboundKey::new(&AES_256_GCM, bytes).expect("AES-256 key has the right length");
+        SecretKey(LessSafeKey::new(key))
+    }
+
+    /// Derive the key from a user passphrase with PBKDF2-HMAC-SHA256.
+    pub fn from_passphrase(passphrase: &str, salt: &[u8], iterations: u32) -> Result<Self, String> {
+        let iterations = NonZeroU32::new(iterations).ok_or("Invalid key derivation parameters")?;
+        let mut bytes = [0u8; KEY_LEN];
+        pbkdf2::derive(pbkdf2::PBKDF2_HMAC_SHA256, iterations, salt, passphrase.as_bytes(), &mut bytes);
+        Ok(Self::from_bytes(&bytes))
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> Result<String, String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        fill_random(&mut nonce)?;
+        let mut sealed = plaintext.as_bytes().to_vec();
+        self.0
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut sealed)
+            .map_err(|_| "Failed to encrypt value".to_string())?;
+
+        let mut out = nonce.to_vec();
+        out.extend_from_slice(&sealed);
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode(out)))
+    }
+
+    /// Plaintext values (not yet migrated) are returned unchanged.
+    pub fn decrypt(&self, stored: &str) -> Result<String, String> {
+        let Some(encoded) = stored.strip_prefix(ENCRYPTED_PREFIX) else {
+            return Ok(stored.to_string());
+        };
+        let mut data = BASE64.decode(encoded).map_err(|_| "Stored secret is corrupted".to_string())?;
+        if data.len() < NONCE_LEN {
+            return Err("Stored secret is corrupted".to_string());
+        }
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce.copy_from_slice(&data[..NONCE_LEN]);
+        let plaintext = self.0
+            .open_in_place(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut data[NONCE_LEN..])
+            .map_err(|_| "Stored secret could not be decrypted with the current key".to_string())?;
+        String::from_utf8(plaintext.to_vec()).map_err(|_| "Stored secret is corrupted".to_string())
+    }
+}
+
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_PREFIX)
+}
+
+pub fn random_bytes<const N: usize>() -> Result<[u8; N], String> {
+    let mut bytes = [0u8; N];
+    fill_random(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn fill_random(buf: &mut [u8]) -> Result<(), String> {
+    SystemRandom::new().fill(buf).map_err(|_| "System random number generator failed".to_string())
+}
+
+/// Read the key from `path`, creating a new random key there on first use. The
+/// file holds the base64-encoded key and is only readable by the current user
+/// where the platform supports it.
+pub fn load_or_create_key_file(path: &Path) -> Result<SecretKey, String> {
+    match std::fs::read_to_string(path) {
+        Ok(contents) => {
+            let bytes = BASE64.decode(contents.trim()).ok()
+                .and_then(|b| <[u8; KEY_LEN]>::try_from(b).ok())
+                .ok_or_else(|| format!("Key file {} is corrupted", path.display()))?;
+            Ok(SecretKey::from_bytes(&bytes))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let bytes = random_bytes::<KEY_LEN>()?;
+            write_key_file(path, &bytes)?;
+            Ok(SecretKey::from_bytes(&bytes))
+        }
+        Err(e) => Err(format!("Failed to read key file {}: {}", path.display(), e)),
+    }
+}
+
+pub fn write_key_file(path: &Path, bytes: &[u8; KEY_LEN]) -> Result<(), String> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+        .and_then(|mut file| file.write_all(BASE64.encode(bytes).as_bytes()))
+        .map_err(|e| format!("Failed to write key file {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SALT: [u8; SALT_LEN] = [7; SALT_LEN];
+    // Far below PBKDF2_ITERATIONS, to keep the tests fast
+    const ITERATIONS: u32 = 1_000;
+
+    fn key(passphrase: &str) -> SecretKey {
+        SecretKey::from_passphrase(passphrase, &SALT, ITERATIONS).unwrap()
+    }
+
+    /// `stored` with one byte of its decoded payload flipped.
+    fn tampered(stored: &str, index: usize) -> String {
+        let mut data = BASE64.decode(stored.strip_prefix(ENCRYPTED_PREFIX).unwrap()).unwrap();
+        data[index] ^= 0x01;
+        format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode(data))
+    }
+
+    #[test]
+    fn round_trips_with_a_fresh_nonce_each_time() {
+        let key = key("correct horse");
+        let first = key.encrypt("s3cret-token").unwrap();
+        let second = key.encrypt("s3cret-token").unwrap();
+        assert!(is_encrypted(&first));
+        assert_ne!(first, second);
+        assert_eq!(key.decrypt(&first).unwrap(), "s3cret-token");
+        assert_eq!(key.decrypt(&second).unwrap(), "s3cret-token");
+    }
+
+    #[test]
+    fn wrong_passphrase_fails_to_decrypt() {
+        let stored = key("correct horse").encrypt("s3cret-token").unwrap();
+        assert!(key("battery staple").decrypt(&stored).is_err());
+        let other_salt = SecretKey::from_passphrase("correct horse", &[8; SALT_LEN], ITERATIONS).unwrap();
+        assert!(other_salt.decrypt(&stored).is_err());
+    }
+
+    #[test]
+    fn tampered_ciphertext_is_rejected() {
+        let key = key("correct horse");
+        let stored = key.encrypt("s3cret-token").unwrap();
+        // Nonce, ciphertext and tag are all covered
+        for index in [0, NONCE_LEN, NONCE_LEN + "s3cret-token".len()] {
+            assert!(key.decrypt(&tampered(&stored, index)).is_err(), "byte {}", index);
+        }
+    }
+
+    #[test]
+    fn corrupted_values_are_rejected() {
+        let key = key("correct horse");
+        assert!(key.decrypt("enc:v1:not base64!").is_err());
+        assert!(key.decrypt(&format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode([0u8; NONCE_LEN - 1]))).is_err());
+        assert!(key.decrypt(&format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode([0u8; NONCE_LEN]))).is_err());
+    }
+
+    #[test]
+    fn plaintext_from_older_versions_passes_through() {
+        assert!(!is_encrypted("legacy-token"));
+        assert_eq!(key("correct horse").decrypt("legacy-token").unwrap(), "legacy-token");
+    }
+
+    #[test]
+    fn zero_iterations_are_refused() {
+        assert!(SecretKey::from_passphrase("correct horse", &SALT, 0).is_err());
+    }
+
+    #[test]
+    fn key_file_is_created_once_and_reused() {
+        let path = std::env::temp_dir().join(format!("scim-inspector-key-{}", uuid::Uuid::new_v4()));
+        let stored = load_or_create_key_file(&path).unwrap().encrypt("s3cret-token").unwrap();
+        let reloaded = load_or_create_key_file(&path).unwrap();
+        assert_eq!(reloaded.decrypt(&stored).unwrap(), "s3cret-token");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        std::fs::write(&path, "too short").unwrap();
+        assert!(load_or_create_key_file(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,10 +1,21 @@
-use rusqlite::{Connection, Result, params};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use std::sync::Mutex;
+use std::sync::{Mutex, RwLock};
 use chrono;
 
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+
+use super::crypto::{self, SecretKey};
+use super::error::{AppError, Result};
+
 pub struct Database {
     conn: Mutex<Connection>,
+    key_file: PathBuf,
+    // None while a passphrase is set and has not been entered yet. Always taken
+    // before `conn` when both are needed.
+    secret_key: RwLock<Option<SecretKey>>,
 }
 
 impl Database {
@@ -14,8 +25,11 @@ impl Database {
         let conn = Connection::open(db_path)?;
         let db = Database {
             conn: Mutex::new(conn),
+            key_file: app_dir.join("credentials.key"),
+            secret_key: RwLock::new(None),
         };
         db.run_migrations()?;
+        db.load_key_file()?;
         Ok(db)
     }
 
@@ -33,6 +47,26 @@ impl Database {
                 auth_password TEXT,
                 api_key_header TEXT,
                 api_key_value TEXT,
+                oauth_token_url TEXT,
+                oauth_client_id TEXT,
+                oauth_client_secret TEXT,
+                oauth_scope TEXT,
+                oauth_audience TEXT,
+                client_cert_format TEXT,
+                client_cert TEXT,
+                client_key TEXT,
+                client_cert_password TEXT,
+                tls_mode TEXT,
+                ca_bundle TEXT,
+                rate_limit_rps REAL,
+                rate_limit_burst INTEGER,
+                connect_timeout_secs INTEGER,
+                read_timeout_secs INTEGER,
+                request_timeout_secs INTEGER,
+                proxy_url TEXT,
+                proxy_username TEXT,
+                proxy_password TEXT,
+                custom_headers TEXT,
                 created_at TEXT NOT NULL,
                 updated_at TEXT NOT NULL
             );
@@ -45,6 +79,7 @@ impl Database {
                 started_at TEXT NOT NULL,
                 completed_at TEXT,
                 summary_json TEXT,
+                capabilities_json TEXT,
                 FOREIGN KEY (server_config_id) REFERENCES server_configs(id)
             );
 
@@ -62,6 +97,13 @@ impl Database {
                 passed INTEGER NOT NULL,
                 failure_reason TEXT,
                 executed_at TEXT NOT NULL,
+                timing_json TEXT,
+                schema_violations_json TEXT,
+                outcome TEXT,
+                depends_on_json TEXT,
+                requirement_level TEXT,
+                rfc_reference TEXT,
+                severity TEXT,
                 FOREIGN KEY (test_run_id) REFERENCES test_runs(id)
             );
 
@@ -77,10 +119,33 @@ impl Database {
                 success INTEGER NOT NULL,
                 error_message TEXT,
                 timestamp TEXT NOT NULL,
+                attempt INTEGER NOT NULL DEFAULT 1,
+                timing_json TEXT,
+                FOREIGN KEY (test_run_id) REFERENCES test_runs(id)
+            );
+
+            CREATE TABLE IF NOT EXISTS http_exchanges (
+                id TEXT PRIMARY KEY,
+                test_run_id TEXT NOT NULL,
+                sequence INTEGER NOT NULL,
+                started_at TEXT NOT NULL,
+                method TEXT NOT NULL,
+                url TEXT NOT NULL,
+                request_headers TEXT NOT NULL,
+                request_body TEXT,
+                response_status INTEGER,
+                response_status_text TEXT,
+                http_version TEXT,
+                response_headers TEXT NOT NULL,
+                response_body TEXT,
+                duration_ms INTEGER NOT NULL,
+                error TEXT,
+                timing_json TEXT,
                 FOREIGN KEY (test_run_id) REFERENCES test_runs(id)
             );
 
             CREATE INDEX IF NOT EXISTS idx_validation_results_run ON validation_results(test_run_id);
+            CREATE INDEX IF NOT EXISTS idx_http_exchanges_run ON http_exchanges(test_run_id);
             CREATE INDEX IF NOT EXISTS idx_load_test_results_run ON load_test_results(test_run_id);
             CREATE INDEX IF NOT EXISTS idx_test_runs_server ON test_runs(server_config_id);
 
@@ -121,21 +186,217 @@ impl Database {
             CREATE INDEX IF NOT EXISTS idx_sample_data_server ON sample_data(server_config_id);
             "
         )?;
+
+        // Columns added after the initial schema; databases created by older
+        // versions get them here.
+        let added_columns = [
+            ("server_configs", "oauth_token_url", "TEXT"),
+            ("server_configs", "oauth_client_id", "TEXT"),
+            ("server_configs", "oauth_client_secret", "TEXT"),
+            ("server_configs", "oauth_scope", "TEXT"),
+            ("server_configs", "oauth_audience", "TEXT"),
+            ("server_configs", "client_cert_format", "TEXT"),
+            ("server_configs", "client_cert", "TEXT"),
+            ("server_configs", "client_key", "TEXT"),
+            ("server_configs", "client_cert_password", "TEXT"),
+            // Servers saved before TLS policies existed always skipped verification
+            ("server_configs", "tls_mode", "TEXT DEFAULT 'insecure'"),
+            ("server_configs", "ca_bundle", "TEXT"),
+            ("server_configs", "rate_limit_rps", "REAL"),
+            ("server_configs", "rate_limit_burst", "INTEGER"),
+            ("server_configs", "connect_timeout_secs", "INTEGER"),
+            ("server_configs", "read_timeout_secs", "INTEGER"),
+            ("server_configs", "request_timeout_secs", "INTEGER"),
+            ("server_configs", "proxy_url", "TEXT"),
+            ("server_configs", "proxy_username", "TEXT"),
+            ("server_configs", "proxy_password", "TEXT"),
+            ("server_configs", "custom_headers", "TEXT"),
+            ("load_test_results", "attempt", "INTEGER NOT NULL DEFAULT 1"),
+            ("load_test_results", "timing_json", "TEXT"),
+            ("test_runs", "capabilities_json", "TEXT"),
+            ("validation_results", "timing_json", "TEXT"),
+            ("validation_results", "schema_violations_json", "TEXT"),
+            ("validation_results", "outcome", "TEXT"),
+            ("validation_results", "depends_on_json", "TEXT"),
+            ("validation_results", "requirement_level", "TEXT"),
+            ("validation_results", "rfc_reference", "TEXT"),
+            ("validation_results", "severity", "TEXT"),
+            ("http_exchanges", "timing_json", "TEXT"),
+        ];
+        for (table, column, definition) in added_columns {
+            Self::add_column_if_missing(&conn, table, column, definition)?;
+        }
+        Ok(())
+    }
+
+    fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+        let exists = stmt.query_map([], |row| row.get::<_, String>(1))?
+            .filter_map(|c| c.ok())
+            .any(|c| c == column);
+        if !exists {
+            conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+        }
+        Ok(())
+    }
+
+    // Credential Encryption
+
+    // Secret columns of server_configs, in the order of `secret_fields`.
+    const SECRET_COLUMNS: [&'static str; 8] = ["auth_token", "auth_password", "api_key_value", "oauth_client_secret", "client_cert", "client_key", "client_cert_password", "proxy_password"];
+    const SECRET_SETTINGS: [&'static str; 1] = ["openai_api_key"];
+    const PASSPHRASE_SETTING: &'static str = "credential_passphrase";
+
+    /// Without a passphrase the key comes from a key file next to the database.
+    /// Secrets saved in plain text by older versions are encrypted on the way.
+    fn load_key_file(&self) -> Result<()> {
+        let mut secret_key = self.secret_key.write().unwrap();
+        let mut conn = self.conn.lock().unwrap();
+        if Self::passphrase_params(&conn)?.is_some() {
+            return Ok(()); // locked until unlock_credentials
+        }
+        let key = crypto::load_or_create_key_file(&self.key_file).map_err(AppError::Encryption)?;
+        let tx = conn.transaction()?;
+        Self::reseal_secrets(&tx, None, &key)?;
+        tx.commit()?;
+        *secret_key = Some(key);
+        Ok(())
+    }
+
+    pub fn credential_protection(&self) -> Result<super::models::CredentialProtection> {
+        let secret_key = self.secret_key.read().unwrap();
+        let conn = self.conn.lock().unwrap();
+        let mode = match Self::passphrase_params(&conn)? {
+            Some(_) => "passphrase",
+            None => "key_file",
+        };
+        Ok(super::models::CredentialProtection {
+            mode: mode.to_string(),
+            locked: secret_key.is_none(),
+        })
+    }
+
+    pub fn unlock_credentials(&self, passphrase: &str) -> Result<()> {
+        let mut secret_key = self.secret_key.write().unwrap();
+        let mut conn = self.conn.lock().unwrap();
+        let params = Self::passphrase_params(&conn)?
+            .ok_or_else(|| AppError::Encryption("No passphrase is set".to_string()))?;
+        let key = params.unlock(passphrase).map_err(AppError::Encryption)?;
+        let tx = conn.transaction()?;
+        Self::reseal_secrets(&tx, None, &key)?;
+        tx.commit()?;
+        *secret_key = Some(key);
+        Ok(())
+    }
+
+    /// Protect the secrets with a passphrase (set or change it), or with `None`
+    /// go back to a key file. Everything stored is re-encrypted with the new key.
+    pub fn set_credential_passphrase(&self, passphrase: Option<&str>) -> Result<()> {
+        let mut secret_key = self.secret_key.write().unwrap();
+        let current = secret_key.as_ref().ok_or(AppError::CredentialsLocked)?;
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        let new_key = match passphrase {
+            Some(passphrase) => {
+                if passphrase.is_empty() {
+                    return Err(AppError::Encryption("Passphrase must not be empty".to_string()));
+                }
+                let (key, params) = PassphraseParams::create(passphrase).map_err(AppError::Encryption)?;
+                tx.execute(
+                    "INSERT OR REPLACE INTO app_settings (key, value, updated_at) VALUES (?1, ?2, ?3)",
+                    params![Self::PASSPHRASE_SETTING, serde_json::to_string(&params).unwrap_or_default(), chrono::Utc::now().to_rfc3339()],
+                )?;
+                key
+            }
+            None => {
+                if Self::passphrase_params(&tx)?.is_none() {
+                    return Err(AppError::Encryption("No passphrase is set".to_string()));
+                }
+                // Nothing reads the key file while a passphrase is set, so writing
+                // it before the commit is safe.
+                let bytes = crypto::random_bytes::<{ crypto::KEY_LEN }>().map_err(AppError::Encryption)?;
+                crypto::write_key_file(&self.key_file, &bytes).map_err(AppError::Encryption)?;
+                tx.execute("DELETE FROM app_settings WHERE key = ?1", params![Self::PASSPHRASE_SETTING])?;
+                SecretKey::from_bytes(&bytes)
+            }
+        };
+        Self::reseal_secrets(&tx, Some(current), &new_key)?;
+        tx.commit()?;
+
+        if passphrase.is_some() {
+            let _ = std::fs::remove_file(&self.key_file);
+        }
+        *secret_key = Some(new_key);
+        Ok(())
+    }
+
+    fn passphrase_params(conn: &Connection) -> Result<Option<PassphraseParams>> {
+        let mut stmt = conn.prepare("SELECT value FROM app_settings WHERE key = ?1")?;
+        let mut rows = stmt.query_map(params![Self::PASSPHRASE_SETTING], |row| row.get::<_, String>(0))?;
+        match rows.next() {
+            Some(json) => serde_json::from_str(&json?)
+                .map(Some)
+                .map_err(|e| AppError::Encryption(format!("Invalid passphrase settings: {}", e))),
+            None => Ok(None),
+        }
+    }
+
+    /// Encrypt every stored secret with `to`. Values are first decrypted with
+    /// `from`; without it, only values still in plain text are touched.
+    fn reseal_secrets(conn: &Connection, from: Option<&SecretKey>, to: &SecretKey) -> Result<()> {
+        let reseal = |value: &str| -> std::result::Result<Option<String>, String> {
+            match from {
+                Some(from) => Ok(Some(to.encrypt(&from.decrypt(value)?)?)),
+                None if crypto::is_encrypted(value) => Ok(None),
+                None => Ok(Some(to.encrypt(value)?)),
+            }
+        };
+
+        for column in Self::SECRET_COLUMNS {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT id, {0} FROM server_configs WHERE {0} IS NOT NULL AND {0} != ''", column
+            ))?;
+            let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            for (id, value) in rows {
+                if let Some(sealed) = reseal(&value).map_err(AppError::Encryption)? {
+                    conn.execute(&format!("UPDATE server_configs SET {} = ?1 WHERE id = ?2", column), params![sealed, id])?;
+                }
+            }
+        }
+        for key in Self::SECRET_SETTINGS {
+            let value: Option<String> = conn.query_row(
+                "SELECT value FROM app_settings WHERE key = ?1", params![key], |row| row.get(0),
+            ).optional()?;
+            if let Some(value) = value.filter(|v| !v.is_empty()) {
+                if let Some(sealed) = reseal(&value).map_err(AppError::Encryption)? {
+                    conn.execute("UPDATE app_settings SET value = ?1 WHERE key = ?2", params![sealed, key])?;
+                }
+            }
+        }
         Ok(())
     }
 
     // App Settings
     pub fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        let secret_key = self.secret_key.read().unwrap();
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare("SELECT value FROM app_settings WHERE key = ?1")?;
         let mut rows = stmt.query_map(params![key], |row| row.get::<_, String>(0))?;
         match rows.next() {
-            Some(val) => Ok(Some(val?)),
+            Some(val) => Ok(reveal(secret_key.as_ref(), Some(val?))?),
             None => Ok(None),
         }
     }
 
     pub fn save_setting(&self, key: &str, value: &str) -> Result<()> {
+        let secret_key = self.secret_key.read().unwrap();
+        let value = if Self::SECRET_SETTINGS.contains(&key) {
+            seal(secret_key.as_ref(), value)?
+        } else {
+            value.to_string()
+        };
         let conn = self.conn.lock().unwrap();
         conn.execute(
             "INSERT OR REPLACE INTO app_settings (key, value, updated_at) VALUES (?1, ?2, ?3)",
@@ -151,10 +412,59 @@ impl Database {
     }
 
     // Server Config CRUD
+    const SERVER_CONFIG_COLUMNS: &'static str = "id, name, base_url, auth_type, auth_token, auth_username, auth_password, api_key_header, api_key_value, oauth_token_url, oauth_client_id, oauth_client_secret, oauth_scope, oauth_audience, client_cert_format, client_cert, client_key, client_cert_password, tls_mode, ca_bundle, rate_limit_rps, rate_limit_burst, connect_timeout_secs, read_timeout_secs, request_timeout_secs, proxy_url, proxy_username, proxy_password, custom_headers, created_at, updated_at";
+
+    fn server_config_from_row(row: &rusqlite::Row) -> rusqlite::Result<super::models::ServerConfig> {
+        Ok(super::models::ServerConfig {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            base_url: row.get(2)?,
+            auth_type: row.get(3)?,
+            auth_token: row.get(4)?,
+            auth_username: row.get(5)?,
+            auth_password: row.get(6)?,
+            api_key_header: row.get(7)?,
+            api_key_value: row.get(8)?,
+            oauth_token_url: row.get(9)?,
+            oauth_client_id: row.get(10)?,
+            oauth_client_secret: row.get(11)?,
+            oauth_scope: row.get(12)?,
+            oauth_audience: row.get(13)?,
+            client_cert_format: row.get(14)?,
+            client_cert: row.get(15)?,
+            client_key: row.get(16)?,
+            client_cert_password: row.get(17)?,
+            tls_mode: row.get(18)?,
+            ca_bundle: row.get(19)?,
+            rate_limit_rps: row.get(20)?,
+            rate_limit_burst: row.get(21)?,
+            connect_timeout_secs: row.get(22)?,
+            read_timeout_secs: row.get(23)?,
+            request_timeout_secs: row.get(24)?,
+            proxy_url: row.get(25)?,
+            proxy_username: row.get(26)?,
+            proxy_password: row.get(27)?,
+            custom_headers: row.get::<_, Option<String>>(28)?
+                .and_then(|json| serde_json::from_str(&json).ok()),
+            created_at: row.get(29)?,
+            updated_at: row.get(30)?,
+        })
+    }
+
     pub fn save_server_config(&self, config: &super::models::ServerConfig) -> Result<()> {
+        let secret_key = self.secret_key.read().unwrap();
+        let mut config = config.clone();
+        for field in secret_fields(&mut config) {
+            if let Some(value) = field.as_deref().filter(|v| !v.is_empty()) {
+                *field = Some(seal(secret_key.as_ref(), value)?);
+            }
+        }
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "INSERT OR REPLACE INTO server_configs (id, name, base_url, auth_type, auth_token, auth_username, auth_password, api_key_header, api_key_value, created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            &format!(
+                "INSERT OR REPLACE INTO server_configs ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31)",
+                Self::SERVER_CONFIG_COLUMNS
+            ),
             params![
                 config.id,
                 config.name,
@@ -165,6 +475,26 @@ impl Database {
                 config.auth_password,
                 config.api_key_header,
                 config.api_key_value,
+                config.oauth_token_url,
+                config.oauth_client_id,
+                config.oauth_client_secret,
+                config.oauth_scope,
+                config.oauth_audience,
+                config.client_cert_format,
+                config.client_cert,
+                config.client_key,
+                config.client_cert_password,
+                config.tls_mode,
+                config.ca_bundle,
+                config.rate_limit_rps,
+                config.rate_limit_burst,
+                config.connect_timeout_secs,
+                config.read_timeout_secs,
+                config.request_timeout_secs,
+                config.proxy_url,
+                config.proxy_username,
+                config.proxy_password,
+                config.custom_headers.as_ref().and_then(|h| serde_json::to_string(h).ok()),
                 config.created_at,
                 config.updated_at,
             ],
@@ -173,50 +503,28 @@ impl Database {
     }
 
     pub fn get_server_configs(&self) -> Result<Vec<super::models::ServerConfig>> {
+        let secret_key = self.secret_key.read().unwrap();
         let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT id, name, base_url, auth_type, auth_token, auth_username, auth_password, api_key_header, api_key_value, created_at, updated_at FROM server_configs ORDER BY updated_at DESC"
-        )?;
-        let configs = stmt.query_map([], |row| {
-            Ok(super::models::ServerConfig {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                base_url: row.get(2)?,
-                auth_type: row.get(3)?,
-                auth_token: row.get(4)?,
-                auth_username: row.get(5)?,
-                auth_password: row.get(6)?,
-                api_key_header: row.get(7)?,
-                api_key_value: row.get(8)?,
-                created_at: row.get(9)?,
-                updated_at: row.get(10)?,
-            })
-        })?.collect::<Result<Vec<_>>>()?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM server_configs ORDER BY updated_at DESC",
+            Self::SERVER_CONFIG_COLUMNS
+        ))?;
+        let configs = stmt.query_map([], Self::server_config_from_row)?
+            .map(|config| reveal_secrets(secret_key.as_ref(), config?))
+            .collect::<Result<Vec<_>>>()?;
         Ok(configs)
     }
 
     pub fn get_server_config(&self, id: &str) -> Result<Option<super::models::ServerConfig>> {
+        let secret_key = self.secret_key.read().unwrap();
         let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
-            "SELECT id, name, base_url, auth_type, auth_token, auth_username, auth_password, api_key_header, api_key_value, created_at, updated_at FROM server_configs WHERE id = ?1"
-        )?;
-        let mut rows = stmt.query_map(params![id], |row| {
-            Ok(super::models::ServerConfig {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                base_url: row.get(2)?,
-                auth_type: row.get(3)?,
-                auth_token: row.get(4)?,
-                auth_username: row.get(5)?,
-                auth_password: row.get(6)?,
-                api_key_header: row.get(7)?,
-                api_key_value: row.get(8)?,
-                created_at: row.get(9)?,
-                updated_at: row.get(10)?,
-            })
-        })?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM server_configs WHERE id = ?1",
+            Self::SERVER_CONFIG_COLUMNS
+        ))?;
+        let mut rows = stmt.query_map(params![id], Self::server_config_from_row)?;
         match rows.next() {
-            Some(row) => Ok(Some(row?)),
+            Some(row) => Ok(Some(reveal_secrets(secret_key.as_ref(), row?)?)),
             None => Ok(None),
         }
     }
@@ -231,7 +539,7 @@ impl Database {
     pub fn save_test_run(&self, run: &super::models::TestRun) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "INSERT OR REPLACE INTO test_runs (id, server_config_id, run_type, status, started_at, completed_at, summary_json) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT OR REPLACE INTO test_runs (id, server_config_id, run_type, status, started_at, completed_at, summary_json, capabilities_json) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
             params![
                 run.id,
                 run.server_config_id,
@@ -240,6 +548,7 @@ impl Database {
                 run.started_at,
                 run.completed_at,
                 run.summary_json,
+                run.capabilities_json,
             ],
         )?;
         Ok(())
@@ -247,7 +556,7 @@ impl Database {
 
     pub fn get_test_runs(&self, server_config_id: Option<&str>, run_type: Option<&str>) -> Result<Vec<super::models::TestRun>> {
         let conn = self.conn.lock().unwrap();
-        let mut query = String::from("SELECT id, server_config_id, run_type, status, started_at, completed_at, summary_json FROM test_runs WHERE 1=1");
+        let mut query = String::from("SELECT id, server_config_id, run_type, status, started_at, completed_at, summary_json, capabilities_json FROM test_runs WHERE 1=1");
         let mut param_values: Vec<String> = Vec::new();
         
         if let Some(sid) = server_config_id {
@@ -271,15 +580,16 @@ impl Database {
                 started_at: row.get(4)?,
                 completed_at: row.get(5)?,
                 summary_json: row.get(6)?,
+                capabilities_json: row.get(7)?,
             })
-        })?.collect::<Result<Vec<_>>>()?;
+        })?.collect::<rusqlite::Result<Vec<_>>>()?;
         Ok(runs)
     }
 
     pub fn get_test_run(&self, id: &str) -> Result<Option<super::models::TestRun>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, server_config_id, run_type, status, started_at, completed_at, summary_json FROM test_runs WHERE id = ?1"
+            "SELECT id, server_config_id, run_type, status, started_at, completed_at, summary_json, capabilities_json FROM test_runs WHERE id = ?1"
         )?;
         let mut rows = stmt.query_map(params![id], |row| {
             Ok(super::models::TestRun {
@@ -290,6 +600,7 @@ impl Database {
                 started_at: row.get(4)?,
                 completed_at: row.get(5)?,
                 summary_json: row.get(6)?,
+                capabilities_json: row.get(7)?,
             })
         })?;
         match rows.next() {
@@ -300,6 +611,7 @@ impl Database {
 
     pub fn delete_test_run(&self, id: &str) -> Result<()> {
         let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM http_exchanges WHERE test_run_id = ?1", params![id])?;
         conn.execute("DELETE FROM load_test_results WHERE test_run_id = ?1", params![id])?;
         conn.execute("DELETE FROM validation_results WHERE test_run_id = ?1", params![id])?;
         conn.execute("DELETE FROM test_runs WHERE id = ?1", params![id])?;
@@ -310,7 +622,7 @@ impl Database {
     pub fn save_validation_result(&self, result: &super::models::ValidationResult) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "INSERT INTO validation_results (id, test_run_id, test_name, category, http_method, url, request_body, response_status, response_body, duration_ms, passed, failure_reason, executed_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+            "INSERT INTO validation_results (id, test_run_id, test_name, category, http_method, url, request_body, response_status, response_body, duration_ms, passed, failure_reason, executed_at, timing_json, schema_violations_json, outcome, depends_on_json, requirement_level, rfc_reference, severity) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)",
             params![
                 result.id,
                 result.test_run_id,
@@ -325,6 +637,13 @@ impl Database {
                 result.passed,
                 result.failure_reason,
                 result.executed_at,
+                timing_json(&result.timing),
+                schema_violations_json(&result.schema_violations),
+                result.outcome.as_str(),
+                depends_on_json(&result.depends_on),
+                result.requirement_level.as_str(),
+                result.rfc_reference,
+                result.severity.as_str(),
             ],
         )?;
         Ok(())
@@ -333,9 +652,28 @@ impl Database {
     pub fn get_validation_results(&self, test_run_id: &str) -> Result<Vec<super::models::ValidationResult>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, test_run_id, test_name, category, http_method, url, request_body, response_status, response_body, duration_ms, passed, failure_reason, executed_at FROM validation_results WHERE test_run_id = ?1 ORDER BY executed_at ASC"
+            "SELECT id, test_run_id, test_name, category, http_method, url, request_body, response_status, response_body, duration_ms, passed, failure_reason, executed_at, timing_json, schema_violations_json, outcome, depends_on_json, requirement_level, rfc_reference, severity FROM validation_results WHERE test_run_id = ?1 ORDER BY executed_at ASC"
         )?;
         let results = stmt.query_map(params![test_run_id], |row| {
+            let passed: bool = row.get(10)?;
+            let failure_reason: Option<String> = row.get(11)?;
+            // Rows saved before outcomes were stored carry only pass/fail
+            let outcome = row.get::<_, Option<String>>(15)?
+                .and_then(|o| super::models::TestOutcome::parse(&o))
+                .unwrap_or_else(|| passed.into());
+            let saved_level = row.get::<_, Option<String>>(17)?
+                .and_then(|l| super::models::RequirementLevel::parse(&l));
+            let (requirement_level, rfc_reference, severity) = match saved_level {
+                Some(level) => (
+                    level,
+                    row.get(18)?,
+                    row.get::<_, Option<String>>(19)?
+                        .and_then(|s| super::models::Severity::parse(&s))
+                        .unwrap_or_default(),
+                ),
+                // Rows saved before requirements were stored count as a MUST of medium severity
+                None => Default::default(),
+            };
             Ok(super::models::ValidationResult {
                 id: row.get(0)?,
                 test_run_id: row.get(1)?,
@@ -347,11 +685,20 @@ impl Database {
                 response_status: row.get(7)?,
                 response_body: row.get(8)?,
                 duration_ms: row.get(9)?,
-                passed: row.get(10)?,
-                failure_reason: row.get(11)?,
+                timing: parse_timing(row.get(13)?),
+                passed,
+                outcome,
+                failure_reason,
                 executed_at: row.get(12)?,
+                schema_violations: parse_schema_violations(row.get(14)?),
+                depends_on: row.get::<_, Option<String>>(16)?
+                    .and_then(|j| serde_json::from_str(&j).ok())
+                    .unwrap_or_default(),
+                requirement_level,
+                rfc_reference,
+                severity,
             })
-        })?.collect::<Result<Vec<_>>>()?;
+        })?.collect::<rusqlite::Result<Vec<_>>>()?;
         Ok(results)
     }
 
@@ -361,7 +708,7 @@ impl Database {
         let tx = conn.unchecked_transaction()?;
         {
             let mut stmt = tx.prepare(
-                "INSERT INTO load_test_results (id, test_run_id, request_index, http_method, url, request_body, status_code, duration_ms, success, error_message, timestamp) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)"
+                "INSERT INTO load_test_results (id, test_run_id, request_index, http_method, url, request_body, status_code, duration_ms, success, error_message, timestamp, attempt, timing_json) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)"
             )?;
             for r in results {
                 stmt.execute(params![
@@ -376,6 +723,8 @@ impl Database {
                     r.success,
                     r.error_message,
                     r.timestamp,
+                    r.attempt,
+                    timing_json(&r.timing),
                 ])?;
             }
         }
@@ -386,7 +735,7 @@ impl Database {
     pub fn get_load_test_results(&self, test_run_id: &str) -> Result<Vec<super::models::LoadTestResult>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, test_run_id, request_index, http_method, url, request_body, status_code, duration_ms, success, error_message, timestamp FROM load_test_results WHERE test_run_id = ?1 ORDER BY request_index ASC"
+            "SELECT id, test_run_id, request_index, http_method, url, request_body, status_code, duration_ms, success, error_message, timestamp, attempt, timing_json FROM load_test_results WHERE test_run_id = ?1 ORDER BY request_index ASC, attempt ASC"
         )?;
         let results = stmt.query_map(params![test_run_id], |row| {
             Ok(super::models::LoadTestResult {
@@ -401,15 +750,78 @@ impl Database {
                 success: row.get(8)?,
                 error_message: row.get(9)?,
                 timestamp: row.get(10)?,
+                attempt: row.get(11)?,
+                timing: parse_timing(row.get(12)?),
             })
-        })?.collect::<Result<Vec<_>>>()?;
+        })?.collect::<rusqlite::Result<Vec<_>>>()?;
         Ok(results)
     }
 
+    // HTTP Exchanges
+    pub fn save_http_exchanges(&self, exchanges: &[super::models::HttpExchange]) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO http_exchanges (id, test_run_id, sequence, started_at, method, url, request_headers, request_body, response_status, response_status_text, http_version, response_headers, response_body, duration_ms, error, timing_json) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)"
+            )?;
+            for e in exchanges {
+                stmt.execute(params![
+                    e.id,
+                    e.test_run_id,
+                    e.sequence,
+                    e.started_at,
+                    e.method,
+                    e.url,
+                    serde_json::to_string(&e.request_headers).unwrap_or_default(),
+                    e.request_body,
+                    e.response_status,
+                    e.response_status_text,
+                    e.http_version,
+                    serde_json::to_string(&e.response_headers).unwrap_or_default(),
+                    e.response_body,
+                    e.duration_ms,
+                    e.error,
+                    timing_json(&e.timing),
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_http_exchanges(&self, test_run_id: &str) -> Result<Vec<super::models::HttpExchange>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, test_run_id, sequence, started_at, method, url, request_headers, request_body, response_status, response_status_text, http_version, response_headers, response_body, duration_ms, error, timing_json FROM http_exchanges WHERE test_run_id = ?1 ORDER BY sequence ASC"
+        )?;
+        let exchanges = stmt.query_map(params![test_run_id], |row| {
+            Ok(super::models::HttpExchange {
+                id: row.get(0)?,
+                test_run_id: row.get(1)?,
+                sequence: row.get(2)?,
+                started_at: row.get(3)?,
+                method: row.get(4)?,
+                url: row.get(5)?,
+                request_headers: serde_json::from_str(&row.get::<_, String>(6)?).unwrap_or_default(),
+                request_body: row.get(7)?,
+                response_status: row.get(8)?,
+                response_status_text: row.get(9)?,
+                http_version: row.get(10)?,
+                response_headers: serde_json::from_str(&row.get::<_, String>(11)?).unwrap_or_default(),
+                response_body: row.get(12)?,
+                duration_ms: row.get(13)?,
+                error: row.get(14)?,
+                timing: parse_timing(row.get(15)?),
+            })
+        })?.collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(exchanges)
+    }
+
     pub fn clear_all_data(&self) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute_batch(
-            "DELETE FROM load_test_results; DELETE FROM validation_results; DELETE FROM test_runs; DELETE FROM field_mapping_rules; DELETE FROM server_configs;"
+            "DELETE FROM http_exchanges; DELETE FROM load_test_results; DELETE FROM validation_results; DELETE FROM test_runs; DELETE FROM field_mapping_rules; DELETE FROM server_configs;"
         )?;
         Ok(())
     }
@@ -453,7 +865,7 @@ impl Database {
                 created_at: row.get(8)?,
                 updated_at: row.get(9)?,
             })
-        })?.collect::<Result<Vec<_>>>()?;
+        })?.collect::<rusqlite::Result<Vec<_>>>()?;
         Ok(rules)
     }
 
@@ -504,7 +916,7 @@ impl Database {
                 created_at: row.get(6)?,
                 updated_at: row.get(7)?,
             })
-        })?.collect::<Result<Vec<_>>>()?;
+        })?.collect::<rusqlite::Result<Vec<_>>>()?;
         Ok(items)
     }
 
@@ -590,3 +1002,100 @@ impl Database {
         Ok(())
     }
 }
+
+fn timing_json(timing: &Option<super::models::RequestTiming>) -> Option<String> {
+    timing.as_ref().and_then(|t| serde_json::to_string(t).ok())
+}
+
+fn parse_timing(json: Option<String>) -> Option<super::models::RequestTiming> {
+    json.and_then(|j| serde_json::from_str(&j).ok())
+}
+
+fn schema_violations_json(violations: &[super::models::SchemaViolation]) -> Option<String> {
+    if violations.is_empty() {
+        return None;
+    }
+    serde_json::to_string(violations).ok()
+}
+
+fn parse_schema_violations(json: Option<String>) -> Vec<super::models::SchemaViolation> {
+    json.and_then(|j| serde_json::from_str(&j).ok()).unwrap_or_default()
+}
+
+fn depends_on_json(depends_on: &[String]) -> Option<String> {
+    if depends_on.is_empty() {
+        return None;
+    }
+    serde_json::to_string(depends_on).ok()
+}
+
+// ── Credential Encryption ──
+
+// Same order as `Database::SECRET_COLUMNS`.
+fn secret_fields(config: &mut super::models::ServerConfig) -> [&mut Option<String>; 8] {
+    [
+        &mut config.auth_token,
+        &mut config.auth_password,
+        &mut config.api_key_value,
+        &mut config.oauth_client_secret,
+        &mut config.client_cert,
+        &mut config.client_key,
+        &mut config.client_cert_password,
+        &mut config.proxy_password,
+    ]
+}
+
+fn reveal_secrets(key: Option<&SecretKey>, mut config: super::models::ServerConfig) -> Result<super::models::ServerConfig> {
+    for field in secret_fields(&mut config) {
+        *field = reveal(key, field.take())?;
+    }
+    Ok(config)
+}
+
+fn seal(key: Option<&SecretKey>, value: &str) -> Result<String> {
+    key.ok_or(AppError::CredentialsLocked)?.encrypt(value).map_err(AppError::Encryption)
+}
+
+/// Plaintext values (written before encryption existed) come back unchanged.
+fn reveal(key: Option<&SecretKey>, value: Option<String>) -> Result<Option<String>> {
+    match value {
+        Some(v) if crypto::is_encrypted(&v) => {
+            key.ok_or(AppError::CredentialsLocked)?.decrypt(&v).map(Some).map_err(AppError::Encryption)
+        }
+        other => Ok(other),
+    }
+}
+
+/// Stored in app_settings when the secrets are protected by a passphrase. `check`
+/// is a known value encrypted with the derived key, to tell a wrong passphrase
+/// apart from a corrupted database.
+#[derive(Serialize, Deserialize)]
+struct PassphraseParams {
+    salt: String,
+    iterations: u32,
+    check: String,
+}
+
+const PASSPHRASE_CHECK: &str = "scim-inspector";
+
+impl PassphraseParams {
+    fn create(passphrase: &str) -> std::result::Result<(SecretKey, Self), String> {
+        let salt = crypto::random_bytes::<{ crypto::SALT_LEN }>()?;
+        let key = SecretKey::from_passphrase(passphrase, &salt, crypto::PBKDF2_ITERATIONS)?;
+        let params = PassphraseParams {
+            salt: BASE64.encode(salt),
+            iterations: crypto::PBKDF2_ITERATIONS,
+            check: key.encrypt(PASSPHRASE_CHECK)?,
+        };
+        Ok((key, params))
+    }
+
+    fn unlock(&self, passphrase: &str) -> std::result::Result<SecretKey, String> {
+        let salt = BASE64.decode(&self.salt).map_err(|_| "Invalid passphrase settings".to_string())?;
+        let key = SecretKey::from_passphrase(passphrase, &salt, self.iterations)?;
+        match key.decrypt(&self.check) {
+            Ok(check) if check == PASSPHRASE_CHECK => Ok(key),
+            _ => Err("Incorrect passphrase".to_string()),
+        }
+    }
+}
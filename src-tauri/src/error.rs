@@ -0,0 +1,128 @@
+use serde::{Serialize, Serializer};
+
+use crate::filter::FilterError;
+use crate::scim::ScimError;
+
+pub type Result<T> = std::result::Result<T, AppError>;
+
+/// Errors raised by the backend. Commands return them to the frontend as
+/// `{ code, message }`, plus `status`, `scim_type` and `detail` for SCIM errors.
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    /// The request could not be sent or the response could not be read.
+    #[error("{0}")]
+    Transport(String),
+    #[error("{0}")]
+    Timeout(String),
+    /// TLS handshake failures and invalid certificates or keys.
+    #[error("{0}")]
+    Tls(String),
+    /// The credentials were rejected, or no token could be obtained.
+    #[error("{0}")]
+    Auth(String),
+    /// The server answered with a SCIM error response.
+    #[error("{0}")]
+    Scim(ScimError),
+    #[error("{0}")]
+    Database(#[from] rusqlite::Error),
+    #[error("{0}")]
+    InvalidFilter(#[from] FilterError),
+    #[error("Saved credentials are locked. Enter your passphrase in Settings to unlock them.")]
+    CredentialsLocked,
+    #[error("{0}")]
+    Encryption(String),
+    /// Settings that cannot be used as entered (proxy URL, headers, OAuth fields).
+    #[error("{0}")]
+    Config(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    Export(String),
+    #[error("{0}")]
+    Other(String),
+}
+
+impl AppError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Transport(_) => "transport",
+            AppError::Timeout(_) => "timeout",
+            AppError::Tls(_) => "tls",
+            AppError::Auth(_) => "auth",
+            AppError::Scim(_) => "scim",
+            AppError::Database(_) => "database",
+            AppError::InvalidFilter(_) => "invalid_filter",
+            AppError::CredentialsLocked => "credentials_locked",
+            AppError::Encryption(_) => "encryption",
+            AppError::Config(_) => "config",
+            AppError::NotFound(_) => "not_found",
+            AppError::Export(_) => "export",
+            AppError::Other(_) => "other",
+        }
+    }
+
+    /// Classify a reqwest error, prefixing its message with `context`.
+    pub fn request(context: &str, e: &reqwest::Error) -> Self {
+        let message = format!("{}: {}", context, describe_error(e));
+        if e.is_timeout() {
+            AppError::Timeout(message)
+        } else if is_tls_error(e) {
+            AppError::Tls(message)
+        } else {
+            AppError::Transport(message)
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Payload<'a> {
+            code: &'static str,
+            message: String,
+            #[serde(flatten)]
+            scim: Option<&'a ScimError>,
+        }
+        let scim = match self {
+            AppError::Scim(e) => Some(e),
+            _ => None,
+        };
+        Payload { code: self.code(), message: self.to_string(), scim }.serialize(serializer)
+    }
+}
+
+/// reqwest's own message stops at "error sending request for url (...)"; the part
+/// that explains what went wrong (refused connection, DNS, TLS alert) sits at the
+/// bottom of the source chain.
+pub fn describe_error(e: &reqwest::Error) -> String {
+    let mut root = None;
+    let mut source = std::error::Error::source(e);
+    while let Some(s) = source {
+        root = Some(s.to_string());
+        source = s.source();
+    }
+    match root {
+        Some(root) if !e.to_string().contains(&root) => format!("{}: {}", e, root),
+        _ => e.to_string(),
+    }
+}
+
+/// rustls errors reach reqwest wrapped in (possibly nested) `io::Error`s, which
+/// hide them from `source()`, so io errors in the chain are unwrapped as well.
+fn is_tls_error(e: &reqwest::Error) -> bool {
+    let mut source = std::error::Error::source(e);
+    while let Some(s) = source {
+        let mut inner: &(dyn std::error::Error + 'static) = s;
+        loop {
+            if inner.is::<rustls::Error>() {
+                return true;
+            }
+            match inner.downcast_ref::<std::io::Error>().and_then(|io| io.get_ref()) {
+                Some(wrapped) => inner = wrapped,
+                None => break,
+            }
+        }
+        source = s.source();
+    }
+    false
+}
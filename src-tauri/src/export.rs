@@ -1,3 +1,4 @@
+use crate::error::AppError;
 use crate::models::*;
 
 pub struct ExportEngine;
@@ -46,7 +47,8 @@ impl ExportEngine {
 
         wtr.write_record([
             "Test Name", "Category", "HTTP Method", "URL", "Response Status",
-            "Duration (ms)", "Passed", "Failure Reason", "Executed At"
+            "Duration (ms)", "Passed", "Outcome", "Requirement", "RFC Reference", "Severity",
+            "Failure Reason", "Executed At", "Schema Violations"
         ]).map_err(|e| format!("CSV write error: {}", e))?;
 
         for r in results {
@@ -58,8 +60,13 @@ impl ExportEngine {
                 &r.response_status.map_or(String::new(), |s| s.to_string()),
                 &r.duration_ms.to_string(),
                 &r.passed.to_string(),
+                r.outcome.as_str(),
+                r.requirement_level.as_str(),
+                r.rfc_reference.as_deref().unwrap_or(""),
+                r.severity.as_str(),
                 r.failure_reason.as_deref().unwrap_or(""),
                 r.executed_at.as_str(),
+                &schema_violations_text(r),
             ]).map_err(|e| format!("CSV write error: {}", e))?;
         }
 
@@ -75,13 +82,14 @@ impl ExportEngine {
             .map_err(|e| format!("Failed to create CSV writer: {}", e))?;
 
         wtr.write_record([
-            "Request Index", "HTTP Method", "URL", "Status Code",
+            "Request Index", "Attempt", "HTTP Method", "URL", "Status Code",
             "Duration (ms)", "Success", "Error Message", "Timestamp"
         ]).map_err(|e| format!("CSV write error: {}", e))?;
 
         for r in results {
             wtr.write_record([
                 &r.request_index.to_string(),
+                &r.attempt.to_string(),
                 r.http_method.as_str(),
                 r.url.as_str(),
                 &r.status_code.map_or(String::new(), |s| s.to_string()),
@@ -115,6 +123,8 @@ table{width:100%;border-collapse:collapse;margin-top:16px;font-size:13px}
 th{background:#e3f2fd;padding:8px 12px;text-align:left;font-weight:600}
 td{padding:6px 12px;border-bottom:1px solid #e0e0e0}
 .pass{color:#2e7d32;font-weight:600} .fail{color:#c62828;font-weight:600}
+.warn{color:#e65100;font-weight:600} .skip{color:#757575;font-weight:600}
+h3.critical{color:#b71c1c} h3.high{color:#c62828} h3.medium{color:#e65100} h3.low{color:#757575}
 @media print{body{padding:0}.stat{break-inside:avoid}}
 </style></head><body>
 <h1>SCIM Validation Report</h1>
@@ -123,39 +133,71 @@ td{padding:6px 12px;border-bottom:1px solid #e0e0e0}
 
         html.push_str(&format!(
             r#"<div class="stat"><div class="value score">{:.1}%</div><div class="label">Compliance Score</div></div>
+<div class="stat"><div class="value score">{:.1}%</div><div class="label">Weighted by Severity</div></div>
 <div class="stat"><div class="value">{}</div><div class="label">Total</div></div>
 <div class="stat"><div class="value score">{}</div><div class="label">Passed</div></div>
 <div class="stat"><div class="value fail-c">{}</div><div class="label">Failed</div></div>
 <div class="stat"><div class="value">{}</div><div class="label">Skipped</div></div>
+<div class="stat"><div class="value">{}</div><div class="label">Warnings</div></div>
+<div class="stat"><div class="value">{}</div><div class="label">Errors</div></div>
 </div>"#,
-            summary.compliance_score, summary.total, summary.passed, summary.failed, summary.skipped
+            summary.compliance_score, summary.weighted_score, summary.total, summary.passed, summary.failed,
+            summary.skipped, summary.warnings, summary.errors
         ));
 
         // Category breakdown
         if !summary.categories.is_empty() {
-            html.push_str("<h2>Category Breakdown</h2><table><tr><th>Category</th><th>Passed</th><th>Failed</th><th>Total</th></tr>");
+            html.push_str("<h2>Category Breakdown</h2><table><tr><th>Category</th><th>Passed</th><th>Failed</th><th>Skipped</th><th>Total</th></tr>");
             for cat in &summary.categories {
                 html.push_str(&format!(
-                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
-                    cat.name, cat.passed, cat.failed, cat.total
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    cat.name, cat.passed, cat.failed, cat.skipped, cat.total
                 ));
             }
             html.push_str("</table>");
         }
 
+        // Failures, most severe first
+        let findings = findings_by_severity(results);
+        if !findings.is_empty() {
+            html.push_str("<h2>Findings by Severity</h2>");
+            for (severity, group) in &findings {
+                html.push_str(&format!(
+                    "<h3 class=\"{}\">{} ({})</h3><table><tr><th>Test</th><th>Category</th><th>Requirement</th><th>Result</th><th>Reason</th></tr>",
+                    severity.as_str(), severity_label(*severity), group.len()
+                ));
+                for r in group {
+                    html.push_str(&format!(
+                        "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"fail\">{}</td><td>{}</td></tr>",
+                        html_escape(&r.test_name), html_escape(&r.category), html_escape(&requirement_text(r)),
+                        outcome_label(r.outcome), html_escape(r.failure_reason.as_deref().unwrap_or("-"))
+                    ));
+                }
+                html.push_str("</table>");
+            }
+        }
+
         // Detailed results
         html.push_str("<h2>Detailed Results</h2><table>
-<tr><th>Test</th><th>Category</th><th>Method</th><th>Status</th><th>Duration</th><th>Result</th><th>Reason</th></tr>");
+<tr><th>Test</th><th>Category</th><th>Requirement</th><th>Method</th><th>Status</th><th>Duration</th><th>Result</th><th>Reason</th></tr>");
 
         for r in results {
-            let result_class = if r.passed { "pass" } else { "fail" };
-            let result_text = if r.passed { "PASS" } else { "FAIL" };
+            let result_class = match r.outcome {
+                TestOutcome::Passed => "pass",
+                TestOutcome::Warning => "warn",
+                TestOutcome::Skipped => "skip",
+                TestOutcome::Failed | TestOutcome::Error => "fail",
+            };
+            let result_text = outcome_label(r.outcome);
             let status = r.response_status.map_or("-".to_string(), |s| s.to_string());
-            let reason = r.failure_reason.as_deref().unwrap_or("-");
+            let mut reason = r.failure_reason.as_deref().unwrap_or("-").to_string();
+            if !r.schema_violations.is_empty() {
+                reason = format!("{} | Schema: {}", reason, schema_violations_text(r));
+            }
             html.push_str(&format!(
-                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}ms</td><td class=\"{}\">{}</td><td>{}</td></tr>",
-                html_escape(&r.test_name), html_escape(&r.category), &r.http_method,
-                status, r.duration_ms, result_class, result_text, html_escape(reason)
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}ms</td><td class=\"{}\">{}</td><td>{}</td></tr>",
+                html_escape(&r.test_name), html_escape(&r.category), html_escape(&requirement_text(r)), &r.http_method,
+                status, r.duration_ms, result_class, result_text, html_escape(&reason)
             ));
         }
 
@@ -202,12 +244,15 @@ td{padding:4px 10px;border-bottom:1px solid #e0e0e0}
 <div class="stat"><div class="value">{}ms</div><div class="label">Min</div></div>
 <div class="stat"><div class="value">{}ms</div><div class="label">Max</div></div>
 <div class="stat error-stat"><div class="value">{:.1}%</div><div class="label">Error Rate</div></div>
+<div class="stat"><div class="value">{}</div><div class="label">Retried</div></div>
+<div class="stat"><div class="value">{}</div><div class="label">Throttled (429)</div></div>
 </div>"#,
             summary.total_requests, summary.requests_per_second,
             summary.avg_latency_ms, summary.p50_latency_ms,
             summary.p75_latency_ms, summary.p90_latency_ms, summary.p95_latency_ms, summary.p99_latency_ms,
             summary.min_latency_ms, summary.max_latency_ms,
-            summary.error_rate
+            summary.error_rate,
+            summary.retried_attempts, summary.throttled_responses
         ));
 
         // Status code distribution
@@ -227,12 +272,12 @@ td{padding:4px 10px;border-bottom:1px solid #e0e0e0}
             "<h2>Request Details (showing {} of {})</h2>",
             max_detail, results.len()
         ));
-        html.push_str("<table><tr><th>#</th><th>Method</th><th>URL</th><th>Status</th><th>Duration</th><th>Success</th></tr>");
+        html.push_str("<table><tr><th>#</th><th>Attempt</th><th>Method</th><th>URL</th><th>Status</th><th>Duration</th><th>Success</th></tr>");
         for r in results.iter().take(max_detail) {
             let status = r.status_code.map_or("-".to_string(), |s| s.to_string());
             html.push_str(&format!(
-                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}ms</td><td>{}</td></tr>",
-                r.request_index, &r.http_method, html_escape(&r.url),
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}ms</td><td>{}</td></tr>",
+                r.request_index, r.attempt, &r.http_method, html_escape(&r.url),
                 status, r.duration_ms, if r.success { "✓" } else { "✗" }
             ));
         }
@@ -243,6 +288,81 @@ td{padding:4px 10px;border-bottom:1px solid #e0e0e0}
         Ok(())
     }
 
+    // ── HAR export ─────────────────────────────────────────────────────────
+
+    /// Write captured traffic as an HTTP Archive (HAR 1.2). Exchanges that failed
+    /// without a response have no timing breakdown, so their total time is
+    /// reported as `wait`; they get status 0 and the error in `_error`.
+    pub fn export_har(exchanges: &[HttpExchange], output_path: &str) -> Result<(), AppError> {
+        let entries: Vec<serde_json::Value> = exchanges.iter().map(|e| {
+            let query_string: Vec<serde_json::Value> = reqwest::Url::parse(&e.url)
+                .map(|url| url.query_pairs()
+                    .map(|(name, value)| serde_json::json!({ "name": name, "value": value }))
+                    .collect())
+                .unwrap_or_default();
+            let http_version = e.http_version.clone().unwrap_or_else(|| "HTTP/1.1".to_string());
+
+            let mut request = serde_json::json!({
+                "method": e.method,
+                "url": e.url,
+                "httpVersion": http_version,
+                "cookies": [],
+                "headers": har_headers(&e.request_headers),
+                "queryString": query_string,
+                "headersSize": -1,
+                "bodySize": e.request_body.as_ref().map_or(0, |b| b.len() as i64),
+            });
+            if let Some(ref body) = e.request_body {
+                request["postData"] = serde_json::json!({
+                    "mimeType": header_value(&e.request_headers, "content-type").unwrap_or("application/scim+json"),
+                    "text": body,
+                });
+            }
+
+            let (time, timings) = har_timings(e);
+            let body = e.response_body.as_deref().unwrap_or("");
+            let mut entry = serde_json::json!({
+                "startedDateTime": e.started_at,
+                "time": time,
+                "request": request,
+                "response": {
+                    "status": e.response_status.unwrap_or(0),
+                    "statusText": e.response_status_text.as_deref().unwrap_or(""),
+                    "httpVersion": http_version,
+                    "cookies": [],
+                    "headers": har_headers(&e.response_headers),
+                    "content": {
+                        "size": body.len(),
+                        "mimeType": header_value(&e.response_headers, "content-type").unwrap_or(""),
+                        "text": body,
+                    },
+                    "redirectURL": header_value(&e.response_headers, "location").unwrap_or(""),
+                    "headersSize": -1,
+                    "bodySize": if e.response_body.is_some() { body.len() as i64 } else { -1 },
+                },
+                "cache": {},
+                "timings": timings,
+            });
+            if let Some(ref error) = e.error {
+                entry["_error"] = serde_json::json!(error);
+            }
+            entry
+        }).collect();
+
+        let har = serde_json::json!({
+            "log": {
+                "version": "1.2",
+                "creator": { "name": "SCIM Inspector", "version": env!("CARGO_PKG_VERSION") },
+                "entries": entries,
+            }
+        });
+        let json = serde_json::to_string_pretty(&har)
+            .map_err(|e| AppError::Export(format!("Failed to serialize: {}", e)))?;
+        std::fs::write(output_path, json)
+            .map_err(|e| AppError::Export(format!("Failed to write file: {}", e)))?;
+        Ok(())
+    }
+
     // ── Excel exports ──────────────────────────────────────────────────────
 
     pub fn export_validation_excel(
@@ -322,6 +442,7 @@ td{padding:4px 10px;border-bottom:1px solid #e0e0e0}
             sheet.set_column_width(2, 14.0).map_err(xe)?;
             sheet.set_column_width(3, 14.0).map_err(xe)?;
             sheet.set_column_width(4, 12.0).map_err(xe)?;
+            sheet.set_column_width(5, 12.0).map_err(xe)?;
 
             // R0: Title
             sheet.set_row_height(0, 32.0).map_err(xe)?;
@@ -334,26 +455,31 @@ td{padding:4px 10px;border-bottom:1px solid #e0e0e0}
             // R3: Section – Key Metrics
             sheet.write_with_format(3, 0, "KEY METRICS", &fmt_section).map_err(xe)?;
 
-            let score_color = if summary.compliance_score >= 90.0 {
-                Color::RGB(0x2E7D32)
-            } else if summary.compliance_score >= 70.0 {
-                Color::RGB(0xE65100)
-            } else {
-                Color::RGB(0xC62828)
+            let score_format = |score: f64| {
+                let score_color = if score >= 90.0 {
+                    Color::RGB(0x2E7D32)
+                } else if score >= 70.0 {
+                    Color::RGB(0xE65100)
+                } else {
+                    Color::RGB(0xC62828)
+                };
+                Format::new()
+                    .set_bold()
+                    .set_font_size(14.0)
+                    .set_font_color(score_color)
+                    .set_border(FormatBorder::Thin)
+                    .set_align(FormatAlign::Center)
             };
-
-            let fmt_score = Format::new()
-                .set_bold()
-                .set_font_size(14.0)
-                .set_font_color(score_color)
-                .set_border(FormatBorder::Thin)
-                .set_align(FormatAlign::Center);
+            let fmt_score = score_format(summary.compliance_score);
+            let fmt_weighted = score_format(summary.weighted_score);
 
             let metrics: &[(&str, String)] = &[
                 ("Compliance Score", format!("{:.1}%", summary.compliance_score)),
+                ("Weighted by Severity", format!("{:.1}%", summary.weighted_score)),
                 ("Total Tests", summary.total.to_string()),
                 ("Passed", summary.passed.to_string()),
                 ("Failed", summary.failed.to_string()),
+                ("Skipped", summary.skipped.to_string()),
                 ("Duration", format!("{:.2}s", summary.duration_ms as f64 / 1000.0)),
             ];
 
@@ -361,25 +487,28 @@ td{padding:4px 10px;border-bottom:1px solid #e0e0e0}
                 let row = 4 + i as u32;
                 sheet.write_with_format(row, 0, *label, &fmt_bold).map_err(xe)?;
                 let vfmt = if i == 0 { &fmt_score }
-                           else if i == 2 { &fmt_pass_text }
-                           else if i == 3 { &fmt_fail_text }
+                           else if i == 1 { &fmt_weighted }
+                           else if i == 3 { &fmt_pass_text }
+                           else if i == 4 { &fmt_fail_text }
                            else { &fmt_cell };
                 sheet.write_with_format(row, 1, val.as_str(), vfmt).map_err(xe)?;
             }
 
-            // R10: Section – Category Breakdown
-            sheet.write_with_format(10, 0, "CATEGORY BREAKDOWN", &fmt_section).map_err(xe)?;
+            // R12: Section – Category Breakdown
+            sheet.write_with_format(12, 0, "CATEGORY BREAKDOWN", &fmt_section).map_err(xe)?;
 
-            sheet.write_with_format(11, 0, "Category", &fmt_header).map_err(xe)?;
-            sheet.write_with_format(11, 1, "Total", &fmt_header).map_err(xe)?;
-            sheet.write_with_format(11, 2, "Passed", &fmt_header).map_err(xe)?;
-            sheet.write_with_format(11, 3, "Failed", &fmt_header).map_err(xe)?;
-            sheet.write_with_format(11, 4, "Pass Rate", &fmt_header).map_err(xe)?;
+            sheet.write_with_format(13, 0, "Category", &fmt_header).map_err(xe)?;
+            sheet.write_with_format(13, 1, "Total", &fmt_header).map_err(xe)?;
+            sheet.write_with_format(13, 2, "Passed", &fmt_header).map_err(xe)?;
+            sheet.write_with_format(13, 3, "Failed", &fmt_header).map_err(xe)?;
+            sheet.write_with_format(13, 4, "Skipped", &fmt_header).map_err(xe)?;
+            sheet.write_with_format(13, 5, "Pass Rate", &fmt_header).map_err(xe)?;
 
-            let cat_first: u32 = 12;
+            let cat_first: u32 = 14;
             for (i, cat) in summary.categories.iter().enumerate() {
                 let row = cat_first + i as u32;
-                let pass_rate = if cat.total > 0 { cat.passed as f64 / cat.total as f64 } else { 0.0 };
+                let counted = cat.total - cat.skipped;
+                let pass_rate = if counted > 0 { cat.passed as f64 / counted as f64 } else { 0.0 };
                 let pr_color = if pass_rate >= 0.9 { Color::RGB(0x2E7D32) }
                                else if pass_rate >= 0.7 { Color::RGB(0xE65100) }
                                else { Color::RGB(0xC62828) };
@@ -393,7 +522,8 @@ td{padding:4px 10px;border-bottom:1px solid #e0e0e0}
                 sheet.write_with_format(row, 1, cat.total as u32, &fmt_center).map_err(xe)?;
                 sheet.write_with_format(row, 2, cat.passed as u32, &fmt_center).map_err(xe)?;
                 sheet.write_with_format(row, 3, cat.failed as u32, &fmt_center).map_err(xe)?;
-                sheet.write_with_format(row, 4, pass_rate, &fmt_pr).map_err(xe)?;
+                sheet.write_with_format(row, 4, cat.skipped as u32, &fmt_center).map_err(xe)?;
+                sheet.write_with_format(row, 5, pass_rate, &fmt_pr).map_err(xe)?;
             }
 
             let cat_last = cat_first + summary.categories.len().saturating_sub(1) as u32;
@@ -418,6 +548,11 @@ td{padding:4px 10px;border-bottom:1px solid #e0e0e0}
                     .set_categories(("Summary & Charts", cat_first, 0, cat_last, 0))
                     .set_values(("Summary & Charts", cat_first, 3, cat_last, 3));
 
+                chart.add_series()
+                    .set_name("Skipped")
+                    .set_categories(("Summary & Charts", cat_first, 0, cat_last, 0))
+                    .set_values(("Summary & Charts", cat_first, 4, cat_last, 4));
+
                 sheet.insert_chart(cat_last + 2, 0, &chart).map_err(xe)?;
             }
 
@@ -429,6 +564,8 @@ td{padding:4px 10px;border-bottom:1px solid #e0e0e0}
             sheet.write(5, 7, summary.passed as u32).map_err(xe)?;
             sheet.write(6, 6, "Failed").map_err(xe)?;
             sheet.write(6, 7, summary.failed as u32).map_err(xe)?;
+            sheet.write(7, 6, "Skipped").map_err(xe)?;
+            sheet.write(7, 7, summary.skipped as u32).map_err(xe)?;
 
             let mut pie = Chart::new(ChartType::Pie);
             pie.title().set_name("Overall Pass / Fail");
@@ -436,8 +573,8 @@ td{padding:4px 10px;border-bottom:1px solid #e0e0e0}
             pie.set_width(320);
             pie.set_height(240);
             pie.add_series()
-                .set_categories(("Summary & Charts", 5, 6, 6, 6))
-                .set_values(("Summary & Charts", 5, 7, 6, 7));
+                .set_categories(("Summary & Charts", 5, 6, 7, 6))
+                .set_values(("Summary & Charts", 5, 7, 7, 7));
 
             sheet.insert_chart(3, 9, &pie).map_err(xe)?;
         }
@@ -456,11 +593,16 @@ td{padding:4px 10px;border-bottom:1px solid #e0e0e0}
             sheet.set_column_width(5, 9.0).map_err(xe)?;
             sheet.set_column_width(6, 52.0).map_err(xe)?;
             sheet.set_column_width(7, 24.0).map_err(xe)?;
+            sheet.set_column_width(8, 52.0).map_err(xe)?;
+            sheet.set_column_width(9, 12.0).map_err(xe)?;
+            sheet.set_column_width(10, 18.0).map_err(xe)?;
+            sheet.set_column_width(11, 10.0).map_err(xe)?;
 
             let headers = [
                 "Test Name", "Category", "Method",
                 "Status Code", "Duration (ms)", "Result",
-                "Failure Reason", "Executed At",
+                "Failure Reason", "Executed At", "Schema Violations",
+                "Requirement", "RFC Reference", "Severity",
             ];
             for (c, h) in headers.iter().enumerate() {
                 sheet.write_with_format(0, c as u16, *h, &fmt_header).map_err(xe)?;
@@ -469,13 +611,16 @@ td{padding:4px 10px;border-bottom:1px solid #e0e0e0}
 
             for (i, r) in results.iter().enumerate() {
                 let row = 1 + i as u32;
-                let rf = if r.passed { &fmt_cell_pass } else { &fmt_cell_fail };
-                let rtf = if r.passed { &fmt_pass_text } else { &fmt_fail_text };
+                let skipped = r.outcome == TestOutcome::Skipped;
+                let rf = if r.passed { &fmt_cell_pass } else if skipped { &fmt_cell } else { &fmt_cell_fail };
+                let rtf = if r.passed { &fmt_pass_text } else if skipped { &fmt_center } else { &fmt_fail_text };
 
                 let center_rf = if r.passed {
                     Format::new().set_border(FormatBorder::Thin)
                         .set_background_color(Color::RGB(0xE8F5E9))
                         .set_align(FormatAlign::Center)
+                } else if skipped {
+                    fmt_center.clone()
                 } else {
                     Format::new().set_border(FormatBorder::Thin)
                         .set_background_color(Color::RGB(0xFFEBEE))
@@ -488,9 +633,52 @@ td{padding:4px 10px;border-bottom:1px solid #e0e0e0}
                 let status_str = r.response_status.map(|s| s.to_string()).unwrap_or_default();
                 sheet.write_with_format(row, 3, status_str.as_str(), &center_rf).map_err(xe)?;
                 sheet.write_with_format(row, 4, r.duration_ms, &center_rf).map_err(xe)?;
-                sheet.write_with_format(row, 5, if r.passed { "PASS" } else { "FAIL" }, rtf).map_err(xe)?;
+                sheet.write_with_format(row, 5, outcome_label(r.outcome), rtf).map_err(xe)?;
                 sheet.write_with_format(row, 6, r.failure_reason.as_deref().unwrap_or(""), rf).map_err(xe)?;
                 sheet.write_with_format(row, 7, &r.executed_at, rf).map_err(xe)?;
+                sheet.write_with_format(row, 8, schema_violations_text(r).as_str(), rf).map_err(xe)?;
+                sheet.write_with_format(row, 9, r.requirement_level.as_str(), &center_rf).map_err(xe)?;
+                sheet.write_with_format(row, 10, r.rfc_reference.as_deref().unwrap_or(""), rf).map_err(xe)?;
+                sheet.write_with_format(row, 11, severity_label(r.severity), &center_rf).map_err(xe)?;
+            }
+        }
+
+        // ╔══════════════════════════════════════════════════════╗
+        // ║  Sheet 3 – Findings by severity                     ║
+        // ╚══════════════════════════════════════════════════════╝
+        {
+            let sheet = workbook.add_worksheet();
+            sheet.set_name("Findings").map_err(xe)?;
+            sheet.set_column_width(0, 38.0).map_err(xe)?;
+            sheet.set_column_width(1, 24.0).map_err(xe)?;
+            sheet.set_column_width(2, 12.0).map_err(xe)?;
+            sheet.set_column_width(3, 18.0).map_err(xe)?;
+            sheet.set_column_width(4, 9.0).map_err(xe)?;
+            sheet.set_column_width(5, 60.0).map_err(xe)?;
+
+            let headers = ["Test Name", "Category", "Requirement", "RFC Reference", "Result", "Failure Reason"];
+            let mut row = 0u32;
+            let findings = findings_by_severity(results);
+            if findings.is_empty() {
+                sheet.write_with_format(row, 0, "No failed tests", &fmt_gray).map_err(xe)?;
+            }
+            for (severity, group) in findings {
+                sheet.write_with_format(row, 0, format!("{} ({})", severity_label(severity).to_uppercase(), group.len()), &fmt_section).map_err(xe)?;
+                row += 1;
+                for (c, h) in headers.iter().enumerate() {
+                    sheet.write_with_format(row, c as u16, *h, &fmt_header).map_err(xe)?;
+                }
+                row += 1;
+                for r in group {
+                    sheet.write_with_format(row, 0, &r.test_name, &fmt_cell_fail).map_err(xe)?;
+                    sheet.write_with_format(row, 1, &r.category, &fmt_cell_fail).map_err(xe)?;
+                    sheet.write_with_format(row, 2, r.requirement_level.as_str(), &fmt_cell_fail).map_err(xe)?;
+                    sheet.write_with_format(row, 3, r.rfc_reference.as_deref().unwrap_or(""), &fmt_cell_fail).map_err(xe)?;
+                    sheet.write_with_format(row, 4, outcome_label(r.outcome), &fmt_fail_text).map_err(xe)?;
+                    sheet.write_with_format(row, 5, r.failure_reason.as_deref().unwrap_or(""), &fmt_cell_fail).map_err(xe)?;
+                    row += 1;
+                }
+                row += 1;
             }
         }
 
@@ -865,3 +1053,84 @@ fn html_escape(s: &str) -> String {
         .replace('>', "&gt;")
         .replace('"', "&quot;")
 }
+
+fn outcome_label(outcome: TestOutcome) -> &'static str {
+    match outcome {
+        TestOutcome::Passed => "PASS",
+        TestOutcome::Failed => "FAIL",
+        TestOutcome::Skipped => "SKIP",
+        TestOutcome::Warning => "WARN",
+        TestOutcome::Error => "ERROR",
+    }
+}
+
+fn severity_label(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "Critical",
+        Severity::High => "High",
+        Severity::Medium => "Medium",
+        Severity::Low => "Low",
+    }
+}
+
+/// "MUST (RFC 7644 §3.3)", or the bare level for checks that are not from an RFC.
+fn requirement_text(r: &ValidationResult) -> String {
+    match &r.rfc_reference {
+        Some(section) => format!("{} ({})", r.requirement_level.as_str(), section),
+        None => r.requirement_level.as_str().to_string(),
+    }
+}
+
+/// Failed and errored tests grouped by severity, most severe first.
+fn findings_by_severity(results: &[ValidationResult]) -> Vec<(Severity, Vec<&ValidationResult>)> {
+    Severity::ALL.iter()
+        .map(|&severity| (severity, results.iter()
+            .filter(|r| r.severity == severity && matches!(r.outcome, TestOutcome::Failed | TestOutcome::Error))
+            .collect::<Vec<_>>()))
+        .filter(|(_, group)| !group.is_empty())
+        .collect()
+}
+
+/// "attribute (rule): message" for each schema violation, `; `-separated.
+fn schema_violations_text(r: &ValidationResult) -> String {
+    r.schema_violations.iter()
+        .map(|v| format!("{} ({}): {}", v.attribute, v.rule, v.message))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn har_headers(headers: &[HttpHeader]) -> Vec<serde_json::Value> {
+    headers.iter()
+        .map(|h| serde_json::json!({ "name": h.name, "value": h.value }))
+        .collect()
+}
+
+fn header_value<'a>(headers: &'a [HttpHeader], name: &str) -> Option<&'a str> {
+    headers.iter()
+        .find(|h| h.name.eq_ignore_ascii_case(name))
+        .map(|h| h.value.as_str())
+}
+
+/// HAR `timings` in milliseconds, and the entry's total time (their sum). Per the
+/// spec `connect` includes `ssl`, and phases that did not happen are -1.
+fn har_timings(e: &HttpExchange) -> (f64, serde_json::Value) {
+    let Some(t) = e.timing else {
+        let wait = e.duration_ms as f64;
+        return (wait, serde_json::json!({ "send": 0, "wait": wait, "receive": 0 }));
+    };
+    let ms = |us: i64| us as f64 / 1000.0;
+    let (dns, connect, ssl) = if t.connection_reused {
+        (-1.0, -1.0, -1.0)
+    } else {
+        (ms(t.dns_us), ms(t.connect_us + t.tls_us), if t.tls_us > 0 { ms(t.tls_us) } else { -1.0 })
+    };
+    let time = dns.max(0.0) + connect.max(0.0) + ms(t.ttfb_us) + ms(t.download_us);
+    (time, serde_json::json!({
+        "dns": dns,
+        "connect": connect,
+        "ssl": ssl,
+        "send": 0,
+        "wait": ms(t.ttfb_us),
+        "receive": ms(t.download_us),
+    }))
+}
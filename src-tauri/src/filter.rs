@@ -0,0 +1,775 @@
+//! SCIM filter expressions (RFC 7644 §3.4.2.2): an AST, a parser for the full
+//! grammar and a builder, plus percent-encoding for putting them in a URL.
+//!
+//! ```text
+//! FILTER    = attrExp / logExp / valuePath / *1"not" "(" FILTER ")"
+//! valuePath = attrPath "[" valFilter "]"
+//! attrExp   = (attrPath SP "pr") / (attrPath SP compareOp SP compValue)
+//! logExp    = FILTER SP ("and" / "or") SP FILTER
+//! ```
+//!
+//! `not` binds tighter than `and`, which binds tighter than `or`. Operators and
+//! keywords are case-insensitive.
+
+use serde_json::Number;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    Compare { path: AttrPath, op: CompareOp, value: CompValue },
+    Present(AttrPath),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+    /// `emails[type eq "work"]`: the inner filter applies to each value of a
+    /// multi-valued (or complex) attribute.
+    ValuePath { path: AttrPath, filter: Box<Filter> },
+}
+
+/// `[URI ":"] ATTRNAME ["." subAttr]`, e.g. `name.givenName` or
+/// `urn:ietf:params:scim:schemas:extension:enterprise:2.0:User:employeeNumber`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttrPath {
+    pub schema: Option<String>,
+    pub name: String,
+    pub sub_attr: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Co,
+    Sw,
+    Ew,
+    Gt,
+    Lt,
+    Ge,
+    Le,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompValue {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Invalid filter at position {position}: {message}")]
+pub struct FilterError {
+    /// Byte offset into the filter string.
+    pub position: usize,
+    pub message: String,
+}
+
+// ── Builder ──
+
+impl Filter {
+    pub fn compare(path: impl Into<AttrPath>, op: CompareOp, value: impl Into<CompValue>) -> Self {
+        Filter::Compare { path: path.into(), op, value: value.into() }
+    }
+
+    pub fn eq(path: impl Into<AttrPath>, value: impl Into<CompValue>) -> Self {
+        Self::compare(path, CompareOp::Eq, value)
+    }
+
+    pub fn ne(path: impl Into<AttrPath>, value: impl Into<CompValue>) -> Self {
+        Self::compare(path, CompareOp::Ne, value)
+    }
+
+    pub fn co(path: impl Into<AttrPath>, value: impl Into<CompValue>) -> Self {
+        Self::compare(path, CompareOp::Co, value)
+    }
+
+    pub fn sw(path: impl Into<AttrPath>, value: impl Into<CompValue>) -> Self {
+        Self::compare(path, CompareOp::Sw, value)
+    }
+
+    pub fn ew(path: impl Into<AttrPath>, value: impl Into<CompValue>) -> Self {
+        Self::compare(path, CompareOp::Ew, value)
+    }
+
+    pub fn gt(path: impl Into<AttrPath>, value: impl Into<CompValue>) -> Self {
+        Self::compare(path, CompareOp::Gt, value)
+    }
+
+    pub fn ge(path: impl Into<AttrPath>, value: impl Into<CompValue>) -> Self {
+        Self::compare(path, CompareOp::Ge, value)
+    }
+
+    pub fn lt(path: impl Into<AttrPath>, value: impl Into<CompValue>) -> Self {
+        Self::compare(path, CompareOp::Lt, value)
+    }
+
+    pub fn le(path: impl Into<AttrPath>, value: impl Into<CompValue>) -> Self {
+        Self::compare(path, CompareOp::Le, value)
+    }
+
+    pub fn present(path: impl Into<AttrPath>) -> Self {
+        Filter::Present(path.into())
+    }
+
+    pub fn value_path(path: impl Into<AttrPath>, filter: Filter) -> Self {
+        Filter::ValuePath { path: path.into(), filter: Box::new(filter) }
+    }
+
+    pub fn and(self, other: Filter) -> Self {
+        Filter::And(Box::new(self), Box::new(other))
+    }
+
+    pub fn or(self, other: Filter) -> Self {
+        Filter::Or(Box::new(self), Box::new(other))
+    }
+
+    pub fn negate(self) -> Self {
+        Filter::Not(Box::new(self))
+    }
+
+    pub fn parse(input: &str) -> Result<Self, FilterError> {
+        Parser::new(input)?.parse()
+    }
+
+    /// The filter percent-encoded for use as a query parameter value.
+    pub fn encoded(&self) -> String {
+        encode_query_value(&self.to_string())
+    }
+}
+
+impl AttrPath {
+    /// Split a path without validating it; use `parse` for untrusted input.
+    pub fn new(path: &str) -> Self {
+        let (schema, rest) = match path.rfind(':') {
+            Some(i) => (Some(path[..i].to_string()), &path[i + 1..]),
+            None => (None, path),
+        };
+        let (name, sub_attr) = match rest.split_once('.') {
+            Some((name, sub)) => (name.to_string(), Some(sub.to_string())),
+            None => (rest.to_string(), None),
+        };
+        AttrPath { schema, name, sub_attr }
+    }
+
+    pub fn parse(path: &str) -> Result<Self, FilterError> {
+        let parsed = Self::new(path);
+        let offset = parsed.schema.as_ref().map(|s| s.len() + 1).unwrap_or(0);
+        if parsed.schema.as_deref().is_some_and(|s| !s.contains(':')) {
+            return Err(FilterError { position: 0, message: format!("'{}' is not a schema URN", parsed.schema.unwrap()) });
+        }
+        if !is_attr_name(&parsed.name) {
+            return Err(FilterError { position: offset, message: format!("'{}' is not a valid attribute name", parsed.name) });
+        }
+        if let Some(ref sub) = parsed.sub_attr {
+            if !is_attr_name(sub) {
+                let position = offset + parsed.name.len() + 1;
+                return Err(FilterError { position, message: format!("'{}' is not a valid sub-attribute name", sub) });
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+/// ATTRNAME = ALPHA *("-" / "_" / DIGIT / ALPHA), plus `$ref`.
+fn is_attr_name(name: &str) -> bool {
+    if name == "$ref" {
+        return true;
+    }
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+impl From<&str> for AttrPath {
+    fn from(path: &str) -> Self {
+        AttrPath::new(path)
+    }
+}
+
+impl From<&String> for AttrPath {
+    fn from(path: &String) -> Self {
+        AttrPath::new(path)
+    }
+}
+
+impl CompareOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompareOp::Eq => "eq",
+            CompareOp::Ne => "ne",
+            CompareOp::Co => "co",
+            CompareOp::Sw => "sw",
+            CompareOp::Ew => "ew",
+            CompareOp::Gt => "gt",
+            CompareOp::Lt => "lt",
+            CompareOp::Ge => "ge",
+            CompareOp::Le => "le",
+        }
+    }
+
+    fn from_keyword(word: &str) -> Option<Self> {
+        Some(match word.to_ascii_lowercase().as_str() {
+            "eq" => CompareOp::Eq,
+            "ne" => CompareOp::Ne,
+            "co" => CompareOp::Co,
+            "sw" => CompareOp::Sw,
+            "ew" => CompareOp::Ew,
+            "gt" => CompareOp::Gt,
+            "lt" => CompareOp::Lt,
+            "ge" => CompareOp::Ge,
+            "le" => CompareOp::Le,
+            _ => return None,
+        })
+    }
+}
+
+impl From<&str> for CompValue {
+    fn from(value: &str) -> Self {
+        CompValue::String(value.to_string())
+    }
+}
+
+impl From<&String> for CompValue {
+    fn from(value: &String) -> Self {
+        CompValue::String(value.clone())
+    }
+}
+
+impl From<String> for CompValue {
+    fn from(value: String) -> Self {
+        CompValue::String(value)
+    }
+}
+
+impl From<bool> for CompValue {
+    fn from(value: bool) -> Self {
+        CompValue::Bool(value)
+    }
+}
+
+impl From<i64> for CompValue {
+    fn from(value: i64) -> Self {
+        CompValue::Number(value.into())
+    }
+}
+
+// ── Rendering ──
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Filter::Compare { path, op, value } => write!(f, "{} {} {}", path, op.as_str(), value),
+            Filter::Present(path) => write!(f, "{} pr", path),
+            Filter::And(a, b) => {
+                // `and` binds tighter than `or`, so `or` operands need grouping
+                let group = |x: &Filter| matches!(x, Filter::Or(..));
+                write_operand(f, a, group(a))?;
+                write!(f, " and ")?;
+                write_operand(f, b, group(b))
+            }
+            Filter::Or(a, b) => write!(f, "{} or {}", a, b),
+            Filter::Not(inner) => write!(f, "not ({})", inner),
+            Filter::ValuePath { path, filter } => write!(f, "{}[{}]", path, filter),
+        }
+    }
+}
+
+fn write_operand(f: &mut fmt::Formatter<'_>, filter: &Filter, grouped: bool) -> fmt::Result {
+    if grouped {
+        write!(f, "({})", filter)
+    } else {
+        write!(f, "{}", filter)
+    }
+}
+
+impl fmt::Display for AttrPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ref schema) = self.schema {
+            write!(f, "{}:", schema)?;
+        }
+        write!(f, "{}", self.name)?;
+        if let Some(ref sub) = self.sub_attr {
+            write!(f, ".{}", sub)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CompValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompValue::Null => write!(f, "null"),
+            CompValue::Bool(b) => write!(f, "{}", b),
+            CompValue::Number(n) => write!(f, "{}", n),
+            // Filter strings use JSON string syntax, escapes included
+            CompValue::String(s) => write!(f, "{}", serde_json::Value::String(s.clone())),
+        }
+    }
+}
+
+impl FromStr for Filter {
+    type Err = FilterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Filter::parse(s)
+    }
+}
+
+// ── Percent-encoding ──
+
+/// Percent-encode a query parameter value: everything except RFC 3986
+/// unreserved characters. Spaces become `%20`, never `+`, which some servers
+/// do not decode in filters.
+pub fn encode_query_value(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() * 3);
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+/// Decode `%XX` escapes. A `+` is left alone, since in a SCIM filter it is more
+/// likely to be part of a value (an email address) than an encoded space.
+pub fn decode_query_value(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(decoded)) => {
+                out.push(decoded);
+                i += 3;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Re-encode a query string typed by hand (`filter=userName eq "a+b@x.com"&count=10`)
+/// so that each value reaches the server exactly as written. Values that are
+/// already percent-encoded are decoded first, so they are not encoded twice.
+pub fn encode_query_string(query: &str) -> String {
+    query.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) => format!(
+                "{}={}",
+                encode_query_value(&decode_query_value(key)),
+                encode_query_value(&decode_query_value(value))
+            ),
+            None => encode_query_value(&decode_query_value(pair)),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+// ── Parser ──
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    OpenBracket,
+    CloseBracket,
+    Str(String),
+    /// Attribute paths, operators, keywords and literals.
+    Word(String),
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    len: usize,
+}
+
+impl Parser {
+    fn new(input: &str) -> Result<Self, FilterError> {
+        Ok(Parser { tokens: tokenize(input)?, pos: 0, len: input.len() })
+    }
+
+    fn parse(mut self) -> Result<Filter, FilterError> {
+        if self.tokens.is_empty() {
+            return Err(FilterError { position: 0, message: "Filter is empty".to_string() });
+        }
+        let filter = self.parse_or(false)?;
+        match self.tokens.get(self.pos) {
+            None => Ok(filter),
+            Some((position, token)) => Err(FilterError {
+                position: *position,
+                message: format!("Unexpected {}", describe(token)),
+            }),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.pos).map(|(p, _)| *p).unwrap_or(self.len)
+    }
+
+    fn next(&mut self) -> Option<(usize, Token)> {
+        let token = self.tokens.get(self.pos).cloned();
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, FilterError> {
+        Err(FilterError { position: self.position(), message: message.into() })
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), FilterError> {
+        match self.peek() {
+            Some(token) if *token == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(token) => {
+                let message = format!("Expected {}, found {}", describe(&expected), describe(token));
+                self.error(message)
+            }
+            None => self.error(format!("Expected {}, found end of filter", describe(&expected))),
+        }
+    }
+
+    fn parse_or(&mut self, in_value_path: bool) -> Result<Filter, FilterError> {
+        let mut left = self.parse_and(in_value_path)?;
+        while self.peek_keyword("or") {
+            self.pos += 1;
+            let right = self.parse_and(in_value_path)?;
+            left = left.or(right);
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self, in_value_path: bool) -> Result<Filter, FilterError> {
+        let mut left = self.parse_unary(in_value_path)?;
+        while self.peek_keyword("and") {
+            self.pos += 1;
+            let right = self.parse_unary(in_value_path)?;
+            left = left.and(right);
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self, in_value_path: bool) -> Result<Filter, FilterError> {
+        if self.peek_keyword("not") && self.tokens.get(self.pos + 1).is_some_and(|(_, t)| *t == Token::Open) {
+            self.pos += 1;
+            return Ok(self.parse_group(in_value_path)?.negate());
+        }
+        match self.peek() {
+            Some(Token::Open) => self.parse_group(in_value_path),
+            Some(Token::Word(_)) => self.parse_attr_expr(in_value_path),
+            Some(token) => {
+                let message = format!("Expected an attribute or '(', found {}", describe(token));
+                self.error(message)
+            }
+            None => self.error("Expected an attribute or '(', found end of filter"),
+        }
+    }
+
+    fn parse_group(&mut self, in_value_path: bool) -> Result<Filter, FilterError> {
+        self.expect(Token::Open)?;
+        let inner = self.parse_or(in_value_path)?;
+        self.expect(Token::Close)?;
+        Ok(inner)
+    }
+
+    fn parse_attr_expr(&mut self, in_value_path: bool) -> Result<Filter, FilterError> {
+        let Some((start, Token::Word(word))) = self.next() else {
+            return self.error("Expected an attribute");
+        };
+        let path = AttrPath::parse(&word).map_err(|e| FilterError { position: start + e.position, ..e })?;
+
+        if self.peek() == Some(&Token::OpenBracket) {
+            if in_value_path {
+                return self.error("Value filters cannot be nested");
+            }
+            self.pos += 1;
+            let inner = self.parse_or(true)?;
+            self.expect(Token::CloseBracket)?;
+            return Ok(Filter::value_path(path, inner));
+        }
+
+        let Some((op_position, Token::Word(op))) = self.next() else {
+            self.pos = self.pos.saturating_sub(1);
+            return self.error(format!("Expected an operator after '{}'", word));
+        };
+        if op.eq_ignore_ascii_case("pr") {
+            return Ok(Filter::Present(path));
+        }
+        let Some(op) = CompareOp::from_keyword(&op) else {
+            return Err(FilterError { position: op_position, message: format!("'{}' is not a filter operator", op) });
+        };
+
+        let value = match self.next() {
+            Some((_, Token::Str(s))) => CompValue::String(s),
+            Some((position, Token::Word(w))) => match w.as_str() {
+                "true" => CompValue::Bool(true),
+                "false" => CompValue::Bool(false),
+                "null" => CompValue::Null,
+                _ => match w.parse::<Number>() {
+                    Ok(n) => CompValue::Number(n),
+                    Err(_) => {
+                        return Err(FilterError {
+                            position,
+                            message: format!("'{}' is not a valid value; strings must be quoted", w),
+                        })
+                    }
+                },
+            },
+            Some((position, token)) => {
+                return Err(FilterError { position, message: format!("Expected a value, found {}", describe(&token)) })
+            }
+            None => return self.error(format!("Expected a value after '{}'", op.as_str())),
+        };
+        Ok(Filter::Compare { path, op, value })
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Open => "'('".to_string(),
+        Token::Close => "')'".to_string(),
+        Token::OpenBracket => "'['".to_string(),
+        Token::CloseBracket => "']'".to_string(),
+        Token::Str(s) => format!("string {}", serde_json::Value::String(s.clone())),
+        Token::Word(w) => format!("'{}'", w),
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, FilterError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' | ')' | '[' | ']' => {
+                chars.next();
+                let token = match c {
+                    '(' => Token::Open,
+                    ')' => Token::Close,
+                    '[' => Token::OpenBracket,
+                    _ => Token::CloseBracket,
+                };
+                tokens.push((start, token));
+            }
+            '"' => {
+                chars.next();
+                let mut end = None;
+                let mut escaped = false;
+                for (i, c) in chars.by_ref() {
+                    match c {
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        '"' => {
+                            end = Some(i);
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                let Some(end) = end else {
+                    return Err(FilterError { position: start, message: "Unterminated string".to_string() });
+                };
+                let value = serde_json::from_str::<String>(&input[start..=end])
+                    .map_err(|_| FilterError { position: start, message: "Invalid escape in string".to_string() })?;
+                tokens.push((start, Token::Str(value)));
+            }
+            _ => {
+                let mut end = input.len();
+                while let Some(&(i, c)) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '(' | ')' | '[' | ']' | '"') {
+                        end = i;
+                        break;
+                    }
+                    chars.next();
+                }
+                tokens.push((start, Token::Word(input[start..end].to_string())));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Filter {
+        Filter::parse(input).unwrap_or_else(|e| panic!("{}: {}", input, e))
+    }
+
+    fn error_at(input: &str) -> usize {
+        Filter::parse(input).unwrap_err().position
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(
+            parse(r#"a eq 1 or b eq 2 and c eq 3"#),
+            Filter::eq("a", 1).or(Filter::eq("b", 2).and(Filter::eq("c", 3)))
+        );
+        assert_eq!(
+            parse(r#"a eq 1 and b eq 2 or c eq 3"#),
+            Filter::eq("a", 1).and(Filter::eq("b", 2)).or(Filter::eq("c", 3))
+        );
+    }
+
+    #[test]
+    fn not_binds_tighter_than_and() {
+        assert_eq!(
+            parse("not (a pr) and b pr"),
+            Filter::present("a").negate().and(Filter::present("b"))
+        );
+    }
+
+    #[test]
+    fn parentheses_override_precedence() {
+        assert_eq!(
+            parse("(a pr or b pr) and c pr"),
+            Filter::present("a").or(Filter::present("b")).and(Filter::present("c"))
+        );
+    }
+
+    #[test]
+    fn chains_are_left_associative() {
+        assert_eq!(
+            parse("a pr or b pr or c pr"),
+            Filter::present("a").or(Filter::present("b")).or(Filter::present("c"))
+        );
+    }
+
+    #[test]
+    fn rendering_groups_or_inside_and() {
+        let filter = Filter::present("a").or(Filter::present("b")).and(Filter::present("c"));
+        assert_eq!(filter.to_string(), "(a pr or b pr) and c pr");
+        assert_eq!(parse(&filter.to_string()), filter);
+
+        let filter = Filter::present("a").and(Filter::present("b")).or(Filter::present("c"));
+        assert_eq!(filter.to_string(), "a pr and b pr or c pr");
+        assert_eq!(parse(&filter.to_string()), filter);
+    }
+
+    #[test]
+    fn keywords_and_operators_are_case_insensitive() {
+        assert_eq!(
+            parse(r#"userName EQ "bjensen" AND NOT (active Eq false)"#),
+            Filter::eq("userName", "bjensen").and(Filter::eq("active", false).negate())
+        );
+        assert_eq!(parse("title PR"), Filter::present("title"));
+    }
+
+    #[test]
+    fn not_without_parentheses_is_an_attribute() {
+        assert_eq!(parse("not pr"), Filter::present("not"));
+    }
+
+    #[test]
+    fn string_escapes_are_decoded_and_reencoded() {
+        let filter = parse(r#"displayName eq "say \"hi\" \\ é""#);
+        assert_eq!(filter, Filter::eq("displayName", "say \"hi\" \\ é"));
+        assert_eq!(filter.to_string(), r#"displayName eq "say \"hi\" \\ é""#);
+
+        let built = Filter::eq("displayName", "line\nbreak \"quoted\"");
+        assert_eq!(parse(&built.to_string()), built);
+    }
+
+    #[test]
+    fn brackets_and_keywords_inside_strings_are_literal() {
+        assert_eq!(
+            parse(r#"displayName eq "a) or (b [c]""#),
+            Filter::eq("displayName", "a) or (b [c]")
+        );
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(parse("manager eq null"), Filter::eq("manager", CompValue::Null));
+        assert_eq!(parse("active eq true"), Filter::eq("active", true));
+        assert_eq!(
+            parse("score ge 1.5"),
+            Filter::ge("score", CompValue::Number(Number::from_f64(1.5).unwrap()))
+        );
+        assert_eq!(parse("age lt -3"), Filter::lt("age", -3));
+    }
+
+    #[test]
+    fn schema_qualified_paths_and_sub_attributes() {
+        let urn = "urn:ietf:params:scim:schemas:extension:enterprise:2.0:User";
+        let filter = parse(&format!(r#"{}:manager.value eq "26118915""#, urn));
+        let Filter::Compare { path, .. } = &filter else { panic!("{:?}", filter) };
+        assert_eq!(path.schema.as_deref(), Some(urn));
+        assert_eq!(path.name, "manager");
+        assert_eq!(path.sub_attr.as_deref(), Some("value"));
+    }
+
+    #[test]
+    fn value_paths() {
+        assert_eq!(
+            parse(r#"emails[type eq "work" and value ew "@example.com"]"#),
+            Filter::value_path("emails", Filter::eq("type", "work").and(Filter::ew("value", "@example.com")))
+        );
+        let filter = Filter::value_path("emails", Filter::eq("type", "work"));
+        assert_eq!(filter.to_string(), r#"emails[type eq "work"]"#);
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_token() {
+        assert_eq!(error_at(""), 0);
+        assert_eq!(error_at("   "), 0);
+        assert_eq!(error_at(r#"userName eq "bjensen"#), 12); // unterminated string
+        assert_eq!(error_at("userName eq bjensen"), 12); // unquoted value
+        assert_eq!(error_at(r#"userName like "b""#), 9); // unknown operator
+        assert_eq!(error_at("userName"), 0); // no operator after the attribute
+        assert_eq!(error_at("userName eq"), 11); // no value
+        assert_eq!(error_at("(title pr"), 9); // unclosed group
+        assert_eq!(error_at("title pr)"), 8); // stray ')'
+        assert_eq!(error_at("title pr and"), 12);
+        assert_eq!(error_at(r#"1name eq "x""#), 0);
+        assert_eq!(error_at(r#"name.1st eq "x""#), 5);
+        assert_eq!(error_at(r#"userName eq "\q""#), 12); // invalid escape
+    }
+
+    #[test]
+    fn value_filters_cannot_be_nested() {
+        assert!(Filter::parse(r#"groups[members[value eq "x"]]"#).is_err());
+    }
+
+    #[test]
+    fn encoding_keeps_plus_and_uses_percent_20_for_spaces() {
+        let filter = Filter::eq("userName", "a+b@example.com");
+        assert_eq!(filter.encoded(), "userName%20eq%20%22a%2Bb%40example.com%22");
+        assert_eq!(decode_query_value(&filter.encoded()), filter.to_string());
+        assert_eq!(decode_query_value("a+b%2"), "a+b%2");
+    }
+
+    #[test]
+    fn query_strings_are_not_encoded_twice() {
+        let typed = r#"filter=userName eq "a+b@example.com"&count=10"#;
+        let encoded = "filter=userName%20eq%20%22a%2Bb%40example.com%22&count=10";
+        assert_eq!(encode_query_string(typed), encoded);
+        assert_eq!(encode_query_string(encoded), encoded);
+    }
+}
@@ -1,10 +1,25 @@
 pub mod models;
 pub mod db;
 pub mod scim_client;
+pub mod pagination;
+pub mod error;
+pub mod scim;
+pub mod filter;
+pub mod conformance;
+pub mod requirements;
+pub mod crypto;
+pub mod oauth;
+pub mod tls;
+pub mod connection;
+pub mod timing;
+pub mod rate_limit;
+pub mod traffic;
 pub mod validation;
 pub mod load_test;
 pub mod export;
 pub mod commands;
+#[cfg(test)]
+mod test_server;
 
 use commands::AppState;
 use db::Database;
@@ -39,6 +54,7 @@ pub fn run() {
             commands::start_load_test,
             commands::stop_load_test,
             commands::get_load_test_results,
+            commands::get_http_exchanges,
             commands::get_test_runs,
             commands::get_test_run,
             commands::delete_test_run,
@@ -52,7 +68,11 @@ pub fn run() {
             commands::get_app_setting,
             commands::save_app_setting,
             commands::delete_app_setting,
+            commands::get_credential_protection,
+            commands::unlock_credentials,
+            commands::set_credential_passphrase,
             commands::execute_scim_request,
+            commands::validate_scim_filter,
             commands::generate_scim_data,
             commands::get_sample_data,
             commands::save_sample_data,
@@ -8,8 +8,14 @@ use std::time::Instant;
 use tokio::sync::{Semaphore, Mutex};
 use tauri::{AppHandle, Emitter};
 
+use crate::filter::Filter;
 use crate::models::*;
-use crate::scim_client::ScimClient;
+use crate::scim::{self, BulkOperation, BulkRequest, Group, Member, PatchOp, PatchOperation, ServiceProviderConfig, User};
+use crate::scim_client::{RetriedAttempt, ScimClient};
+
+/// Users per /Bulk request when the server does not advertise maxOperations.
+const DEFAULT_BULK_BATCH: usize = 10;
+const MAX_BULK_BATCH: usize = 100;
 
 pub struct LoadTestEngine;
 
@@ -54,6 +60,7 @@ impl LoadTestEngine {
             "group_lifecycle" => Self::scenario_group_lifecycle(app, client, test_run_id, config, cancel_flag).await,
             "add_remove_members" => Self::scenario_add_remove_members(app, client, test_run_id, config, cancel_flag).await,
             "update_groups" => Self::scenario_update_groups(app, client, test_run_id, config, cancel_flag).await,
+            "bulk_import" => Self::scenario_bulk_import(app, client, test_run_id, config, cancel_flag).await,
             _ => Self::scenario_create_users(app, client, test_run_id, config, cancel_flag).await,
         }
     }
@@ -91,9 +98,13 @@ impl LoadTestEngine {
             }
         }
 
-        // Re-index results sequentially
-        for (i, r) in all_results.iter_mut().enumerate() {
-            r.request_index = i as i64;
+        // Re-index results sequentially; retries keep their request's index
+        let mut index = -1;
+        for r in all_results.iter_mut() {
+            if r.attempt == 1 {
+                index += 1;
+            }
+            r.request_index = index;
         }
         all_results
     }
@@ -133,24 +144,22 @@ impl LoadTestEngine {
                 if cancel.load(Ordering::Relaxed) { return None; }
 
                 let body = Self::generate_user_body(i);
-                let result = client.request(Method::POST, "/Users", Some(&body)).await;
+                let (retries, result) = client.request_with_retries(Method::POST, "/Users", Some(&body)).await;
                 let comp = completed.fetch_add(1, Ordering::Relaxed) + 1;
 
-                let load_result = Self::build_result(&run_id, i, "POST", "/Users", Some(body), &result, &error_count);
+                let load_results = Self::build_results(&run_id, i, "POST", "/Users", Some(body), &retries, &result, &error_count);
 
                 // Capture created user ID for cleanup
                 if let Ok(ref resp) = result {
                     if resp.status == 201 {
-                        if let Ok(json) = serde_json::from_str::<Value>(&resp.body) {
-                            if let Some(id) = json.get("id").and_then(|v| v.as_str()) {
-                                created_ids.lock().await.push(id.to_string());
-                            }
+                        if let Some(id) = scim::resource_id(&resp.body) {
+                            created_ids.lock().await.push(id);
                         }
                     }
                 }
 
                 Self::emit_phase_progress(&app, &run_id, "Creating users", comp, total, &start_time, &error_count);
-                Some(load_result)
+                Some(load_results)
             }));
         }
 
@@ -199,23 +208,21 @@ impl LoadTestEngine {
                 if cancel.load(Ordering::Relaxed) { return None; }
 
                 let body = Self::generate_user_body(i);
-                let result = client.request(Method::POST, "/Users", Some(&body)).await;
+                let (retries, result) = client.request_with_retries(Method::POST, "/Users", Some(&body)).await;
                 let comp = completed.fetch_add(1, Ordering::Relaxed) + 1;
 
-                let load_result = Self::build_result(&run_id, i, "POST", "/Users", Some(body), &result, &error_count);
+                let load_results = Self::build_results(&run_id, i, "POST", "/Users", Some(body), &retries, &result, &error_count);
 
                 if let Ok(ref resp) = result {
                     if resp.status == 201 {
-                        if let Ok(json) = serde_json::from_str::<Value>(&resp.body) {
-                            if let Some(id) = json.get("id").and_then(|v| v.as_str()) {
-                                created_ids.lock().await.push(id.to_string());
-                            }
+                        if let Some(id) = scim::resource_id(&resp.body) {
+                            created_ids.lock().await.push(id);
                         }
                     }
                 }
 
                 Self::emit_phase_progress(&app, &run_id, "Creating users", comp, total_http, &start_time, &error_count);
-                Some(load_result)
+                Some(load_results)
             }));
         }
 
@@ -242,12 +249,12 @@ impl LoadTestEngine {
                 if cancel.load(Ordering::Relaxed) { return None; }
 
                 let body = Self::generate_patch_body();
-                let result = client.request(Method::PATCH, &path, Some(&body)).await;
+                let (retries, result) = client.request_with_retries(Method::PATCH, &path, Some(&body)).await;
                 let comp = completed.fetch_add(1, Ordering::Relaxed) + 1;
 
-                let load_result = Self::build_result(&run_id, idx, "PATCH", &path, Some(body), &result, &error_count);
+                let load_results = Self::build_results(&run_id, idx, "PATCH", &path, Some(body), &retries, &result, &error_count);
                 Self::emit_phase_progress(&app, &run_id, "Updating users", comp, total_http, &start_time, &error_count);
-                Some(load_result)
+                Some(load_results)
             }));
         }
 
@@ -299,16 +306,15 @@ impl LoadTestEngine {
 
                 // 1. Create
                 let body = Self::generate_user_body(i);
-                let create_result = client.request(Method::POST, "/Users", Some(&body)).await;
+                let (create_retries, create_result) = client.request_with_retries(Method::POST, "/Users", Some(&body)).await;
                 let comp = completed.fetch_add(1, Ordering::Relaxed) + 1;
-                batch.push(Self::build_result(&run_id, base_idx, "POST", "/Users", Some(body), &create_result, &error_count));
+                batch.extend(Self::build_results(&run_id, base_idx, "POST", "/Users", Some(body), &create_retries, &create_result, &error_count));
                 Self::emit_phase_progress(&app, &run_id, "Creating users", comp, total_http, &start_time, &error_count);
 
                 // Extract user ID for read + delete
                 let user_id = create_result.ok().and_then(|resp| {
                     if resp.status == 201 {
-                        serde_json::from_str::<Value>(&resp.body).ok()
-                            .and_then(|j| j.get("id").and_then(|v| v.as_str()).map(|s| s.to_string()))
+                        scim::resource_id(&resp.body)
                     } else {
                         None
                     }
@@ -318,18 +324,18 @@ impl LoadTestEngine {
                     if !cancel.load(Ordering::Relaxed) {
                         // 2. Read
                         let read_path = format!("/Users/{}", uid);
-                        let read_result = client.request(Method::GET, &read_path, None).await;
+                        let (read_retries, read_result) = client.request_with_retries(Method::GET, &read_path, None).await;
                         let comp = completed.fetch_add(1, Ordering::Relaxed) + 1;
-                        batch.push(Self::build_result(&run_id, base_idx + 1, "GET", &read_path, None, &read_result, &error_count));
+                        batch.extend(Self::build_results(&run_id, base_idx + 1, "GET", &read_path, None, &read_retries, &read_result, &error_count));
                         Self::emit_phase_progress(&app, &run_id, "Reading users", comp, total_http, &start_time, &error_count);
                     }
 
                     if !cancel.load(Ordering::Relaxed) {
                         // 3. Delete
                         let del_path = format!("/Users/{}", uid);
-                        let del_result = client.request(Method::DELETE, &del_path, None).await;
+                        let (del_retries, del_result) = client.request_with_retries(Method::DELETE, &del_path, None).await;
                         let comp = completed.fetch_add(1, Ordering::Relaxed) + 1;
-                        batch.push(Self::build_result(&run_id, base_idx + 2, "DELETE", &del_path, None, &del_result, &error_count));
+                        batch.extend(Self::build_results(&run_id, base_idx + 2, "DELETE", &del_path, None, &del_retries, &del_result, &error_count));
                         Self::emit_phase_progress(&app, &run_id, "Deleting users", comp, total_http, &start_time, &error_count);
                     }
                 } else {
@@ -348,6 +354,8 @@ impl LoadTestEngine {
                         success: false,
                         error_message: Some("Skipped — create failed".to_string()),
                         timestamp: Utc::now().to_rfc3339(),
+                        attempt: 1,
+                        timing: None,
                     });
                     batch.push(LoadTestResult {
                         id: Uuid::new_v4().to_string(),
@@ -361,6 +369,8 @@ impl LoadTestEngine {
                         success: false,
                         error_message: Some("Skipped — create failed".to_string()),
                         timestamp: Utc::now().to_rfc3339(),
+                        attempt: 1,
+                        timing: None,
                     });
                 }
 
@@ -374,7 +384,7 @@ impl LoadTestEngine {
             }
         }
 
-        results.sort_by_key(|r| r.request_index);
+        results.sort_by_key(|r| (r.request_index, r.attempt));
         results
     }
 
@@ -412,12 +422,12 @@ impl LoadTestEngine {
 
                 let start_index = (i * 10) + 1;
                 let path = format!("/Users?startIndex={}&count=10", start_index);
-                let result = client.request(Method::GET, &path, None).await;
+                let (retries, result) = client.request_with_retries(Method::GET, &path, None).await;
                 let comp = completed.fetch_add(1, Ordering::Relaxed) + 1;
 
-                let load_result = Self::build_result(&run_id, i, "GET", &path, None, &result, &error_count);
+                let load_results = Self::build_results(&run_id, i, "GET", &path, None, &retries, &result, &error_count);
                 Self::emit_phase_progress(&app, &run_id, "Listing users", comp, total, &start_time, &error_count);
-                Some(load_result)
+                Some(load_results)
             }));
         }
 
@@ -460,23 +470,21 @@ impl LoadTestEngine {
                 if cancel.load(Ordering::Relaxed) { return None; }
 
                 let body = Self::generate_group_body(i);
-                let result = client.request(Method::POST, "/Groups", Some(&body)).await;
+                let (retries, result) = client.request_with_retries(Method::POST, "/Groups", Some(&body)).await;
                 let comp = completed.fetch_add(1, Ordering::Relaxed) + 1;
 
-                let load_result = Self::build_result(&run_id, i, "POST", "/Groups", Some(body), &result, &error_count);
+                let load_results = Self::build_results(&run_id, i, "POST", "/Groups", Some(body), &retries, &result, &error_count);
 
                 if let Ok(ref resp) = result {
                     if resp.status == 201 {
-                        if let Ok(json) = serde_json::from_str::<Value>(&resp.body) {
-                            if let Some(id) = json.get("id").and_then(|v| v.as_str()) {
-                                created_ids.lock().await.push(id.to_string());
-                            }
+                        if let Some(id) = scim::resource_id(&resp.body) {
+                            created_ids.lock().await.push(id);
                         }
                     }
                 }
 
                 Self::emit_phase_progress(&app, &run_id, "Creating groups", comp, total, &start_time, &error_count);
-                Some(load_result)
+                Some(load_results)
             }));
         }
 
@@ -528,31 +536,30 @@ impl LoadTestEngine {
 
                 // 1. Create
                 let body = Self::generate_group_body(i);
-                let create_result = client.request(Method::POST, "/Groups", Some(&body)).await;
+                let (create_retries, create_result) = client.request_with_retries(Method::POST, "/Groups", Some(&body)).await;
                 let comp = completed.fetch_add(1, Ordering::Relaxed) + 1;
-                batch.push(Self::build_result(&run_id, base_idx, "POST", "/Groups", Some(body), &create_result, &error_count));
+                batch.extend(Self::build_results(&run_id, base_idx, "POST", "/Groups", Some(body), &create_retries, &create_result, &error_count));
                 Self::emit_phase_progress(&app, &run_id, "Creating groups", comp, total_http, &start_time, &error_count);
 
                 let group_id = create_result.ok().and_then(|resp| {
                     if resp.status == 201 {
-                        serde_json::from_str::<Value>(&resp.body).ok()
-                            .and_then(|j| j.get("id").and_then(|v| v.as_str()).map(|s| s.to_string()))
+                        scim::resource_id(&resp.body)
                     } else { None }
                 });
 
                 if let Some(ref gid) = group_id {
                     if !cancel.load(Ordering::Relaxed) {
                         let read_path = format!("/Groups/{}", gid);
-                        let read_result = client.request(Method::GET, &read_path, None).await;
+                        let (read_retries, read_result) = client.request_with_retries(Method::GET, &read_path, None).await;
                         let comp = completed.fetch_add(1, Ordering::Relaxed) + 1;
-                        batch.push(Self::build_result(&run_id, base_idx + 1, "GET", &read_path, None, &read_result, &error_count));
+                        batch.extend(Self::build_results(&run_id, base_idx + 1, "GET", &read_path, None, &read_retries, &read_result, &error_count));
                         Self::emit_phase_progress(&app, &run_id, "Reading groups", comp, total_http, &start_time, &error_count);
                     }
                     if !cancel.load(Ordering::Relaxed) {
                         let del_path = format!("/Groups/{}", gid);
-                        let del_result = client.request(Method::DELETE, &del_path, None).await;
+                        let (del_retries, del_result) = client.request_with_retries(Method::DELETE, &del_path, None).await;
                         let comp = completed.fetch_add(1, Ordering::Relaxed) + 1;
-                        batch.push(Self::build_result(&run_id, base_idx + 2, "DELETE", &del_path, None, &del_result, &error_count));
+                        batch.extend(Self::build_results(&run_id, base_idx + 2, "DELETE", &del_path, None, &del_retries, &del_result, &error_count));
                         Self::emit_phase_progress(&app, &run_id, "Deleting groups", comp, total_http, &start_time, &error_count);
                     }
                 } else {
@@ -565,6 +572,8 @@ impl LoadTestEngine {
                         duration_ms: 0, success: false,
                         error_message: Some("Skipped — create failed".to_string()),
                         timestamp: Utc::now().to_rfc3339(),
+                        attempt: 1,
+                        timing: None,
                     });
                     batch.push(LoadTestResult {
                         id: Uuid::new_v4().to_string(), test_run_id: run_id.clone(),
@@ -573,6 +582,8 @@ impl LoadTestEngine {
                         duration_ms: 0, success: false,
                         error_message: Some("Skipped — create failed".to_string()),
                         timestamp: Utc::now().to_rfc3339(),
+                        attempt: 1,
+                        timing: None,
                     });
                 }
                 batch
@@ -584,7 +595,7 @@ impl LoadTestEngine {
                 results.extend(batch);
             }
         }
-        results.sort_by_key(|r| r.request_index);
+        results.sort_by_key(|r| (r.request_index, r.attempt));
         results
     }
 
@@ -607,16 +618,15 @@ impl LoadTestEngine {
 
         // 1. Create one group
         let group_body = Self::generate_group_body(0);
-        let group_result = client.request(Method::POST, "/Groups", Some(&group_body)).await;
+        let (group_retries, group_result) = client.request_with_retries(Method::POST, "/Groups", Some(&group_body)).await;
         completed.fetch_add(1, Ordering::Relaxed);
-        results.push(Self::build_result(test_run_id, idx, "POST", "/Groups", Some(group_body), &group_result, &error_count));
+        results.extend(Self::build_results(test_run_id, idx, "POST", "/Groups", Some(group_body), &group_retries, &group_result, &error_count));
         Self::emit_phase_progress(app, test_run_id, "Creating group", 1, total_http, &start_time, &error_count);
         idx += 1;
 
         let group_id = group_result.ok().and_then(|resp| {
             if resp.status == 201 {
-                serde_json::from_str::<Value>(&resp.body).ok()
-                    .and_then(|j| j.get("id").and_then(|v| v.as_str()).map(|s| s.to_string()))
+                scim::resource_id(&resp.body)
             } else { None }
         });
 
@@ -630,18 +640,16 @@ impl LoadTestEngine {
         for i in 0..n {
             if cancel_flag.load(Ordering::Relaxed) { break; }
             let body = Self::generate_user_body(i);
-            let result = client.request(Method::POST, "/Users", Some(&body)).await;
+            let (retries, result) = client.request_with_retries(Method::POST, "/Users", Some(&body)).await;
             let comp = completed.fetch_add(1, Ordering::Relaxed) + 1;
-            results.push(Self::build_result(test_run_id, idx, "POST", "/Users", Some(body), &result, &error_count));
+            results.extend(Self::build_results(test_run_id, idx, "POST", "/Users", Some(body), &retries, &result, &error_count));
             Self::emit_phase_progress(app, test_run_id, "Creating users", comp, total_http, &start_time, &error_count);
             idx += 1;
 
             if let Ok(ref resp) = result {
                 if resp.status == 201 {
-                    if let Ok(json) = serde_json::from_str::<Value>(&resp.body) {
-                        if let Some(id) = json.get("id").and_then(|v| v.as_str()) {
-                            user_ids.push(id.to_string());
-                        }
+                    if let Some(id) = scim::resource_id(&resp.body) {
+                        user_ids.push(id);
                     }
                 }
             }
@@ -651,13 +659,12 @@ impl LoadTestEngine {
         for uid in &user_ids {
             if cancel_flag.load(Ordering::Relaxed) { break; }
             let path = format!("/Groups/{}", group_id);
-            let body = serde_json::json!({
-                "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
-                "Operations": [{ "op": "add", "path": "members", "value": [{ "value": uid }] }]
-            }).to_string();
-            let result = client.request(Method::PATCH, &path, Some(&body)).await;
+            let body = scim::to_body(&PatchOp::new(vec![
+                PatchOperation::add(Some("members"), serde_json::json!([Member::new(uid)])),
+            ]));
+            let (retries, result) = client.request_with_retries(Method::PATCH, &path, Some(&body)).await;
             let comp = completed.fetch_add(1, Ordering::Relaxed) + 1;
-            results.push(Self::build_result(test_run_id, idx, "PATCH", &path, Some(body), &result, &error_count));
+            results.extend(Self::build_results(test_run_id, idx, "PATCH", &path, Some(body), &retries, &result, &error_count));
             Self::emit_phase_progress(app, test_run_id, "Adding members", comp, total_http, &start_time, &error_count);
             idx += 1;
         }
@@ -666,13 +673,12 @@ impl LoadTestEngine {
         for uid in &user_ids {
             if cancel_flag.load(Ordering::Relaxed) { break; }
             let path = format!("/Groups/{}", group_id);
-            let body = serde_json::json!({
-                "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
-                "Operations": [{ "op": "remove", "path": format!("members[value eq \"{}\"]", uid) }]
-            }).to_string();
-            let result = client.request(Method::PATCH, &path, Some(&body)).await;
+            let body = scim::to_body(&PatchOp::new(vec![
+                PatchOperation::remove(&Filter::value_path("members", Filter::eq("value", uid)).to_string()),
+            ]));
+            let (retries, result) = client.request_with_retries(Method::PATCH, &path, Some(&body)).await;
             let comp = completed.fetch_add(1, Ordering::Relaxed) + 1;
-            results.push(Self::build_result(test_run_id, idx, "PATCH", &path, Some(body), &result, &error_count));
+            results.extend(Self::build_results(test_run_id, idx, "PATCH", &path, Some(body), &retries, &result, &error_count));
             Self::emit_phase_progress(app, test_run_id, "Removing members", comp, total_http, &start_time, &error_count);
             idx += 1;
         }
@@ -721,22 +727,20 @@ impl LoadTestEngine {
                 if cancel.load(Ordering::Relaxed) { return None; }
 
                 let body = Self::generate_group_body(i);
-                let result = client.request(Method::POST, "/Groups", Some(&body)).await;
+                let (retries, result) = client.request_with_retries(Method::POST, "/Groups", Some(&body)).await;
                 let comp = completed.fetch_add(1, Ordering::Relaxed) + 1;
-                let load_result = Self::build_result(&run_id, i, "POST", "/Groups", Some(body), &result, &error_count);
+                let load_results = Self::build_results(&run_id, i, "POST", "/Groups", Some(body), &retries, &result, &error_count);
 
                 if let Ok(ref resp) = result {
                     if resp.status == 201 {
-                        if let Ok(json) = serde_json::from_str::<Value>(&resp.body) {
-                            if let Some(id) = json.get("id").and_then(|v| v.as_str()) {
-                                created_ids.lock().await.push(id.to_string());
-                            }
+                        if let Some(id) = scim::resource_id(&resp.body) {
+                            created_ids.lock().await.push(id);
                         }
                     }
                 }
 
                 Self::emit_phase_progress(&app, &run_id, "Creating groups", comp, total_http, &start_time, &error_count);
-                Some(load_result)
+                Some(load_results)
             }));
         }
 
@@ -763,15 +767,14 @@ impl LoadTestEngine {
                 if cancel.load(Ordering::Relaxed) { return None; }
 
                 let suffix = Self::random_suffix(6);
-                let body = serde_json::json!({
-                    "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
-                    "Operations": [{ "op": "replace", "path": "displayName", "value": format!("Updated_{}", suffix) }]
-                }).to_string();
-                let result = client.request(Method::PATCH, &path, Some(&body)).await;
+                let body = scim::to_body(&PatchOp::new(vec![
+                    PatchOperation::replace(Some("displayName"), Value::String(format!("Updated_{}", suffix))),
+                ]));
+                let (retries, result) = client.request_with_retries(Method::PATCH, &path, Some(&body)).await;
                 let comp = completed.fetch_add(1, Ordering::Relaxed) + 1;
-                let load_result = Self::build_result(&run_id, idx, "PATCH", &path, Some(body), &result, &error_count);
+                let load_results = Self::build_results(&run_id, idx, "PATCH", &path, Some(body), &retries, &result, &error_count);
                 Self::emit_phase_progress(&app, &run_id, "Updating groups", comp, total_http, &start_time, &error_count);
-                Some(load_result)
+                Some(load_results)
             }));
         }
         results.extend(Self::collect_results(update_handles).await);
@@ -782,6 +785,93 @@ impl LoadTestEngine {
         results
     }
 
+    /// Scenario: Bulk Import — POST /Bulk with batches of user creates, then cleanup.
+    /// `total_requests` counts users; each HTTP request carries one batch.
+    async fn scenario_bulk_import(
+        app: &AppHandle,
+        client: Arc<ScimClient>,
+        test_run_id: &str,
+        config: &LoadTestConfig,
+        cancel_flag: Arc<AtomicBool>,
+    ) -> Vec<LoadTestResult> {
+        let total_users = config.total_requests;
+        let batch_size = Self::bulk_batch_size(&client).await;
+        let total = total_users.div_ceil(batch_size);
+        let semaphore = Arc::new(Semaphore::new(config.concurrency));
+        let completed = Arc::new(AtomicUsize::new(0));
+        let error_count = Arc::new(AtomicUsize::new(0));
+        let created_ids: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+        let start_time = Instant::now();
+
+        let mut handles = Vec::new();
+
+        for i in 0..total {
+            if cancel_flag.load(Ordering::Relaxed) { break; }
+            Self::apply_ramp_up(config, i, total, &start_time).await;
+
+            let permit = semaphore.clone().acquire_owned().await.unwrap();
+            let client = client.clone();
+            let cancel = cancel_flag.clone();
+            let completed = completed.clone();
+            let error_count = error_count.clone();
+            let created_ids = created_ids.clone();
+            let app = app.clone();
+            let run_id = test_run_id.to_string();
+
+            handles.push(tokio::spawn(async move {
+                let _permit = permit;
+                if cancel.load(Ordering::Relaxed) { return None; }
+
+                let users = (i * batch_size..((i + 1) * batch_size).min(total_users))
+                    .map(|index| BulkOperation::post(&format!("user{}", index), "/Users", &Self::generate_user(index)))
+                    .collect();
+                let body = scim::to_body(&BulkRequest::new(users));
+                let (retries, result) = client.request_with_retries(Method::POST, "/Bulk", Some(&body)).await;
+                let comp = completed.fetch_add(1, Ordering::Relaxed) + 1;
+
+                let mut load_results = Self::build_results(&run_id, i, "POST", "/Bulk", Some(body), &retries, &result, &error_count);
+
+                // A 200 can still carry failed operations; count the batch as failed
+                if let Some(bulk) = result.as_ref().ok().filter(|r| r.status == 200).and_then(|r| r.bulk_response()) {
+                    let failed: Vec<_> = bulk.operations.iter().filter(|op| !op.is_success()).collect();
+                    if let (Some(first), Some(row)) = (failed.first(), load_results.last_mut()) {
+                        let status = first.status.map(|s| s.to_string()).unwrap_or_else(|| "no status".to_string());
+                        row.success = false;
+                        row.error_message = Some(format!("{} of {} operations failed (first: status {})", failed.len(), bulk.operations.len(), status));
+                        error_count.fetch_add(1, Ordering::Relaxed);
+                    }
+                    let mut ids = created_ids.lock().await;
+                    ids.extend(bulk.operations.iter().filter(|op| op.is_success()).filter_map(|op| op.resource_id()));
+                }
+
+                Self::emit_phase_progress(&app, &run_id, "Importing users", comp, total, &start_time, &error_count);
+                Some(load_results)
+            }));
+        }
+
+        let mut results = Self::collect_results(handles).await;
+
+        // Cleanup: delete all created users
+        let ids = created_ids.lock().await.clone();
+        Self::cleanup_users(app, &client, test_run_id, &ids, &cancel_flag, &mut results, total, &start_time).await;
+
+        results
+    }
+
+    /// Operations per /Bulk request: the server's maxOperations, capped so each
+    /// batch stays a realistic import chunk.
+    async fn bulk_batch_size(client: &ScimClient) -> usize {
+        let max_operations = match client.get("/ServiceProviderConfig").await {
+            Ok(resp) if resp.status == 200 => serde_json::from_str::<ServiceProviderConfig>(&resp.body).ok()
+                .and_then(|config| config.bulk?.max_operations),
+            _ => None,
+        };
+        match max_operations {
+            Some(max) if max > 0 => (max as usize).min(MAX_BULK_BATCH),
+            _ => DEFAULT_BULK_BATCH,
+        }
+    }
+
     // ── Cleanup ──
 
     #[allow(clippy::too_many_arguments)]
@@ -802,7 +892,8 @@ impl LoadTestEngine {
             if cancel_flag.load(Ordering::Relaxed) { break; }
 
             let path = format!("/Users/{}", user_id);
-            let del = client.request(Method::DELETE, &path, None).await;
+            let (retries, del) = client.request_with_retries(Method::DELETE, &path, None).await;
+            results.extend(Self::retry_rows(test_run_id, base_total + i, "DELETE", &path, &None, &retries));
 
             let success = match &del {
                 Ok(resp) => resp.status >= 200 && resp.status < 300,
@@ -824,8 +915,10 @@ impl LoadTestEngine {
                 status_code: del.as_ref().ok().map(|r| r.status as i32),
                 duration_ms,
                 success,
-                error_message: del.err(),
+                timing: del.as_ref().ok().map(|r| r.timing),
+                error_message: del.err().map(|e| e.to_string()),
                 timestamp: Utc::now().to_rfc3339(),
+                attempt: retries.len() as i64 + 1,
             });
 
             // Emit cleanup progress
@@ -864,7 +957,8 @@ impl LoadTestEngine {
         for (i, rid) in ids.iter().enumerate() {
             if cancel_flag.load(Ordering::Relaxed) { break; }
             let path = format!("{}/{}", resource_path, rid);
-            let del = client.request(Method::DELETE, &path, None).await;
+            let (retries, del) = client.request_with_retries(Method::DELETE, &path, None).await;
+            results.extend(Self::retry_rows(test_run_id, base_total + i, "DELETE", &path, &None, &retries));
             let success = match &del { Ok(resp) => resp.status >= 200 && resp.status < 300, Err(_) => false };
             let duration_ms = match &del { Ok(resp) => resp.duration_ms, Err(_) => 0 };
             results.push(LoadTestResult {
@@ -877,8 +971,10 @@ impl LoadTestEngine {
                 status_code: del.as_ref().ok().map(|r| r.status as i32),
                 duration_ms,
                 success,
-                error_message: del.err(),
+                timing: del.as_ref().ok().map(|r| r.timing),
+                error_message: del.err().map(|e| e.to_string()),
                 timestamp: Utc::now().to_rfc3339(),
+                attempt: retries.len() as i64 + 1,
             });
             if (i + 1) % 10 == 0 || i + 1 == cleanup_total {
                 let elapsed_secs = start_time.elapsed().as_secs_f64();
@@ -903,45 +999,30 @@ impl LoadTestEngine {
         let suffix = Self::random_suffix(8);
         let team_names = ["Engineering", "Marketing", "Sales", "Support", "Product", "Design", "DevOps", "QA", "Finance", "Legal"];
         let team = team_names[index % team_names.len()];
-        serde_json::json!({
-            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:Group"],
-            "displayName": format!("{} Team {} {}", team, suffix, index),
-            "members": []
-        }).to_string()
+        scim::to_body(&Group::new(&format!("{} Team {} {}", team, suffix, index)))
     }
 
     fn generate_user_body(index: usize) -> String {
+        scim::to_body(&Self::generate_user(index))
+    }
+
+    fn generate_user(index: usize) -> User {
         let suffix = Self::random_suffix(8);
         let username = format!("loadtest_{}_{:04}@test.example.com", suffix, index);
         let given = format!("Load{}", &suffix[..4]);
         let family = format!("Test{}", &suffix[4..]);
-        serde_json::json!({
-            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
-            "userName": username,
-            "name": {
-                "givenName": given,
-                "familyName": family
-            },
-            "emails": [{
-                "value": username,
-                "type": "work",
-                "primary": true
-            }],
-            "displayName": format!("{} {}", given, family),
-            "active": true
-        }).to_string()
+        User::new(&username)
+            .with_name(&given, &family)
+            .with_work_email(&username)
+            .with_display_name(&format!("{} {}", given, family))
+            .with_active(true)
     }
 
     fn generate_patch_body() -> String {
         let suffix = Self::random_suffix(6);
-        serde_json::json!({
-            "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
-            "Operations": [{
-                "op": "replace",
-                "path": "displayName",
-                "value": format!("Updated_{}", suffix)
-            }]
-        }).to_string()
+        scim::to_body(&PatchOp::new(vec![
+            PatchOperation::replace(Some("displayName"), Value::String(format!("Updated_{}", suffix))),
+        ]))
     }
 
     fn random_suffix(len: usize) -> String {
@@ -968,16 +1049,21 @@ impl LoadTestEngine {
         }
     }
 
-    fn build_result(
+    #[allow(clippy::too_many_arguments)]
+    fn build_results(
         run_id: &str,
         index: usize,
         method: &str,
         path: &str,
         body: Option<String>,
-        result: &Result<crate::scim_client::ScimResponse, String>,
+        retries: &[RetriedAttempt],
+        result: &crate::error::Result<crate::scim_client::ScimResponse>,
         error_count: &AtomicUsize,
-    ) -> LoadTestResult {
-        match result {
+    ) -> Vec<LoadTestResult> {
+        error_count.fetch_add(retries.len(), Ordering::Relaxed);
+        let mut rows = Self::retry_rows(run_id, index, method, path, &body, retries);
+        let attempt = rows.len() as i64 + 1;
+        rows.push(match result {
             Ok(resp) => {
                 let success = resp.status >= 200 && resp.status < 400;
                 if !success {
@@ -993,8 +1079,10 @@ impl LoadTestEngine {
                     status_code: Some(resp.status as i32),
                     duration_ms: resp.duration_ms,
                     success,
-                    error_message: if !success { Some(format!("Status {}", resp.status)) } else { None },
+                    error_message: if !success { Some(Self::status_message(resp)) } else { None },
                     timestamp: Utc::now().to_rfc3339(),
+                    attempt,
+                    timing: Some(resp.timing),
                 }
             }
             Err(e) => {
@@ -1009,11 +1097,48 @@ impl LoadTestEngine {
                     status_code: None,
                     duration_ms: 0,
                     success: false,
-                    error_message: Some(e.clone()),
+                    error_message: Some(e.to_string()),
                     timestamp: Utc::now().to_rfc3339(),
+                    attempt,
+                    timing: None,
                 }
             }
-        }
+        });
+        rows
+    }
+
+    /// One failed row per retried attempt, sharing the request's index so the
+    /// throttling shows up in the results and the summary.
+    fn retry_rows(
+        run_id: &str,
+        index: usize,
+        method: &str,
+        path: &str,
+        body: &Option<String>,
+        retries: &[RetriedAttempt],
+    ) -> Vec<LoadTestResult> {
+        retries.iter().enumerate().map(|(i, r)| {
+            let cause = match (&r.error, r.status) {
+                (Some(e), _) => e.clone(),
+                (None, Some(status)) => format!("Status {}", status),
+                (None, None) => "Request failed".to_string(),
+            };
+            LoadTestResult {
+                id: Uuid::new_v4().to_string(),
+                test_run_id: run_id.to_string(),
+                request_index: index as i64,
+                http_method: method.to_string(),
+                url: path.to_string(),
+                request_body: body.clone(),
+                status_code: r.status.map(|s| s as i32),
+                duration_ms: r.duration_ms,
+                success: false,
+                error_message: Some(format!("{} — retried after {} ms", cause, r.delay_ms)),
+                timestamp: Utc::now().to_rfc3339(),
+                attempt: i as i64 + 1,
+                timing: r.timing,
+            }
+        }).collect()
     }
 
     fn emit_phase_progress(
@@ -1039,14 +1164,14 @@ impl LoadTestEngine {
         }
     }
 
-    async fn collect_results(handles: Vec<tokio::task::JoinHandle<Option<LoadTestResult>>>) -> Vec<LoadTestResult> {
+    async fn collect_results(handles: Vec<tokio::task::JoinHandle<Option<Vec<LoadTestResult>>>>) -> Vec<LoadTestResult> {
         let mut results = Vec::new();
         for handle in handles {
-            if let Ok(Some(result)) = handle.await {
-                results.push(result);
+            if let Ok(Some(rows)) = handle.await {
+                results.extend(rows);
             }
         }
-        results.sort_by_key(|r| r.request_index);
+        results.sort_by_key(|r| (r.request_index, r.attempt));
         results
     }
 
@@ -1088,6 +1213,10 @@ impl LoadTestEngine {
             }
         }
 
+        // Every attempt after the first means the previous one was retried
+        let retried_attempts = results.iter().filter(|r| r.attempt > 1).count();
+        let throttled_responses = results.iter().filter(|r| r.status_code == Some(429)).count();
+
         LoadTestSummary {
             total_requests,
             successful,
@@ -1104,6 +1233,52 @@ impl LoadTestEngine {
             p99_latency_ms: p99,
             requests_per_second: rps,
             status_code_distribution: status_dist,
+            retried_attempts,
+            throttled_responses,
+            timing_breakdown: Self::timing_breakdown(results),
+        }
+    }
+
+    /// Phase percentiles over the results that got a response. DNS, connect and
+    /// TLS only count requests that opened a new connection; on pooled ones they
+    /// are zero and would drown out the real handshake times.
+    fn timing_breakdown(results: &[LoadTestResult]) -> Option<TimingBreakdown> {
+        let timings: Vec<&RequestTiming> = results.iter().filter_map(|r| r.timing.as_ref()).collect();
+        if timings.is_empty() {
+            return None;
+        }
+        let new_connections: Vec<&RequestTiming> = timings.iter().copied().filter(|t| !t.connection_reused).collect();
+        Some(TimingBreakdown {
+            new_connections: new_connections.len(),
+            dns: Self::phase_stats(new_connections.iter().map(|t| t.dns_us)),
+            connect: Self::phase_stats(new_connections.iter().map(|t| t.connect_us)),
+            tls: Self::phase_stats(new_connections.iter().map(|t| t.tls_us)),
+            ttfb: Self::phase_stats(timings.iter().map(|t| t.ttfb_us)),
+            download: Self::phase_stats(timings.iter().map(|t| t.download_us)),
+        })
+    }
+
+    fn phase_stats(values: impl Iterator<Item = i64>) -> PhaseStats {
+        let mut sorted: Vec<i64> = values.collect();
+        if sorted.is_empty() {
+            return PhaseStats::default();
+        }
+        sorted.sort();
+        PhaseStats {
+            avg_us: sorted.iter().sum::<i64>() as f64 / sorted.len() as f64,
+            p50_us: Self::percentile(&sorted, 50.0),
+            p90_us: Self::percentile(&sorted, 90.0),
+            p95_us: Self::percentile(&sorted, 95.0),
+            p99_us: Self::percentile(&sorted, 99.0),
+            max_us: *sorted.last().unwrap_or(&0),
+        }
+    }
+
+    /// "Status 409", plus the scimType when the server sent a SCIM error.
+    fn status_message(resp: &crate::scim_client::ScimResponse) -> String {
+        match resp.scim_error().and_then(|e| e.scim_type) {
+            Some(scim_type) => format!("Status {} ({})", resp.status, scim_type),
+            None => format!("Status {}", resp.status),
         }
     }
 
@@ -7,16 +7,84 @@ pub struct ServerConfig {
     pub id: String,
     pub name: String,
     pub base_url: String,
-    pub auth_type: String, // "bearer", "basic", "apikey"
+    pub auth_type: String, // "bearer", "basic", "apikey", "oauth2_client_credentials"
     pub auth_token: Option<String>,
     pub auth_username: Option<String>,
     pub auth_password: Option<String>,
     pub api_key_header: Option<String>,
     pub api_key_value: Option<String>,
+    pub oauth_token_url: Option<String>,
+    pub oauth_client_id: Option<String>,
+    pub oauth_client_secret: Option<String>,
+    pub oauth_scope: Option<String>,
+    pub oauth_audience: Option<String>,
+    pub client_cert_format: Option<String>, // "pem" or "pkcs12"
+    pub client_cert: Option<String>,        // PEM chain, or base64-encoded PKCS#12
+    pub client_key: Option<String>,         // PEM private key (when not bundled with the cert)
+    pub client_cert_password: Option<String>,
+    pub tls_mode: Option<String>,  // "strict" (default), "custom_ca", "insecure"
+    pub ca_bundle: Option<String>, // PEM CA certificates trusted in "custom_ca" mode
+    pub rate_limit_rps: Option<f64>,   // client-side request quota; None or 0 = unlimited
+    pub rate_limit_burst: Option<u32>, // requests allowed back-to-back (default 1)
+    pub connect_timeout_secs: Option<u32>, // default 10
+    pub read_timeout_secs: Option<u32>,    // max wait between reads of a response; default 30
+    pub request_timeout_secs: Option<u32>, // max time for a whole request, body included; default 120
+    pub proxy_url: Option<String>,         // HTTP(S) proxy for all traffic to this server
+    pub proxy_username: Option<String>,
+    pub proxy_password: Option<String>,
+    pub custom_headers: Option<Vec<HttpHeader>>, // sent with every request
     pub created_at: String,
     pub updated_at: String,
 }
 
+#[cfg(test)]
+impl ServerConfig {
+    /// A bearer-token config for `base_url` with every optional setting left empty.
+    pub fn for_tests(base_url: &str) -> Self {
+        ServerConfig {
+            id: "test".to_string(),
+            name: "test".to_string(),
+            base_url: base_url.to_string(),
+            auth_type: "bearer".to_string(),
+            auth_token: None,
+            auth_username: None,
+            auth_password: None,
+            api_key_header: None,
+            api_key_value: None,
+            oauth_token_url: None,
+            oauth_client_id: None,
+            oauth_client_secret: None,
+            oauth_scope: None,
+            oauth_audience: None,
+            client_cert_format: None,
+            client_cert: None,
+            client_key: None,
+            client_cert_password: None,
+            tls_mode: None,
+            ca_bundle: None,
+            rate_limit_rps: None,
+            rate_limit_burst: None,
+            connect_timeout_secs: None,
+            read_timeout_secs: None,
+            request_timeout_secs: None,
+            proxy_url: None,
+            proxy_username: None,
+            proxy_password: None,
+            custom_headers: None,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+}
+
+// ── Credential Encryption ──
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialProtection {
+    pub mode: String, // "key_file" or "passphrase"
+    pub locked: bool, // a passphrase is set but has not been entered this session
+}
+
 // ── Test Run ──
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -28,6 +96,9 @@ pub struct TestRun {
     pub started_at: String,
     pub completed_at: Option<String>,
     pub summary_json: Option<String>,
+    /// The server's /ServiceProviderConfig as parsed at the start of a validation run.
+    #[serde(default)]
+    pub capabilities_json: Option<String>,
 }
 
 // ── Validation ──
@@ -44,18 +115,180 @@ pub struct ValidationResult {
     pub response_status: Option<i32>,
     pub response_body: Option<String>,
     pub duration_ms: i64,
-    pub passed: bool,
+    pub timing: Option<RequestTiming>, // None when no response was received
+    pub passed: bool, // outcome is Passed or Warning; kept for older readers
+    #[serde(default)]
+    pub outcome: TestOutcome,
     pub failure_reason: Option<String>,
     pub executed_at: String,
+    #[serde(default)]
+    pub schema_violations: Vec<SchemaViolation>, // returned resources that contradict the server's /Schemas
+    #[serde(default)]
+    pub depends_on: Vec<String>, // tests in the same category that must pass for this one to count
+    #[serde(default)]
+    pub requirement_level: RequirementLevel,
+    #[serde(default)]
+    pub rfc_reference: Option<String>, // e.g. "RFC 7644 §3.5.2"; None for user-defined checks
+    #[serde(default)]
+    pub severity: Severity,
+}
+
+/// RFC 2119 keyword of the requirement a test checks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum RequirementLevel {
+    #[default]
+    Must,
+    Should,
+    May,
+}
+
+impl RequirementLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RequirementLevel::Must => "MUST",
+            RequirementLevel::Should => "SHOULD",
+            RequirementLevel::May => "MAY",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "MUST" => Some(RequirementLevel::Must),
+            "SHOULD" => Some(RequirementLevel::Should),
+            "MAY" => Some(RequirementLevel::May),
+            _ => None,
+        }
+    }
+}
+
+/// How much a failure of the test matters to a client integrating with the server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Critical,
+    High,
+    #[default]
+    Medium,
+    Low,
+}
+
+impl Severity {
+    /// Most severe first.
+    pub const ALL: [Severity; 4] = [Severity::Critical, Severity::High, Severity::Medium, Severity::Low];
+
+    /// Weight of a test in the weighted compliance score.
+    pub fn weight(&self) -> f64 {
+        match self {
+            Severity::Critical => 8.0,
+            Severity::High => 4.0,
+            Severity::Medium => 2.0,
+            Severity::Low => 1.0,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Critical => "critical",
+            Severity::High => "high",
+            Severity::Medium => "medium",
+            Severity::Low => "low",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "critical" => Some(Severity::Critical),
+            "high" => Some(Severity::High),
+            "medium" => Some(Severity::Medium),
+            "low" => Some(Severity::Low),
+            _ => None,
+        }
+    }
+}
+
+/// How a validation test ended.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestOutcome {
+    Passed,
+    #[default]
+    Failed,
+    /// Not run or not counted: a prerequisite did not pass, or the server does
+    /// not support the feature. Left out of the compliance score.
+    Skipped,
+    /// Passed with a finding: the response contradicts the server's own
+    /// `/Schemas`, or the server ignored a SHOULD. Counts as a pass in the
+    /// plain score but earns only half its weight in the weighted one.
+    Warning,
+    /// No usable response: the request could not be sent, or timed out.
+    Error,
+}
+
+impl TestOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TestOutcome::Passed => "passed",
+            TestOutcome::Failed => "failed",
+            TestOutcome::Skipped => "skipped",
+            TestOutcome::Warning => "warning",
+            TestOutcome::Error => "error",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "passed" => Some(TestOutcome::Passed),
+            "failed" => Some(TestOutcome::Failed),
+            "skipped" => Some(TestOutcome::Skipped),
+            "warning" => Some(TestOutcome::Warning),
+            "error" => Some(TestOutcome::Error),
+            _ => None,
+        }
+    }
+
+    pub fn is_pass(&self) -> bool {
+        matches!(self, TestOutcome::Passed | TestOutcome::Warning)
+    }
+
+    /// Share of a test's severity weight it earns in the weighted score.
+    pub fn credit(&self) -> f64 {
+        match self {
+            TestOutcome::Passed => 1.0,
+            TestOutcome::Warning => 0.5,
+            _ => 0.0,
+        }
+    }
+}
+
+/// A test that only passes or fails.
+impl From<bool> for TestOutcome {
+    fn from(passed: bool) -> Self {
+        if passed { TestOutcome::Passed } else { TestOutcome::Failed }
+    }
+}
+
+/// A returned attribute that does not match its definition in `/Schemas`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaViolation {
+    pub attribute: String, // "emails[0].type", "urn:...:enterprise:2.0:User:manager.value"
+    pub rule: String,      // "type", "required", "multiValued", "canonicalValues", "mutability", "returned", "caseExact"
+    pub message: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationSummary {
     pub total: usize,
-    pub passed: usize,
-    pub failed: usize,
+    pub passed: usize, // includes warnings
+    pub failed: usize, // includes errors
     pub skipped: usize,
+    #[serde(default)]
+    pub warnings: usize,
+    #[serde(default)]
+    pub errors: usize,
     pub compliance_score: f64,
+    #[serde(default)]
+    pub weighted_score: f64, // compliance_score with each test weighted by its severity
     pub duration_ms: i64,
     pub categories: Vec<CategorySummary>,
 }
@@ -66,6 +299,8 @@ pub struct CategorySummary {
     pub total: usize,
     pub passed: usize,
     pub failed: usize,
+    #[serde(default)]
+    pub skipped: usize,
 }
 
 // ── Load Test ──
@@ -79,6 +314,9 @@ pub struct LoadTestConfig {
     pub total_requests: usize,
     pub concurrency: usize,
     pub ramp_up_seconds: Option<u64>,
+    pub retry_policy: Option<RetryPolicy>,
+    pub target_rps: Option<f64>,  // overrides the server's rate limit for this run
+    pub capture_traffic: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -101,6 +339,8 @@ pub struct LoadTestResult {
     pub success: bool,
     pub error_message: Option<String>,
     pub timestamp: String,
+    pub attempt: i64, // 1 for the first try; retries of the same request share its request_index
+    pub timing: Option<RequestTiming>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -120,6 +360,99 @@ pub struct LoadTestSummary {
     pub p99_latency_ms: i64,
     pub requests_per_second: f64,
     pub status_code_distribution: std::collections::HashMap<i32, usize>,
+    pub retried_attempts: usize,     // attempts that were followed by a retry
+    pub throttled_responses: usize,  // 429 responses, retried or not
+    #[serde(default)]
+    pub timing_breakdown: Option<TimingBreakdown>,
+}
+
+// ── Request Timing ──
+
+/// Where the time of one request went, in microseconds. DNS, connect and TLS
+/// are zero when the request reused a pooled connection.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct RequestTiming {
+    pub dns_us: i64,
+    pub connect_us: i64,  // TCP connect (and proxy tunnel setup)
+    pub tls_us: i64,      // TLS handshake
+    pub ttfb_us: i64,     // from connection ready until response headers arrived
+    pub download_us: i64, // reading the response body
+    pub total_us: i64,
+    pub connection_reused: bool,
+}
+
+/// Percentiles for each phase of the requests in a load test. Connection
+/// phases only count requests that opened a new connection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimingBreakdown {
+    pub new_connections: usize,
+    pub dns: PhaseStats,
+    pub connect: PhaseStats,
+    pub tls: PhaseStats,
+    pub ttfb: PhaseStats,
+    pub download: PhaseStats,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PhaseStats {
+    pub avg_us: f64,
+    pub p50_us: i64,
+    pub p90_us: i64,
+    pub p95_us: i64,
+    pub p99_us: i64,
+    pub max_us: i64,
+}
+
+// ── Retry Policy ──
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,          // total tries including the first
+    pub initial_backoff_ms: u64,    // doubled after every attempt
+    pub max_backoff_ms: u64,        // also caps Retry-After
+    pub jitter: bool,               // randomize each delay between 50% and 100%
+    pub respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    /// A single attempt, no retries.
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 30_000,
+            jitter: true,
+            respect_retry_after: true,
+        }
+    }
+}
+
+// ── HTTP Traffic Capture ──
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpHeader {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpExchange {
+    pub id: String,
+    pub test_run_id: String,
+    pub sequence: i64, // order in which requests were sent
+    pub started_at: String,
+    pub method: String,
+    pub url: String,
+    pub request_headers: Vec<HttpHeader>, // secrets redacted
+    pub request_body: Option<String>,
+    pub response_status: Option<i32>,    // None when the request failed without a response
+    pub response_status_text: Option<String>,
+    pub http_version: Option<String>,
+    pub response_headers: Vec<HttpHeader>,
+    pub response_body: Option<String>,
+    pub duration_ms: i64,
+    pub error: Option<String>,
+    pub timing: Option<RequestTiming>,
 }
 
 // ── Validation Run Config ──
@@ -131,6 +464,8 @@ pub struct ValidationRunConfig {
     pub field_mapping_rules: Option<Vec<FieldMappingRule>>,
     pub user_joining_property: Option<String>,   // e.g. "userName" (default)
     pub group_joining_property: Option<String>,  // e.g. "displayName" (default)
+    pub retry_policy: Option<RetryPolicy>,
+    pub capture_traffic: Option<bool>,
 }
 
 // ── IPC Events ──
@@ -160,7 +495,7 @@ pub struct LoadTestProgress {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExportRequest {
     pub test_run_id: String,
-    pub format: String, // "pdf", "csv", "json"
+    pub format: String, // "pdf", "csv", "json", "excel", "har"
     pub output_path: String,
 }
 
@@ -172,6 +507,7 @@ pub struct TestConnectionResult {
     pub status_code: Option<u16>,
     pub response_body: Option<String>,
     pub error: Option<String>,
+    pub error_code: Option<String>, // an `AppError` code, or "scim" for a SCIM error response
     pub duration_ms: i64,
 }
 
@@ -219,6 +555,7 @@ pub struct ExplorerResponse {
     pub headers: std::collections::HashMap<String, String>,
     pub body: String,
     pub duration_ms: i64,
+    pub timing: RequestTiming,
     pub request_url: String,
 }
 
@@ -0,0 +1,229 @@
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+use crate::error::{AppError, Result};
+use crate::models::ServerConfig;
+
+/// Tokens are refreshed this long before they actually expire so that a
+/// request never leaves with a token that dies in flight.
+const EXPIRY_SKEW: Duration = Duration::from_secs(30);
+
+/// Lifetime assumed when the token endpoint omits `expires_in`.
+const DEFAULT_TOKEN_LIFETIME: Duration = Duration::from_secs(3600);
+
+struct CachedToken {
+    access_token: String,
+    refresh_at: Instant,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct TokenErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// OAuth 2.0 client-credentials grant (RFC 6749 §4.4) with an in-memory token cache.
+///
+/// The cache lives behind an async mutex, so when many load-test workers find the
+/// token expired at the same time only one of them hits the token endpoint and the
+/// rest wait for its result.
+pub struct OAuthTokenProvider {
+    http: Client,
+    token_url: String,
+    client_id: String,
+    client_secret: String,
+    scope: Option<String>,
+    audience: Option<String>,
+    cached: Mutex<Option<CachedToken>>,
+}
+
+impl OAuthTokenProvider {
+    pub fn from_config(config: &ServerConfig, http: Client) -> Result<Self> {
+        let required = |value: &Option<String>, what: &str| non_empty(value)
+            .ok_or_else(|| AppError::Config(format!("OAuth 2.0 {} is required", what)));
+        let token_url = required(&config.oauth_token_url, "token URL")?;
+        let client_id = required(&config.oauth_client_id, "client ID")?;
+        let client_secret = required(&config.oauth_client_secret, "client secret")?;
+
+        Ok(OAuthTokenProvider {
+            http,
+            token_url,
+            client_id,
+            client_secret,
+            scope: non_empty(&config.oauth_scope),
+            audience: non_empty(&config.oauth_audience),
+            cached: Mutex::new(None),
+        })
+    }
+
+    /// Return a valid access token, fetching a new one if the cached token is
+    /// missing or about to expire.
+    pub async fn access_token(&self) -> Result<String> {
+        let mut cached = self.cached.lock().await;
+        if let Some(ref token) = *cached {
+            if Instant::now() < token.refresh_at {
+                return Ok(token.access_token.clone());
+            }
+        }
+
+        let fresh = self.fetch_token().await?;
+        let access_token = fresh.access_token.clone();
+        *cached = Some(fresh);
+        Ok(access_token)
+    }
+
+    /// Drop the cached token after the server rejected it, unless another task
+    /// has already replaced it with a newer one.
+    pub async fn invalidate(&self, rejected_token: &str) {
+        let mut cached = self.cached.lock().await;
+        if cached.as_ref().is_some_and(|t| t.access_token == rejected_token) {
+            *cached = None;
+        }
+    }
+
+    async fn fetch_token(&self) -> Result<CachedToken> {
+        let mut form: Vec<(&str, &str)> = vec![
+            ("grant_type", "client_credentials"),
+            ("client_id", &self.client_id),
+            ("client_secret", &self.client_secret),
+        ];
+        if let Some(ref scope) = self.scope {
+            form.push(("scope", scope));
+        }
+        if let Some(ref audience) = self.audience {
+            form.push(("audience", audience));
+        }
+
+        let requested_at = Instant::now();
+        let response = self.http.post(&self.token_url)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| AppError::request("OAuth token request failed", &e))?;
+
+        let status = response.status().as_u16();
+        let body = response.text().await
+            .map_err(|e| AppError::request("Failed to read OAuth token response", &e))?;
+
+        if status != 200 {
+            return Err(AppError::Auth(match serde_json::from_str::<TokenErrorResponse>(&body) {
+                Ok(err) => format!(
+                    "OAuth token endpoint returned {} ({}){}",
+                    status,
+                    err.error,
+                    err.error_description.map(|d| format!(": {}", d)).unwrap_or_default()
+                ),
+                Err(_) => format!("OAuth token endpoint returned {}: {}", status, body),
+            }));
+        }
+
+        let token: TokenResponse = serde_json::from_str(&body)
+            .map_err(|e| AppError::Auth(format!("Invalid OAuth token response: {}", e)))?;
+
+        let lifetime = token.expires_in.map(Duration::from_secs).unwrap_or(DEFAULT_TOKEN_LIFETIME);
+        // Very short-lived tokens would otherwise be refreshed on every request
+        let skew = EXPIRY_SKEW.min(lifetime / 2);
+
+        Ok(CachedToken {
+            access_token: token.access_token,
+            refresh_at: requested_at + lifetime - skew,
+        })
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value.as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scim_client::ScimClient;
+    use crate::test_server;
+    use reqwest::Method;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Default)]
+    struct Hits {
+        token: AtomicUsize,
+        scim: AtomicUsize,
+    }
+
+    /// A local server that issues `token-1`, `token-2`, ... from `/token` and
+    /// answers SCIM requests with 401 for `token-1` and 200 for any later token.
+    async fn serve(expires_in: u64) -> (String, Arc<Hits>) {
+        let hits = Arc::new(Hits::default());
+        let server_hits = hits.clone();
+        let base_url = test_server::serve(move |request| {
+            if request.method == "POST" && request.path == "/token" {
+                let n = server_hits.token.fetch_add(1, Ordering::SeqCst) + 1;
+                (200, format!(r#"{{"access_token":"token-{}","expires_in":{}}}"#, n, expires_in))
+            } else {
+                server_hits.scim.fetch_add(1, Ordering::SeqCst);
+                match request.header("authorization") {
+                    Some("Bearer token-1") => (401, String::new()),
+                    _ => (200, "{}".to_string()),
+                }
+            }
+        }).await;
+        (base_url, hits)
+    }
+
+    fn config(base_url: &str) -> ServerConfig {
+        ServerConfig {
+            auth_type: "oauth2_client_credentials".to_string(),
+            oauth_token_url: Some(format!("{}/token", base_url)),
+            oauth_client_id: Some("client".to_string()),
+            oauth_client_secret: Some("secret".to_string()),
+            ..ServerConfig::for_tests(base_url)
+        }
+    }
+
+    #[tokio::test]
+    async fn caches_token_across_calls() {
+        let (base_url, hits) = serve(3600).await;
+        let provider = OAuthTokenProvider::from_config(&config(&base_url), Client::new()).unwrap();
+
+        assert_eq!(provider.access_token().await.unwrap(), "token-1");
+        assert_eq!(provider.access_token().await.unwrap(), "token-1");
+        assert_eq!(hits.token.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn refreshes_token_before_it_expires() {
+        // A 2s token gets a 1s skew, so it is refreshed after 1s rather than 2s
+        let (base_url, hits) = serve(2).await;
+        let provider = OAuthTokenProvider::from_config(&config(&base_url), Client::new()).unwrap();
+
+        assert_eq!(provider.access_token().await.unwrap(), "token-1");
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(provider.access_token().await.unwrap(), "token-1");
+        tokio::time::sleep(Duration::from_millis(700)).await;
+        assert_eq!(provider.access_token().await.unwrap(), "token-2");
+        assert_eq!(hits.token.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn scim_401_refetches_token_and_retries_once() {
+        let (base_url, hits) = serve(3600).await;
+        let client = ScimClient::new(&config(&base_url)).unwrap();
+
+        let response = client.request(Method::GET, "/Users", None).await.unwrap();
+        assert_eq!(response.status, 200);
+        assert_eq!(hits.token.load(Ordering::SeqCst), 2);
+        assert_eq!(hits.scim.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,382 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::marker::PhantomData;
+
+use crate::error::{AppError, Result};
+use crate::filter::encode_query_value;
+use crate::scim::{ListResponse, ServiceProviderConfig};
+use crate::scim_client::ScimClient;
+
+const DEFAULT_PAGE_SIZE: u64 = 100;
+const DEFAULT_MAX_PAGES: usize = 1000;
+
+/// How the next page is requested.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Position {
+    /// `startIndex`/`count` (RFC 7644 §3.4.2.4), 1-based.
+    Index(u64),
+    /// `cursor`/`count` (RFC 9865). `None` asks for the first page.
+    Cursor(Option<String>),
+}
+
+/// One page of a collection.
+pub struct Page<T> {
+    /// The request path, with paging parameters.
+    pub path: String,
+    pub resources: Vec<T>,
+    pub total_results: Option<u64>,
+    pub duration_ms: i64,
+}
+
+/// Walks a collection (`/Users`, `/Groups?filter=...`) page by page.
+///
+/// Servers get paging wrong in several ways: ignoring `startIndex` and sending
+/// the first page forever, ignoring `count`, reporting a stale `totalResults`,
+/// or handing out a cursor they already sent. The pager only advances by what
+/// actually arrived, drops resources it has already seen, and stops as soon as
+/// a page brings nothing new, so none of these can make it loop.
+pub struct Pager<'a, T> {
+    client: &'a ScimClient,
+    path: String,
+    page_size: u64,
+    max_pages: usize,
+    position: Position,
+    seen_ids: HashSet<String>,
+    seen_cursors: HashSet<String>,
+    pages: usize,
+    total_results: Option<u64>,
+    done: bool,
+    truncated: bool,
+    _resource: PhantomData<T>,
+}
+
+impl<'a, T: for<'de> Deserialize<'de>> Pager<'a, T> {
+    pub fn new(client: &'a ScimClient, path: &str) -> Self {
+        Pager {
+            client,
+            path: path.to_string(),
+            page_size: DEFAULT_PAGE_SIZE,
+            max_pages: DEFAULT_MAX_PAGES,
+            position: Position::Index(1),
+            seen_ids: HashSet::new(),
+            seen_cursors: HashSet::new(),
+            pages: 0,
+            total_results: None,
+            done: false,
+            truncated: false,
+            _resource: PhantomData,
+        }
+    }
+
+    pub fn page_size(mut self, page_size: u64) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Stop after this many pages even if the server reports more.
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = max_pages.max(1);
+        self
+    }
+
+    /// Use cursor pagination (RFC 9865) instead of `startIndex`.
+    pub fn cursor(mut self) -> Self {
+        self.position = Position::Cursor(None);
+        self
+    }
+
+    /// Follow what the server advertises: cursor pagination when it is the
+    /// default (or the only method), and a page size within its limits.
+    pub fn for_server(mut self, config: &ServiceProviderConfig) -> Self {
+        if let Some(ref pagination) = config.pagination {
+            let cursor_default = pagination.default_pagination_method.as_deref()
+                .is_some_and(|m| m.eq_ignore_ascii_case("cursor"));
+            if pagination.cursor && (cursor_default || !pagination.index) {
+                self.position = Position::Cursor(None);
+            }
+            if let Some(max) = pagination.max_page_size.filter(|&max| max > 0) {
+                self.page_size = self.page_size.min(max);
+            }
+        }
+        if let Some(max) = config.filter.as_ref().and_then(|f| f.max_results).filter(|&max| max > 0) {
+            self.page_size = self.page_size.min(max);
+        }
+        self
+    }
+
+    /// `totalResults` from the most recent page.
+    pub fn total_results(&self) -> Option<u64> {
+        self.total_results
+    }
+
+    /// Whether the walk stopped at `max_pages` with more results pending.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// The next page, or `None` once the collection is exhausted. An error ends
+    /// the walk.
+    pub async fn next_page(&mut self) -> Option<Result<Page<T>>> {
+        if self.done {
+            return None;
+        }
+        if self.pages >= self.max_pages {
+            self.done = true;
+            self.truncated = true;
+            return None;
+        }
+        self.pages += 1;
+
+        let path = self.page_path();
+        let resp = match self.client.get(&path).await {
+            Ok(resp) => resp,
+            Err(e) => return Some(Err(self.fail(e))),
+        };
+        if resp.status != 200 {
+            let error = match resp.scim_error() {
+                Some(e) => AppError::Scim(e),
+                None => AppError::Other(format!("GET {} returned status {}", path, resp.status)),
+            };
+            return Some(Err(self.fail(error)));
+        }
+        let list = match ListResponse::<Value>::parse_lenient(&resp.body) {
+            Ok(list) => list,
+            Err(e) => return Some(Err(self.fail(AppError::Other(format!("GET {} did not return a ListResponse: {}", path, e))))),
+        };
+
+        self.total_results = list.total_results;
+        let returned = list.resources().len() as u64;
+        let next_cursor = list.next_cursor.clone();
+        // A server that ignores startIndex answers every request from the top
+        let ignored_start = matches!(self.position, Position::Index(start) if start > 1 && list.start_index.is_some_and(|s| s < start));
+
+        // Keep only resources not seen on an earlier page
+        let mut fresh = Vec::new();
+        for resource in list.into_resources() {
+            let id = match resource.get("id") {
+                Some(Value::String(id)) => Some(id.clone()),
+                Some(Value::Number(id)) => Some(id.to_string()),
+                _ => None,
+            };
+            if let Some(id) = id {
+                if !self.seen_ids.insert(id) {
+                    continue;
+                }
+            }
+            fresh.push(resource);
+        }
+
+        self.position = match self.position {
+            Position::Index(start) => {
+                let next = start + returned;
+                let past_end = self.total_results.is_some_and(|total| next > total);
+                // Without totalResults, a short page is the last one
+                let short = self.total_results.is_none() && returned < self.page_size;
+                if returned == 0 || past_end || short || ignored_start {
+                    self.done = true;
+                }
+                Position::Index(next)
+            }
+            Position::Cursor(_) => match next_cursor {
+                Some(cursor) if !cursor.is_empty() && self.seen_cursors.insert(cursor.clone()) => Position::Cursor(Some(cursor)),
+                _ => {
+                    self.done = true;
+                    Position::Cursor(None)
+                }
+            },
+        };
+        if fresh.is_empty() {
+            // An empty page, or one the server already sent
+            self.done = true;
+            return None;
+        }
+
+        let resources = match fresh.into_iter().map(serde_json::from_value).collect::<serde_json::Result<Vec<T>>>() {
+            Ok(resources) => resources,
+            Err(e) => return Some(Err(self.fail(AppError::Other(format!("GET {} returned an unreadable resource: {}", path, e))))),
+        };
+        Some(Ok(Page { path, resources, total_results: self.total_results, duration_ms: resp.duration_ms }))
+    }
+
+    /// Every remaining resource, up to `max_pages`.
+    pub async fn collect_all(mut self) -> Result<Vec<T>> {
+        let mut all = Vec::new();
+        while let Some(page) = self.next_page().await {
+            all.extend(page?.resources);
+        }
+        Ok(all)
+    }
+
+    fn page_path(&self) -> String {
+        let separator = if self.path.contains('?') { '&' } else { '?' };
+        match self.position {
+            Position::Index(start) => format!("{}{}startIndex={}&count={}", self.path, separator, start, self.page_size),
+            Position::Cursor(None) => format!("{}{}cursor&count={}", self.path, separator, self.page_size),
+            Position::Cursor(Some(ref cursor)) => {
+                format!("{}{}cursor={}&count={}", self.path, separator, encode_query_value(cursor), self.page_size)
+            }
+        }
+    }
+
+    fn fail(&mut self, error: AppError) -> AppError {
+        self.done = true;
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ServerConfig;
+    use crate::test_server;
+    use serde_json::json;
+    use std::sync::{Arc, Mutex};
+
+    /// A local server that answers every GET with `handler(path)` and records
+    /// the paths it was asked for.
+    async fn serve(handler: impl Fn(&str) -> Value + Send + Sync + 'static) -> (ScimClient, Arc<Mutex<Vec<String>>>) {
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let server_requested = requested.clone();
+        let base_url = test_server::serve(move |request| {
+            let body = handler(&request.path).to_string();
+            server_requested.lock().unwrap().push(request.path);
+            (200, body)
+        }).await;
+        (ScimClient::new(&ServerConfig::for_tests(&base_url)).unwrap(), requested)
+    }
+
+    fn param(path: &str, name: &str) -> Option<u64> {
+        let query = path.split_once('?')?.1;
+        query.split('&').find_map(|pair| pair.strip_prefix(name)?.strip_prefix('=')?.parse().ok())
+    }
+
+    fn users(ids: impl IntoIterator<Item = u64>) -> Vec<Value> {
+        ids.into_iter().map(|id| json!({ "id": id.to_string() })).collect()
+    }
+
+    /// A collection of `total` users that honours `startIndex` and `count`.
+    fn index_paging(total: u64) -> impl Fn(&str) -> Value {
+        move |path| {
+            let start = param(path, "startIndex").unwrap_or(1).max(1);
+            let count = param(path, "count").unwrap_or(total);
+            let end = (start + count).min(total + 1);
+            json!({ "totalResults": total, "startIndex": start, "Resources": users(start..end.max(start)) })
+        }
+    }
+
+    fn ids(resources: &[Value]) -> Vec<&str> {
+        resources.iter().map(|r| r["id"].as_str().unwrap()).collect()
+    }
+
+    #[tokio::test]
+    async fn walks_to_total_results_and_no_further() {
+        let (client, requested) = serve(index_paging(5)).await;
+        let all = client.paginate::<Value>("/Users").page_size(2).collect_all().await.unwrap();
+
+        assert_eq!(ids(&all), vec!["1", "2", "3", "4", "5"]);
+        assert_eq!(*requested.lock().unwrap(), vec![
+            "/Users?startIndex=1&count=2",
+            "/Users?startIndex=3&count=2",
+            "/Users?startIndex=5&count=2",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn total_results_of_zero_yields_nothing() {
+        let (client, requested) = serve(index_paging(0)).await;
+        let mut pager = client.paginate::<Value>("/Users");
+        assert!(pager.next_page().await.is_none());
+        assert!(pager.next_page().await.is_none());
+        assert_eq!(pager.total_results(), Some(0));
+        assert_eq!(requested.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn appends_to_an_existing_query() {
+        let (client, requested) = serve(index_paging(1)).await;
+        client.paginate::<Value>("/Users?filter=active%20eq%20true").collect_all().await.unwrap();
+        assert_eq!(requested.lock().unwrap()[0], "/Users?filter=active%20eq%20true&startIndex=1&count=100");
+    }
+
+    #[tokio::test]
+    async fn advances_by_what_arrived_when_count_is_ignored() {
+        // Always sends three, whatever count asked for
+        let (client, requested) = serve(|path: &str| {
+            let start = param(path, "startIndex").unwrap();
+            json!({ "totalResults": 7, "startIndex": start, "Resources": users(start..(start + 3).min(8)) })
+        }).await;
+        let all = client.paginate::<Value>("/Users").page_size(2).collect_all().await.unwrap();
+
+        assert_eq!(all.len(), 7);
+        let starts: Vec<u64> = requested.lock().unwrap().iter().map(|p| param(p, "startIndex").unwrap()).collect();
+        assert_eq!(starts, vec![1, 4, 7]);
+    }
+
+    #[tokio::test]
+    async fn stops_when_start_index_is_ignored() {
+        let (client, requested) = serve(|_: &str| json!({ "totalResults": 10, "startIndex": 1, "Resources": users(1..=2) })).await;
+        let all = client.paginate::<Value>("/Users").page_size(2).collect_all().await.unwrap();
+
+        assert_eq!(ids(&all), vec!["1", "2"]);
+        assert_eq!(requested.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn stops_at_an_empty_page_despite_an_overstated_total() {
+        let (client, requested) = serve(|path: &str| {
+            let start = param(path, "startIndex").unwrap();
+            let resources = if start == 1 { users(1..=2) } else { Vec::new() };
+            json!({ "totalResults": 1000, "Resources": resources })
+        }).await;
+        let all = client.paginate::<Value>("/Users").page_size(2).collect_all().await.unwrap();
+
+        assert_eq!(all.len(), 2);
+        assert_eq!(requested.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn short_page_ends_the_walk_without_total_results() {
+        let (client, requested) = serve(|path: &str| {
+            let start = param(path, "startIndex").unwrap();
+            json!({ "Resources": users(start..(start + 2).min(4)) })
+        }).await;
+        let all = client.paginate::<Value>("/Users").page_size(2).collect_all().await.unwrap();
+
+        assert_eq!(ids(&all), vec!["1", "2", "3"]);
+        assert_eq!(requested.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn max_pages_truncates() {
+        let (client, requested) = serve(index_paging(10)).await;
+        let mut pager = client.paginate::<Value>("/Users").page_size(2).max_pages(2);
+        let mut pages = 0;
+        while let Some(page) = pager.next_page().await {
+            page.unwrap();
+            pages += 1;
+        }
+
+        assert_eq!(pages, 2);
+        assert!(pager.is_truncated());
+        assert_eq!(requested.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn cursor_paging_stops_on_a_repeated_cursor() {
+        let (client, requested) = serve(|path: &str| {
+            if path.contains("cursor=") {
+                json!({ "Resources": users(3..=4), "nextCursor": "page 2" })
+            } else {
+                json!({ "Resources": users(1..=2), "nextCursor": "page 2" })
+            }
+        }).await;
+        let all = client.paginate::<Value>("/Users").cursor().page_size(2).collect_all().await.unwrap();
+
+        assert_eq!(ids(&all), vec!["1", "2", "3", "4"]);
+        assert_eq!(*requested.lock().unwrap(), vec![
+            "/Users?cursor&count=2",
+            "/Users?cursor=page%202&count=2",
+        ]);
+    }
+}
@@ -0,0 +1,109 @@
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Token-bucket rate limiter shared by every request sent through one `ScimClient`.
+///
+/// Callers reserve a token up front, so the bucket may go negative; each caller then
+/// sleeps until its own token has been refilled. This keeps callers in arrival order
+/// without waking and re-checking in a loop.
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    /// `None` when `requests_per_second` is not positive (no limit).
+    pub fn new(requests_per_second: f64, burst: u32) -> Option<Self> {
+        if !(requests_per_second > 0.0 && requests_per_second.is_finite()) {
+            return None;
+        }
+        let burst = burst.max(1) as f64;
+        Some(RateLimiter {
+            rate: requests_per_second,
+            burst,
+            bucket: Mutex::new(Bucket { tokens: burst, refilled_at: Instant::now() }),
+        })
+    }
+
+    /// Wait until a request may be sent.
+    pub async fn acquire(&self) {
+        let wait = {
+            let mut bucket = self.bucket.lock().await;
+            let now = Instant::now();
+            let refill = now.duration_since(bucket.refilled_at).as_secs_f64() * self.rate;
+            bucket.tokens = (bucket.tokens + refill).min(self.burst);
+            bucket.refilled_at = now;
+            bucket.tokens -= 1.0;
+            if bucket.tokens >= 0.0 {
+                return;
+            }
+            Duration::from_secs_f64(-bucket.tokens / self.rate)
+        };
+        tokio::time::sleep(wait).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn no_limit_without_a_positive_rate() {
+        assert!(RateLimiter::new(0.0, 5).is_none());
+        assert!(RateLimiter::new(-1.0, 5).is_none());
+        assert!(RateLimiter::new(f64::NAN, 5).is_none());
+        assert!(RateLimiter::new(f64::INFINITY, 5).is_none());
+    }
+
+    #[tokio::test]
+    async fn burst_goes_out_without_waiting() {
+        let limiter = RateLimiter::new(1.0, 3).unwrap();
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn zero_burst_still_allows_one_request() {
+        let limiter = RateLimiter::new(1.0, 0).unwrap();
+        let start = Instant::now();
+        limiter.acquire().await;
+        assert!(start.elapsed() < Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn waiting_callers_drive_the_bucket_negative_and_leave_in_order() {
+        let limiter = Arc::new(RateLimiter::new(10.0, 1).unwrap());
+        let start = Instant::now();
+        let order = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut callers = Vec::new();
+        for i in 0..3 {
+            let (limiter, order) = (limiter.clone(), order.clone());
+            callers.push(tokio::spawn(async move {
+                limiter.acquire().await;
+                order.lock().unwrap().push(i);
+            }));
+            tokio::task::yield_now().await;
+        }
+
+        // All three reserved a token up front: one from the bucket, two owed
+        let tokens = limiter.bucket.lock().await.tokens;
+        assert!((-2.0..-1.5).contains(&tokens), "{}", tokens);
+
+        for caller in callers {
+            caller.await.unwrap();
+        }
+        let elapsed = start.elapsed();
+        assert!(elapsed >= Duration::from_millis(190) && elapsed < Duration::from_millis(300), "{:?}", elapsed);
+        assert_eq!(*order.lock().unwrap(), vec![0, 1, 2]);
+    }
+}
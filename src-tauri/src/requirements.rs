@@ -0,0 +1,37 @@
+//! What each validation test checks: the RFC 2119 requirement level, the
+//! section of RFC 7643/7644 that states it, and how badly a failure breaks
+//! clients.
+//!
+//! A SHOULD that a server ignores is a finding, not a defect on par with a
+//! broken create, so results carry this alongside pass/fail and the weighted
+//! compliance score counts each test by its severity. Each test declares its
+//! requirement where it is defined.
+
+use crate::models::{RequirementLevel, Severity};
+
+/// The requirement a validation test checks.
+#[derive(Debug, Clone, Copy)]
+pub struct Requirement {
+    pub level: RequirementLevel,
+    pub section: Option<&'static str>, // None when the check is not from an RFC
+    pub severity: Severity,
+}
+
+impl Requirement {
+    pub const fn must(section: &'static str, severity: Severity) -> Self {
+        Self { level: RequirementLevel::Must, section: Some(section), severity }
+    }
+
+    pub const fn should(section: &'static str, severity: Severity) -> Self {
+        Self { level: RequirementLevel::Should, section: Some(section), severity }
+    }
+
+    pub const fn may(section: &'static str, severity: Severity) -> Self {
+        Self { level: RequirementLevel::May, section: Some(section), severity }
+    }
+
+    /// A check of the user's own, such as a field-mapping rule, that no RFC states.
+    pub const fn unreferenced(severity: Severity) -> Self {
+        Self { level: RequirementLevel::Must, section: None, severity }
+    }
+}
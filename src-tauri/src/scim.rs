@@ -0,0 +1,973 @@
+//! Typed SCIM 2.0 resources and protocol messages (RFC 7643 / RFC 7644).
+//!
+//! Deserialization is deliberately lenient: servers in the wild send lowercase
+//! `resources`, capitalized schema keys, numeric ids, `"True"` for booleans and
+//! omit attributes the RFC marks as required. Attributes that are not modelled here, including schema
+//! extensions, are kept in `extra` and written back out unchanged.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+
+use crate::filter::{encode_query_value, Filter};
+
+/// The SCIM media type (RFC 7644 §8.1).
+pub const SCIM_MEDIA_TYPE: &str = "application/scim+json";
+
+pub const USER_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+pub const GROUP_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:Group";
+pub const ENTERPRISE_USER_SCHEMA: &str = "urn:ietf:params:scim:schemas:extension:enterprise:2.0:User";
+pub const LIST_RESPONSE_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+pub const PATCH_OP_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:PatchOp";
+pub const SEARCH_REQUEST_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:SearchRequest";
+pub const ERROR_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+pub const BULK_REQUEST_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:BulkRequest";
+pub const BULK_RESPONSE_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:BulkResponse";
+
+/// Core schemas, whose attributes are covered by the built-in test categories.
+pub const CORE_SCHEMA_PREFIXES: [&str; 2] = [
+    "urn:ietf:params:scim:schemas:core:2.0:",
+    "urn:ietf:params:scim:api:messages:2.0:",
+];
+
+/// Serialize a resource or message as a request body.
+pub fn to_body<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).expect("SCIM types always serialize")
+}
+
+/// Pull the `id` out of a response body without caring about the resource type.
+pub fn resource_id(body: &str) -> Option<String> {
+    #[derive(Deserialize)]
+    struct IdOnly {
+        #[serde(default, deserialize_with = "lenient_string")]
+        id: Option<String>,
+    }
+    serde_json::from_str::<IdOnly>(body).ok()?.id
+}
+
+// ── Common attributes ──
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Meta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Name {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub given_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub middle_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub honorific_prefix: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub honorific_suffix: Option<String>,
+}
+
+/// An entry of a multi-valued attribute such as `emails` or `phoneNumbers`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MultiValued {
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient_string")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient_bool")]
+    pub primary: Option<bool>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl MultiValued {
+    pub fn new(value: &str, kind: &str, primary: bool) -> Self {
+        MultiValued {
+            value: Some(value.to_string()),
+            kind: Some(kind.to_string()),
+            primary: Some(primary),
+            ..Default::default()
+        }
+    }
+}
+
+/// An entry of `addresses` (RFC 7643 §4.1.2).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Address {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatted: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub street_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locality: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient_bool")]
+    pub primary: Option<bool>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Address {
+    pub fn new(locality: &str, kind: &str, primary: bool) -> Self {
+        Address {
+            locality: Some(locality.to_string()),
+            kind: Some(kind.to_string()),
+            primary: Some(primary),
+            ..Default::default()
+        }
+    }
+}
+
+// ── Resources ──
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient_string")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    #[serde(default)]
+    pub user_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<Name>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nick_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient_bool")]
+    pub active: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "null_as_empty")]
+    pub emails: Vec<MultiValued>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "null_as_empty")]
+    pub phone_numbers: Vec<MultiValued>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "null_as_empty")]
+    pub addresses: Vec<Address>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "null_as_empty")]
+    pub groups: Vec<Member>,
+    #[serde(rename = "urn:ietf:params:scim:schemas:extension:enterprise:2.0:User", skip_serializing_if = "Option::is_none")]
+    pub enterprise: Option<EnterpriseUser>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
+    /// Attributes not modelled above, including other schema extensions.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl User {
+    pub fn new(user_name: &str) -> Self {
+        User {
+            schemas: vec![USER_SCHEMA.to_string()],
+            user_name: user_name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_name(mut self, given_name: &str, family_name: &str) -> Self {
+        self.name = Some(Name {
+            given_name: Some(given_name.to_string()),
+            family_name: Some(family_name.to_string()),
+            ..Default::default()
+        });
+        self
+    }
+
+    pub fn with_display_name(mut self, display_name: &str) -> Self {
+        self.display_name = Some(display_name.to_string());
+        self
+    }
+
+    pub fn with_active(mut self, active: bool) -> Self {
+        self.active = Some(active);
+        self
+    }
+
+    pub fn with_work_email(mut self, email: &str) -> Self {
+        self.emails.push(MultiValued::new(email, "work", true));
+        self
+    }
+
+    pub fn with_enterprise(mut self, enterprise: EnterpriseUser) -> Self {
+        if !self.schemas.iter().any(|s| s == ENTERPRISE_USER_SCHEMA) {
+            self.schemas.push(ENTERPRISE_USER_SCHEMA.to_string());
+        }
+        self.enterprise = Some(enterprise);
+        self
+    }
+
+    /// Set an attribute of an extension schema, declaring the schema if needed.
+    pub fn set_extension_attribute(&mut self, schema_urn: &str, attribute: &str, value: Value) {
+        if !self.schemas.iter().any(|s| s == schema_urn) {
+            self.schemas.push(schema_urn.to_string());
+        }
+        if schema_urn == ENTERPRISE_USER_SCHEMA {
+            let mut extension = self.enterprise_value();
+            extension.insert(attribute.to_string(), value);
+            self.enterprise = serde_json::from_value(Value::Object(extension)).ok();
+            return;
+        }
+        let extension = self.extra.entry(schema_urn.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        if let Value::Object(map) = extension {
+            map.insert(attribute.to_string(), value);
+        }
+    }
+
+    pub fn extension_attribute(&self, schema_urn: &str, attribute: &str) -> Option<Value> {
+        if schema_urn == ENTERPRISE_USER_SCHEMA {
+            return self.enterprise_value().remove(attribute);
+        }
+        self.extra.get(schema_urn)?.get(attribute).cloned()
+    }
+
+    fn enterprise_value(&self) -> Map<String, Value> {
+        match self.enterprise.as_ref().map(serde_json::to_value) {
+            Some(Ok(Value::Object(map))) => map,
+            _ => Map::new(),
+        }
+    }
+
+    pub fn primary_email(&self) -> Option<&str> {
+        self.emails.iter()
+            .find(|e| e.primary == Some(true))
+            .or_else(|| self.emails.first())
+            .and_then(|e| e.value.as_deref())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnterpriseUser {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub employee_number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_center: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organization: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub division: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub department: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub manager: Option<Manager>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manager {
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient_string")]
+    pub value: Option<String>,
+    #[serde(rename = "$ref", skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Group {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient_string")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    #[serde(default)]
+    pub display_name: String,
+    #[serde(default, deserialize_with = "null_as_empty")]
+    pub members: Vec<Member>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Group {
+    pub fn new(display_name: &str) -> Self {
+        Group {
+            schemas: vec![GROUP_SCHEMA.to_string()],
+            display_name: display_name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn has_member(&self, id: &str) -> bool {
+        self.members.iter().any(|m| m.value.as_deref() == Some(id))
+    }
+}
+
+/// A member of a group, or a group in a user's `groups` attribute.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Member {
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient_string")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(rename = "$ref", skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+}
+
+impl Member {
+    pub fn new(id: &str) -> Self {
+        Member { value: Some(id.to_string()), ..Default::default() }
+    }
+}
+
+// ── Protocol messages ──
+
+/// A query response (RFC 7644 §3.4.2).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListResponse<T> {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(default, alias = "totalresults", deserialize_with = "lenient_u64")]
+    pub total_results: Option<u64>,
+    #[serde(default, alias = "itemsperpage", deserialize_with = "lenient_u64")]
+    pub items_per_page: Option<u64>,
+    #[serde(default, alias = "startindex", deserialize_with = "lenient_u64")]
+    pub start_index: Option<u64>,
+    /// `None` when the server left the attribute out, which the RFC allows when
+    /// there are no results.
+    #[serde(rename = "Resources", alias = "resources", skip_serializing_if = "Option::is_none")]
+    pub resources: Option<Vec<T>>,
+    /// Cursor-based pagination (RFC 9865).
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient_string")]
+    pub next_cursor: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient_string")]
+    pub previous_cursor: Option<String>,
+}
+
+impl<T: for<'de> Deserialize<'de>> ListResponse<T> {
+    /// Parse a ListResponse, also accepting a bare array or a single resource,
+    /// which some servers return from the discovery endpoints.
+    pub fn parse_lenient(body: &str) -> serde_json::Result<Self> {
+        let json: Value = serde_json::from_str(body)?;
+        let resources = match json {
+            Value::Array(items) => items,
+            Value::Object(ref map) if map.contains_key("Resources") || map.contains_key("resources")
+                || map.contains_key("totalResults") => return serde_json::from_value(json),
+            single => vec![single],
+        };
+        let resources = resources.into_iter()
+            .map(serde_json::from_value)
+            .collect::<serde_json::Result<Vec<T>>>()?;
+        Ok(ListResponse {
+            schemas: Vec::new(),
+            total_results: Some(resources.len() as u64),
+            items_per_page: None,
+            start_index: None,
+            resources: Some(resources),
+            next_cursor: None,
+            previous_cursor: None,
+        })
+    }
+}
+
+impl<T> ListResponse<T> {
+    pub fn resources(&self) -> &[T] {
+        self.resources.as_deref().unwrap_or_default()
+    }
+
+    pub fn into_resources(self) -> Vec<T> {
+        self.resources.unwrap_or_default()
+    }
+}
+
+/// A query sent as the body of `POST .../.search` (RFC 7644 §3.4.3), with the
+/// same parameters a GET query takes in the URL.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchRequest {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "null_as_empty")]
+    pub attributes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "null_as_empty")]
+    pub excluded_attributes: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_by: Option<String>,
+    /// "ascending" or "descending".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_order: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient_u64")]
+    pub start_index: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient_u64")]
+    pub count: Option<u64>,
+}
+
+impl SearchRequest {
+    pub fn new() -> Self {
+        SearchRequest { schemas: vec![SEARCH_REQUEST_SCHEMA.to_string()], ..Default::default() }
+    }
+
+    pub fn with_filter(mut self, filter: &Filter) -> Self {
+        self.filter = Some(filter.to_string());
+        self
+    }
+
+    pub fn with_attributes(mut self, attributes: &[&str]) -> Self {
+        self.attributes = attributes.iter().map(|a| a.to_string()).collect();
+        self
+    }
+
+    pub fn with_excluded_attributes(mut self, attributes: &[&str]) -> Self {
+        self.excluded_attributes = attributes.iter().map(|a| a.to_string()).collect();
+        self
+    }
+
+    pub fn with_sort(mut self, sort_by: &str, ascending: bool) -> Self {
+        self.sort_by = Some(sort_by.to_string());
+        self.sort_order = Some(if ascending { "ascending" } else { "descending" }.to_string());
+        self
+    }
+
+    pub fn with_page(mut self, start_index: u64, count: u64) -> Self {
+        self.start_index = Some(start_index);
+        self.count = Some(count);
+        self
+    }
+
+    /// The equivalent GET query string, percent-encoded, without the leading `?`.
+    pub fn query_string(&self) -> String {
+        let mut params = Vec::new();
+        if !self.attributes.is_empty() {
+            params.push(("attributes", self.attributes.join(",")));
+        }
+        if !self.excluded_attributes.is_empty() {
+            params.push(("excludedAttributes", self.excluded_attributes.join(",")));
+        }
+        if let Some(ref filter) = self.filter {
+            params.push(("filter", filter.clone()));
+        }
+        if let Some(ref sort_by) = self.sort_by {
+            params.push(("sortBy", sort_by.clone()));
+        }
+        if let Some(ref sort_order) = self.sort_order {
+            params.push(("sortOrder", sort_order.clone()));
+        }
+        if let Some(start_index) = self.start_index {
+            params.push(("startIndex", start_index.to_string()));
+        }
+        if let Some(count) = self.count {
+            params.push(("count", count.to_string()));
+        }
+        params.iter()
+            .map(|(key, value)| format!("{}={}", key, encode_query_value(value)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchOp {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(rename = "Operations", alias = "operations")]
+    pub operations: Vec<PatchOperation>,
+}
+
+impl PatchOp {
+    pub fn new(operations: Vec<PatchOperation>) -> Self {
+        PatchOp { schemas: vec![PATCH_OP_SCHEMA.to_string()], operations }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchOperation {
+    /// "add", "replace" or "remove"; kept as sent since some clients capitalize it.
+    pub op: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+}
+
+impl PatchOperation {
+    pub fn add(path: Option<&str>, value: Value) -> Self {
+        PatchOperation { op: "add".to_string(), path: path.map(str::to_string), value: Some(value) }
+    }
+
+    pub fn replace(path: Option<&str>, value: Value) -> Self {
+        PatchOperation { op: "replace".to_string(), path: path.map(str::to_string), value: Some(value) }
+    }
+
+    pub fn remove(path: &str) -> Self {
+        PatchOperation { op: "remove".to_string(), path: Some(path.to_string()), value: None }
+    }
+}
+
+/// A `/Bulk` request (RFC 7644 §3.7).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkRequest {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    /// Errors the server may hit before it stops processing; unlimited if absent.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient_u64")]
+    pub fail_on_errors: Option<u64>,
+    #[serde(rename = "Operations", alias = "operations")]
+    pub operations: Vec<BulkOperation>,
+}
+
+impl BulkRequest {
+    pub fn new(operations: Vec<BulkOperation>) -> Self {
+        BulkRequest { schemas: vec![BULK_REQUEST_SCHEMA.to_string()], fail_on_errors: None, operations }
+    }
+
+    pub fn with_fail_on_errors(mut self, errors: u64) -> Self {
+        self.fail_on_errors = Some(errors);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkOperation {
+    pub method: String,
+    /// Lets later operations refer to the resource this one creates, as
+    /// `"bulkId:<id>"` (see `bulk_ref`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bulk_id: Option<String>,
+    /// An ETag the resource must still have.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl BulkOperation {
+    pub fn post<T: Serialize>(bulk_id: &str, path: &str, data: &T) -> Self {
+        BulkOperation {
+            method: "POST".to_string(),
+            bulk_id: Some(bulk_id.to_string()),
+            version: None,
+            path: path.to_string(),
+            data: Some(serde_json::to_value(data).expect("SCIM types always serialize")),
+        }
+    }
+
+    pub fn put<T: Serialize>(path: &str, data: &T) -> Self {
+        BulkOperation {
+            method: "PUT".to_string(),
+            bulk_id: None,
+            version: None,
+            path: path.to_string(),
+            data: Some(serde_json::to_value(data).expect("SCIM types always serialize")),
+        }
+    }
+
+    pub fn patch(path: &str, patch: &PatchOp) -> Self {
+        BulkOperation { method: "PATCH".to_string(), ..Self::put(path, patch) }
+    }
+
+    pub fn delete(path: &str) -> Self {
+        BulkOperation { method: "DELETE".to_string(), bulk_id: None, version: None, path: path.to_string(), data: None }
+    }
+}
+
+/// How an operation refers to a resource created earlier in the same request.
+pub fn bulk_ref(bulk_id: &str) -> String {
+    format!("bulkId:{}", bulk_id)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkResponse {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(rename = "Operations", alias = "operations", default, deserialize_with = "null_as_empty")]
+    pub operations: Vec<BulkOperationResponse>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkOperationResponse {
+    #[serde(default)]
+    pub method: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bulk_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// Sent as a string (`"201"`), and by older servers as `{ "code": "201" }`.
+    #[serde(default, deserialize_with = "lenient_status")]
+    pub status: Option<u16>,
+    /// The error body of a failed operation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<Value>,
+}
+
+impl BulkOperationResponse {
+    pub fn is_success(&self) -> bool {
+        self.status.is_some_and(|s| (200..300).contains(&s))
+    }
+
+    /// The id of the resource this operation touched, from its `location`.
+    pub fn resource_id(&self) -> Option<String> {
+        self.location.as_deref()
+            .map(|l| l.trim_end_matches('/'))
+            .and_then(|l| l.rsplit('/').next())
+            .filter(|id| !id.is_empty())
+            .map(str::to_string)
+    }
+
+    pub fn error(&self) -> Option<ScimError> {
+        let response = self.response.as_ref()?;
+        ScimError::parse(self.status.unwrap_or(0), &response.to_string())
+    }
+}
+
+/// The `scimType` keywords of RFC 7644 §3.12, Table 9.
+pub const SCIM_ERROR_TYPES: [&str; 10] = [
+    "invalidFilter", "tooMany", "uniqueness", "mutability", "invalidSyntax",
+    "invalidPath", "noTarget", "invalidValue", "invalidVers", "sensitive",
+];
+
+/// An error response as defined in RFC 7644 §3.12.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScimError {
+    pub status: u16,
+    pub scim_type: Option<String>, // e.g. "uniqueness", "invalidFilter", "mutability"
+    pub detail: Option<String>,
+}
+
+impl ScimError {
+    /// Parse an error body returned with `status`. Bodies that do not declare the
+    /// SCIM error schema are accepted as long as they carry `scimType` or
+    /// `detail`, since many servers leave out `schemas`.
+    pub fn parse(status: u16, body: &str) -> Option<Self> {
+        let json: Value = serde_json::from_str(body).ok()?;
+        let declared = json.get("schemas")
+            .and_then(Value::as_array)
+            .is_some_and(|s| s.iter().any(|v| v.as_str() == Some(ERROR_SCHEMA)));
+        let text = |key: &str| json.get(key)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string);
+        let scim_type = text("scimType");
+        let detail = text("detail");
+        if !declared && scim_type.is_none() && detail.is_none() {
+            return None;
+        }
+        Some(ScimError { status, scim_type, detail })
+    }
+
+    /// "scimType: detail", or whichever of the two the server sent.
+    pub fn summary(&self) -> Option<String> {
+        match (&self.scim_type, &self.detail) {
+            (Some(t), Some(d)) => Some(format!("{}: {}", t, d)),
+            (Some(t), None) => Some(t.clone()),
+            (None, Some(d)) => Some(d.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ScimError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.summary() {
+            Some(summary) => write!(f, "SCIM error {} ({})", self.status, summary),
+            None => write!(f, "SCIM error {}", self.status),
+        }
+    }
+}
+
+// ── Discovery resources ──
+
+/// `/ServiceProviderConfig` (RFC 7643 §5). Features the server leaves out are
+/// `None`, so callers can tell "not declared" from "declared unsupported".
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceProviderConfig {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub documentation_uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub patch: Option<Supported>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bulk: Option<BulkSupport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<FilterSupport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change_password: Option<Supported>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<Supported>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub etag: Option<Supported>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<PaginationSupport>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "null_as_empty")]
+    pub authentication_schemes: Vec<AuthenticationScheme>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// The features a ServiceProviderConfig flags with `supported`, all of which
+/// RFC 7643 §5 requires it to declare.
+pub const SPC_FEATURES: [&str; 6] = ["patch", "bulk", "filter", "changePassword", "sort", "etag"];
+
+impl ServiceProviderConfig {
+    /// Declared support for one of [`SPC_FEATURES`]; `None` if left out.
+    pub fn supports(&self, feature: &str) -> Option<bool> {
+        match feature {
+            "patch" => self.patch.as_ref().map(|f| f.supported),
+            "bulk" => self.bulk.as_ref().map(|f| f.supported),
+            "filter" => self.filter.as_ref().map(|f| f.supported),
+            "changePassword" => self.change_password.as_ref().map(|f| f.supported),
+            "sort" => self.sort.as_ref().map(|f| f.supported),
+            "etag" => self.etag.as_ref().map(|f| f.supported),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Supported {
+    #[serde(default, deserialize_with = "lenient_bool_default")]
+    pub supported: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkSupport {
+    #[serde(default, deserialize_with = "lenient_bool_default")]
+    pub supported: bool,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub max_operations: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub max_payload_size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilterSupport {
+    #[serde(default, deserialize_with = "lenient_bool_default")]
+    pub supported: bool,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub max_results: Option<u64>,
+}
+
+/// Pagination methods the server supports (RFC 9865 §4).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaginationSupport {
+    #[serde(default, deserialize_with = "lenient_bool_default")]
+    pub cursor: bool,
+    #[serde(default, deserialize_with = "lenient_bool_default")]
+    pub index: bool,
+    /// `"cursor"` or `"index"`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_pagination_method: Option<String>,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub default_page_size: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub max_page_size: Option<u64>,
+    /// Seconds a cursor stays valid.
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub cursor_timeout: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticationScheme {
+    #[serde(rename = "type", default)]
+    pub kind: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "lenient_bool")]
+    pub primary: Option<bool>,
+}
+
+/// A schema definition from `/Schemas` (RFC 7643 §7).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Schema {
+    #[serde(default)]
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, alias = "Attributes", deserialize_with = "null_as_empty")]
+    pub attributes: Vec<SchemaAttribute>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Schema {
+    pub fn is_core(&self) -> bool {
+        CORE_SCHEMA_PREFIXES.iter().any(|p| self.id.starts_with(p))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaAttribute {
+    #[serde(default, alias = "Name")]
+    pub name: String,
+    /// "string", "boolean", "decimal", "integer", "dateTime", "reference",
+    /// "binary" or "complex"; defaults to "string" as the RFC specifies.
+    #[serde(rename = "type", alias = "Type", default = "default_attribute_type")]
+    pub kind: String,
+    #[serde(default, deserialize_with = "lenient_bool_default")]
+    pub multi_valued: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, deserialize_with = "lenient_bool_default")]
+    pub required: bool,
+    #[serde(default, deserialize_with = "lenient_bool_default")]
+    pub case_exact: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mutability: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub returned: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uniqueness: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "null_as_empty")]
+    pub canonical_values: Vec<Value>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "null_as_empty")]
+    pub reference_types: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "null_as_empty")]
+    pub sub_attributes: Vec<SchemaAttribute>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+fn default_attribute_type() -> String {
+    "string".to_string()
+}
+
+/// A resource type from `/ResourceTypes` (RFC 7643 §6).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceType {
+    #[serde(default)]
+    pub schemas: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub endpoint: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub schema: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty", deserialize_with = "null_as_empty")]
+    pub schema_extensions: Vec<SchemaExtension>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Meta>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SchemaExtension {
+    pub schema: String,
+    #[serde(default, deserialize_with = "lenient_bool_default")]
+    pub required: bool,
+}
+
+// ── Lenient deserializers ──
+
+/// Booleans sent as strings ("true", "True", "False").
+fn lenient_bool<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<bool>, D::Error> {
+    Ok(match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::Bool(b)) => Some(b),
+        Some(Value::String(s)) if s.eq_ignore_ascii_case("true") => Some(true),
+        Some(Value::String(s)) if s.eq_ignore_ascii_case("false") => Some(false),
+        _ => None,
+    })
+}
+
+fn lenient_bool_default<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    Ok(lenient_bool(deserializer)?.unwrap_or(false))
+}
+
+/// Ids and values sent as numbers.
+fn lenient_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    Ok(match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::String(s)) => Some(s),
+        Some(Value::Number(n)) => Some(n.to_string()),
+        _ => None,
+    })
+}
+
+fn lenient_status<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u16>, D::Error> {
+    let status = match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::Object(mut map)) => map.remove("code"),
+        other => other,
+    };
+    Ok(match status {
+        Some(Value::Number(n)) => n.as_u64().and_then(|n| u16::try_from(n).ok()),
+        Some(Value::String(s)) => s.trim().parse().ok(),
+        _ => None,
+    })
+}
+
+/// Counts sent as strings.
+fn lenient_u64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    Ok(match Option::<Value>::deserialize(deserializer)? {
+        Some(Value::Number(n)) => n.as_u64(),
+        Some(Value::String(s)) => s.trim().parse().ok(),
+        _ => None,
+    })
+}
+
+/// `null` where a list is expected.
+fn null_as_empty<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(Option::<Vec<T>>::deserialize(deserializer)?.unwrap_or_default())
+}
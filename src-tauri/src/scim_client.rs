@@ -1,10 +1,22 @@
-use reqwest::{Client, Method, Response, header};
+use reqwest::{Client, Method, Response, StatusCode, header};
+use reqwest::header::HeaderMap;
 use std::collections::HashMap;
-use std::time::Instant;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use rand::Rng;
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD as BASE64;
 
-use crate::models::ServerConfig;
+use crate::connection::ConnectionSettings;
+use crate::error::{AppError, Result};
+use crate::scim::{self, BulkRequest, BulkResponse, ScimError, SearchRequest, SCIM_MEDIA_TYPE};
+use crate::models::{RequestTiming, RetryPolicy, ServerConfig};
+use crate::oauth::OAuthTokenProvider;
+use crate::pagination::Pager;
+use crate::rate_limit::RateLimiter;
+use crate::traffic::{PendingExchange, ResponseCapture, TrafficRecorder};
+use crate::timing::{self, ConnectionEvents};
+use crate::tls::{self, CertificateReport, TlsSettings};
 
 pub struct ScimClient {
     client: Client,
@@ -15,12 +27,81 @@ pub struct ScimClient {
     auth_password: Option<String>,
     api_key_header: Option<String>,
     api_key_value: Option<String>,
+    oauth: Option<OAuthTokenProvider>,
+    tls: TlsSettings,
+    connection: ConnectionSettings,
+    retry: RetryPolicy,
+    rate_limiter: Option<RateLimiter>,
+    recorder: Option<Arc<TrafficRecorder>>,
 }
 
 pub struct ScimResponse {
     pub status: u16,
     pub body: String,
     pub duration_ms: i64,
+    pub timing: RequestTiming,
+    pub etag: Option<String>,
+    pub headers: HeaderMap,
+}
+
+impl ScimResponse {
+    /// A response header, when present and valid text.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|v| v.to_str().ok())
+    }
+
+    /// The `Location` header of a created resource (RFC 7644 §3.3).
+    pub fn location(&self) -> Option<&str> {
+        self.header(header::LOCATION.as_str())
+    }
+
+    /// The SCIM error in the body of a 4xx/5xx response, if the server sent one.
+    pub fn scim_error(&self) -> Option<ScimError> {
+        if self.status < 400 {
+            return None;
+        }
+        ScimError::parse(self.status, &self.body)
+    }
+
+    /// The per-operation results of a `/Bulk` response.
+    pub fn bulk_response(&self) -> Option<BulkResponse> {
+        serde_json::from_str(&self.body).ok()
+    }
+}
+
+/// A response read in full, before it is shaped for a caller.
+struct RawResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: String,
+    duration_ms: i64,
+    timing: RequestTiming,
+}
+
+/// An attempt that failed and was retried under the client's `RetryPolicy`.
+pub struct RetriedAttempt {
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    pub duration_ms: i64,
+    pub timing: Option<RequestTiming>,
+    pub delay_ms: u64, // wait before the next attempt
+}
+
+/// A conditional request (RFC 7644 §3.14) on a resource version.
+#[derive(Clone, Copy)]
+pub enum Precondition<'a> {
+    /// Only apply the change if the resource still has this ETag.
+    IfMatch(&'a str),
+    /// Only return the resource if its ETag changed (304 otherwise).
+    IfNoneMatch(&'a str),
+}
+
+/// Per-request deviations from the client's defaults.
+#[derive(Clone, Copy, Default)]
+struct RequestOptions<'a> {
+    precondition: Option<Precondition<'a>>,
+    /// Sent as `Content-Type` and `Accept` instead of `application/scim+json`.
+    media_type: Option<&'a str>,
 }
 
 pub struct ScimFullResponse {
@@ -29,20 +110,36 @@ pub struct ScimFullResponse {
     pub headers: HashMap<String, String>,
     pub body: String,
     pub duration_ms: i64,
+    pub timing: RequestTiming,
     pub request_url: String,
 }
 
 impl ScimClient {
-    pub fn new(config: &ServerConfig) -> Result<Self, String> {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .pool_max_idle_per_host(100)
-            .danger_accept_invalid_certs(true) // Allow self-signed certs for dev/testing
-            .build()
-            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    pub fn new(config: &ServerConfig) -> Result<Self> {
+        Self::build(config, 100)
+    }
+
+    pub fn new_with_concurrency(config: &ServerConfig, max_connections: usize) -> Result<Self> {
+        Self::build(config, max_connections)
+    }
+
+    fn build(config: &ServerConfig, max_idle_per_host: usize) -> Result<Self> {
+        let tls = TlsSettings::from_config(config).map_err(AppError::Tls)?;
+        let connection = ConnectionSettings::from_config(config).map_err(AppError::Config)?;
+        let builder = timing::instrument(Client::builder())
+            .pool_max_idle_per_host(max_idle_per_host);
+
+        let client = connection.apply(tls.apply(builder)).build()
+            .map_err(|e| AppError::Other(format!("Failed to create HTTP client: {}", e)))?;
 
         let base_url = config.base_url.trim_end_matches('/').to_string();
 
+        let oauth = if config.auth_type == "oauth2_client_credentials" {
+            Some(OAuthTokenProvider::from_config(config, client.clone())?)
+        } else {
+            None
+        };
+
         Ok(ScimClient {
             client,
             base_url,
@@ -52,29 +149,36 @@ impl ScimClient {
             auth_password: config.auth_password.clone(),
             api_key_header: config.api_key_header.clone(),
             api_key_value: config.api_key_value.clone(),
+            oauth,
+            tls,
+            connection,
+            retry: RetryPolicy::default(),
+            rate_limiter: config.rate_limit_rps
+                .and_then(|rps| RateLimiter::new(rps, config.rate_limit_burst.unwrap_or(1))),
+            recorder: None,
         })
     }
 
-    pub fn new_with_concurrency(config: &ServerConfig, max_connections: usize) -> Result<Self, String> {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .pool_max_idle_per_host(max_connections)
-            .danger_accept_invalid_certs(true)
-            .build()
-            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    /// Capture every exchange this client makes, including retries.
+    pub fn with_recorder(mut self, recorder: Arc<TrafficRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
 
-        let base_url = config.base_url.trim_end_matches('/').to_string();
+    /// Replace the server's rate limit, e.g. with a load test's target RPS.
+    pub fn with_rate_limit(mut self, requests_per_second: f64, burst: u32) -> Self {
+        self.rate_limiter = RateLimiter::new(requests_per_second, burst);
+        self
+    }
 
-        Ok(ScimClient {
-            client,
-            base_url,
-            auth_type: config.auth_type.clone(),
-            auth_token: config.auth_token.clone(),
-            auth_username: config.auth_username.clone(),
-            auth_password: config.auth_password.clone(),
-            api_key_header: config.api_key_header.clone(),
-            api_key_value: config.api_key_value.clone(),
-        })
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Inspect the certificate the server presents, regardless of the TLS mode.
+    pub async fn inspect_certificate(&self) -> Result<Option<CertificateReport>> {
+        tls::inspect_certificate(&self.base_url, &self.tls, &self.connection).await
     }
 
     fn build_url(&self, path: &str) -> String {
@@ -82,6 +186,7 @@ impl ScimClient {
         format!("{}/{}", self.base_url, path)
     }
 
+    /// Attach static credentials. OAuth 2.0 tokens are fetched and attached in `send()`.
     fn apply_auth(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
         match self.auth_type.as_str() {
             "bearer" => {
@@ -110,99 +215,438 @@ impl ScimClient {
         }
     }
 
+    /// Build and send a request with SCIM headers, the server's custom headers and
+    /// auth applied, and read the response. `duration_ms` runs from the moment the
+    /// (final) attempt was sent until the response headers arrived, so token
+    /// acquisition and rate-limit waits are not counted as server latency.
+    ///
+    /// With OAuth 2.0 a 401 drops the cached token and the request is replayed
+    /// once with a freshly fetched one. If the server rejects that token too,
+    /// the error is returned as `AppError::Auth`; every other status, 401 and
+    /// 403 included, is returned as a response for the caller to judge.
+    async fn send(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&str>,
+        options: RequestOptions<'_>,
+    ) -> Result<RawResponse> {
+        let media_type = options.media_type.unwrap_or(SCIM_MEDIA_TYPE);
+        let mut token_refreshed = false;
+        loop {
+            if let Some(ref limiter) = self.rate_limiter {
+                limiter.acquire().await;
+            }
+
+            let mut builder = self.client.request(method.clone(), url)
+                .header(header::CONTENT_TYPE, media_type)
+                .header(header::ACCEPT, media_type)
+                .headers(self.connection.headers().clone());
+            builder = match options.precondition {
+                Some(Precondition::IfMatch(etag)) => builder.header(header::IF_MATCH, etag),
+                Some(Precondition::IfNoneMatch(etag)) => builder.header(header::IF_NONE_MATCH, etag),
+                None => builder,
+            };
+
+            let mut oauth_token = None;
+            if let Some(ref oauth) = self.oauth {
+                let token = oauth.access_token().await?;
+                builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", token));
+                oauth_token = Some(token);
+            } else {
+                builder = self.apply_auth(builder);
+            }
+
+            if let Some(body_str) = body {
+                builder = builder.body(body_str.to_string());
+            }
+
+            let request = builder.build().map_err(|e| AppError::Config(format!("Invalid request: {}", e)))?;
+            let pending = self.recorder.as_ref().map(|r| r.begin(&request, body));
+
+            let start = Instant::now();
+            let (response, events) = timing::observe(self.client.execute(request)).await;
+            let response = match response {
+                Ok(r) => r,
+                Err(e) => {
+                    let error = AppError::request("Request failed", &e);
+                    if let (Some(recorder), Some(pending)) = (&self.recorder, pending) {
+                        recorder.fail(pending, &error.to_string(), start.elapsed().as_millis() as i64);
+                    }
+                    return Err(error);
+                }
+            };
+            let raw = self.read_response(response, start, events, pending).await?;
+
+            if raw.status == StatusCode::UNAUTHORIZED {
+                if let (Some(oauth), Some(token)) = (&self.oauth, &oauth_token) {
+                    if token_refreshed {
+                        return Err(rejected_token(&raw));
+                    }
+                    oauth.invalidate(token).await;
+                    token_refreshed = true;
+                    continue;
+                }
+            }
+
+            return Ok(raw);
+        }
+    }
+
+    async fn read_response(
+        &self,
+        response: Response,
+        start: Instant,
+        events: ConnectionEvents,
+        pending: Option<PendingExchange>,
+    ) -> Result<RawResponse> {
+        let headers_at = Instant::now();
+        let duration_ms = headers_at.duration_since(start).as_millis() as i64;
+        let status = response.status();
+        let version = response.version();
+        let headers = response.headers().clone();
+
+        let body = match response.text().await {
+            Ok(body) => body,
+            Err(e) => {
+                let error = AppError::request("Failed to read response", &e);
+                if let (Some(recorder), Some(pending)) = (&self.recorder, pending) {
+                    recorder.fail(pending, &error.to_string(), start.elapsed().as_millis() as i64);
+                }
+                return Err(error);
+            }
+        };
+
+        let timing = events.breakdown(start, headers_at, Instant::now());
+        if let (Some(recorder), Some(pending)) = (&self.recorder, pending) {
+            recorder.finish(pending, &ResponseCapture {
+                status: status.as_u16(),
+                status_text: status.canonical_reason().unwrap_or("").to_string(),
+                http_version: format!("{:?}", version),
+                headers: recorder.capture_headers(&headers),
+                body: body.clone(),
+                timing,
+            }, start.elapsed().as_millis() as i64);
+        }
+
+        Ok(RawResponse { status, headers, body, duration_ms, timing })
+    }
+
     pub async fn request(
         &self,
         method: Method,
         path: &str,
         body: Option<&str>,
-    ) -> Result<ScimResponse, String> {
-        let url = self.build_url(path);
-        let start = Instant::now();
+    ) -> Result<ScimResponse> {
+        self.request_with_retries(method, path, body).await.1
+    }
 
-        let mut builder = self.client.request(method, &url)
-            .header(header::CONTENT_TYPE, "application/scim+json")
-            .header(header::ACCEPT, "application/scim+json");
+    /// Like `request()`, but also returns the attempts that were retried before
+    /// the final result, for callers that report throttling.
+    ///
+    /// 429 and 503 responses are retried for every method, since the server did
+    /// not process the request. Gateway errors (502/504) and transport errors
+    /// are only retried for idempotent methods.
+    pub async fn request_with_retries(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&str>,
+    ) -> (Vec<RetriedAttempt>, Result<ScimResponse>) {
+        self.retrying(method, path, body, RequestOptions::default()).await
+    }
 
-        builder = self.apply_auth(builder);
+    /// Like `request()`, with an `If-Match` or `If-None-Match` header.
+    pub async fn request_conditional(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&str>,
+        precondition: Precondition<'_>,
+    ) -> Result<ScimResponse> {
+        let options = RequestOptions { precondition: Some(precondition), ..Default::default() };
+        self.retrying(method, path, body, options).await.1
+    }
+
+    /// Like `request()`, sending and accepting `media_type` instead of
+    /// `application/scim+json`.
+    pub async fn request_as(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&str>,
+        media_type: &str,
+    ) -> Result<ScimResponse> {
+        let options = RequestOptions { media_type: Some(media_type), ..Default::default() };
+        self.retrying(method, path, body, options).await.1
+    }
 
-        if let Some(body_str) = body {
-            builder = builder.body(body_str.to_string());
+    async fn retrying(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&str>,
+        options: RequestOptions<'_>,
+    ) -> (Vec<RetriedAttempt>, Result<ScimResponse>) {
+        let url = self.build_url(path);
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut retries = Vec::new();
+        let mut attempt = 1;
+        loop {
+            let start = Instant::now();
+            let result = self.execute(method.clone(), &url, body, options).await;
+            let retryable = match &result {
+                Ok((resp, _)) => is_retryable_status(&method, resp.status),
+                // Replaying rejected credentials cannot succeed
+                Err(AppError::Auth(_)) => false,
+                Err(_) => is_idempotent(&method),
+            };
+            if attempt >= max_attempts || !retryable {
+                return (retries, result.map(|(resp, _)| resp));
+            }
+
+            let delay = match &result {
+                Ok((_, retry_after)) => self.backoff(attempt, *retry_after),
+                Err(_) => self.backoff(attempt, None),
+            };
+            retries.push(match result {
+                Ok((resp, _)) => RetriedAttempt {
+                    status: Some(resp.status),
+                    error: None,
+                    duration_ms: resp.duration_ms,
+                    timing: Some(resp.timing),
+                    delay_ms: delay.as_millis() as u64,
+                },
+                Err(e) => RetriedAttempt {
+                    status: None,
+                    error: Some(e.to_string()),
+                    duration_ms: start.elapsed().as_millis() as i64,
+                    timing: None,
+                    delay_ms: delay.as_millis() as u64,
+                },
+            });
+            tokio::time::sleep(delay).await;
+            attempt += 1;
         }
+    }
+
+    /// One attempt, returning the response and its `Retry-After` delay, if any.
+    async fn execute(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&str>,
+        options: RequestOptions<'_>,
+    ) -> Result<(ScimResponse, Option<Duration>)> {
+        let raw = self.send(method, url, body, options).await?;
+        let retry_after = raw.headers.get(header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(parse_retry_after);
+        let etag = raw.headers.get(header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
 
-        let response: Response = builder.send().await.map_err(|e| format!("Request failed: {}", e))?;
-        let duration_ms = start.elapsed().as_millis() as i64;
-        let status = response.status().as_u16();
-        let body = response.text().await.map_err(|e| format!("Failed to read response: {}", e))?;
+        Ok((ScimResponse {
+            status: raw.status.as_u16(),
+            body: raw.body,
+            duration_ms: raw.duration_ms,
+            timing: raw.timing,
+            etag,
+            headers: raw.headers,
+        }, retry_after))
+    }
 
-        Ok(ScimResponse {
-            status,
-            body,
-            duration_ms,
-        })
+    /// Exponential backoff for the given (1-based) attempt, or the server's
+    /// `Retry-After` when the policy honours it. Never longer than `max_backoff_ms`.
+    fn backoff(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let max = Duration::from_millis(self.retry.max_backoff_ms);
+        if self.retry.respect_retry_after {
+            if let Some(delay) = retry_after {
+                return delay.min(max);
+            }
+        }
+        let exponential = self.retry.initial_backoff_ms.saturating_mul(1u64 << (attempt - 1).min(20));
+        let mut ms = exponential.min(self.retry.max_backoff_ms);
+        if self.retry.jitter && ms > 0 {
+            ms = rand::thread_rng().gen_range(ms / 2..=ms);
+        }
+        Duration::from_millis(ms)
     }
 
-    pub async fn get(&self, path: &str) -> Result<ScimResponse, String> {
+    pub async fn get(&self, path: &str) -> Result<ScimResponse> {
         self.request(Method::GET, path, None).await
     }
 
-    pub async fn post(&self, path: &str, body: &str) -> Result<ScimResponse, String> {
+    pub async fn post(&self, path: &str, body: &str) -> Result<ScimResponse> {
         self.request(Method::POST, path, Some(body)).await
     }
 
-    pub async fn put(&self, path: &str, body: &str) -> Result<ScimResponse, String> {
+    pub async fn put(&self, path: &str, body: &str) -> Result<ScimResponse> {
         self.request(Method::PUT, path, Some(body)).await
     }
 
-    pub async fn patch(&self, path: &str, body: &str) -> Result<ScimResponse, String> {
+    pub async fn patch(&self, path: &str, body: &str) -> Result<ScimResponse> {
         self.request(Method::PATCH, path, Some(body)).await
     }
 
-    pub async fn delete(&self, path: &str) -> Result<ScimResponse, String> {
+    pub async fn delete(&self, path: &str) -> Result<ScimResponse> {
         self.request(Method::DELETE, path, None).await
     }
 
+    /// Query with `POST {endpoint}/.search`; pass `""` to search from the root.
+    pub async fn search(&self, endpoint: &str, request: &SearchRequest) -> Result<ScimResponse> {
+        let path = format!("{}/.search", endpoint.trim_end_matches('/'));
+        self.post(&path, &scim::to_body(request)).await
+    }
+
+    pub async fn bulk(&self, request: &BulkRequest) -> Result<ScimResponse> {
+        self.post("/Bulk", &scim::to_body(request)).await
+    }
+
+    /// Walk a collection page by page; see `Pager`.
+    pub fn paginate<T: for<'de> serde::Deserialize<'de>>(&self, path: &str) -> Pager<'_, T> {
+        Pager::new(self, path)
+    }
+
     /// Like `request()` but captures response headers and status text for Explorer.
     pub async fn request_full(
         &self,
         method: Method,
         path: &str,
         body: Option<&str>,
-    ) -> Result<ScimFullResponse, String> {
+    ) -> Result<ScimFullResponse> {
         let url = self.build_url(path);
-        let start = Instant::now();
-
-        let mut builder = self.client.request(method, &url)
-            .header(header::CONTENT_TYPE, "application/scim+json")
-            .header(header::ACCEPT, "application/scim+json");
-
-        builder = self.apply_auth(builder);
-
-        if let Some(body_str) = body {
-            builder = builder.body(body_str.to_string());
-        }
-
-        let response: Response = builder.send().await.map_err(|e| format!("Request failed: {}", e))?;
-        let duration_ms = start.elapsed().as_millis() as i64;
-        let status = response.status();
-        let status_code = status.as_u16();
-        let status_text = status.canonical_reason().unwrap_or("Unknown").to_string();
+        let raw = self.send(method, &url, body, RequestOptions::default()).await?;
+        let status_code = raw.status.as_u16();
+        let status_text = raw.status.canonical_reason().unwrap_or("Unknown").to_string();
 
         let mut resp_headers = HashMap::new();
-        for (name, value) in response.headers().iter() {
+        for (name, value) in raw.headers.iter() {
             if let Ok(v) = value.to_str() {
                 resp_headers.insert(name.to_string(), v.to_string());
             }
         }
 
-        let body = response.text().await.map_err(|e| format!("Failed to read response: {}", e))?;
-
         Ok(ScimFullResponse {
             status: status_code,
             status_text,
             headers: resp_headers,
-            body,
-            duration_ms,
+            body: raw.body,
+            duration_ms: raw.duration_ms,
+            timing: raw.timing,
             request_url: url,
         })
     }
 }
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS)
+}
+
+fn is_retryable_status(method: &Method, status: u16) -> bool {
+    match status {
+        429 | 503 => true,
+        502 | 504 => is_idempotent(method),
+        _ => false,
+    }
+}
+
+/// The error for a 401 to a freshly fetched OAuth token, with the server's
+/// `detail` when it sent one.
+fn rejected_token(raw: &RawResponse) -> AppError {
+    let detail = ScimError::parse(raw.status.as_u16(), &raw.body)
+        .and_then(|e| e.detail)
+        .map(|d| format!(": {}", d))
+        .unwrap_or_default();
+    AppError::Auth(format!("SCIM server rejected a freshly issued OAuth token (401){}", detail))
+}
+
+/// `Retry-After` is either a number of seconds or an HTTP date (RFC 9110 §10.2.3).
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = at.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    Some(wait.to_std().unwrap_or(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(policy: RetryPolicy) -> ScimClient {
+        ScimClient::new(&ServerConfig::for_tests("http://127.0.0.1")).unwrap().with_retry_policy(policy)
+    }
+
+    fn policy(respect_retry_after: bool, jitter: bool) -> RetryPolicy {
+        RetryPolicy { max_attempts: 5, initial_backoff_ms: 100, max_backoff_ms: 1000, jitter, respect_retry_after }
+    }
+
+    fn http_date(offset_secs: i64) -> String {
+        (chrono::Utc::now() + chrono::Duration::seconds(offset_secs))
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string()
+    }
+
+    #[test]
+    fn retry_after_in_seconds() {
+        assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+        assert_eq!(parse_retry_after(" 0 "), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn retry_after_as_http_date() {
+        let wait = parse_retry_after(&http_date(60)).unwrap();
+        assert!(wait > Duration::from_secs(55) && wait <= Duration::from_secs(60), "{:?}", wait);
+    }
+
+    #[test]
+    fn retry_after_date_in_the_past_means_no_wait() {
+        assert_eq!(parse_retry_after(&http_date(-60)), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn retry_after_that_is_neither_is_ignored() {
+        assert_eq!(parse_retry_after("soon"), None);
+        assert_eq!(parse_retry_after("-5"), None);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let client = client(policy(true, false));
+        let delays: Vec<u128> = (1..=5).map(|attempt| client.backoff(attempt, None).as_millis()).collect();
+        assert_eq!(delays, vec![100, 200, 400, 800, 1000]);
+        // Large attempt numbers must not overflow the shift
+        assert_eq!(client.backoff(64, None), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn backoff_jitter_stays_between_half_and_full_delay() {
+        let client = client(policy(true, true));
+        for _ in 0..100 {
+            let ms = client.backoff(3, None).as_millis();
+            assert!((200..=400).contains(&ms), "{}", ms);
+        }
+    }
+
+    #[test]
+    fn backoff_honours_retry_after_within_the_cap() {
+        let honouring = client(policy(true, false));
+        assert_eq!(honouring.backoff(1, Some(Duration::from_millis(700))), Duration::from_millis(700));
+        assert_eq!(honouring.backoff(1, Some(Duration::from_secs(60))), Duration::from_millis(1000));
+
+        let ignoring = client(policy(false, false));
+        assert_eq!(ignoring.backoff(1, Some(Duration::from_millis(700))), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn only_idempotent_methods_retry_gateway_errors() {
+        assert!(is_retryable_status(&Method::POST, 429));
+        assert!(is_retryable_status(&Method::POST, 503));
+        assert!(!is_retryable_status(&Method::POST, 502));
+        assert!(is_retryable_status(&Method::PUT, 504));
+        assert!(!is_retryable_status(&Method::GET, 500));
+    }
+}
@@ -0,0 +1,80 @@
+//! A minimal HTTP/1.1 server on a local port, for tests that need a real
+//! endpoint to send requests to. Each connection carries one request.
+
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+pub struct Request {
+    pub method: String,
+    /// Path and query, as sent on the request line.
+    pub path: String,
+    headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Request {
+    /// A request header, matched case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+type Handler = dyn Fn(Request) -> (u16, String) + Send + Sync;
+
+/// Answer every request with the status and JSON body `handler` returns.
+/// Returns the server's base URL.
+pub async fn serve(handler: impl Fn(Request) -> (u16, String) + Send + Sync + 'static) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let handler: Arc<Handler> = Arc::new(handler);
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let handler = handler.clone();
+            tokio::spawn(async move { respond(stream, &*handler).await });
+        }
+    });
+    base_url
+}
+
+async fn respond(mut stream: TcpStream, handler: &Handler) {
+    let mut data = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_len = loop {
+        let n = stream.read(&mut chunk).await.unwrap();
+        data.extend_from_slice(&chunk[..n]);
+        if let Some(i) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+            break i + 4;
+        }
+    };
+    let head = String::from_utf8_lossy(&data[..header_len]).into_owned();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap().split(' ');
+    let method = request_line.next().unwrap().to_string();
+    let path = request_line.next().unwrap().to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    let mut request = Request { method, path, headers, body: String::new() };
+    let content_length = request.header("content-length").map_or(0, |v| v.parse::<usize>().unwrap());
+    while data.len() < header_len + content_length {
+        let n = stream.read(&mut chunk).await.unwrap();
+        data.extend_from_slice(&chunk[..n]);
+    }
+    request.body = String::from_utf8_lossy(&data[header_len..]).into_owned();
+
+    let (status, body) = handler(request);
+    let reason = reqwest::StatusCode::from_u16(status).ok()
+        .and_then(|s| s.canonical_reason())
+        .unwrap_or("");
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, reason, body.len(), body
+    );
+    stream.write_all(response.as_bytes()).await.unwrap();
+}
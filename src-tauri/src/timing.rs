@@ -0,0 +1,174 @@
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::ClientBuilder;
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tower::{Layer, Service};
+
+use crate::models::RequestTiming;
+
+// reqwest has no timing hooks of its own, so the DNS resolver and the connector
+// note when they run. They are polled inside the task that sends the request,
+// which is where these events are collected.
+tokio::task_local! {
+    static CONNECTION_EVENTS: RefCell<ConnectionEvents>;
+}
+
+/// When each step of opening a connection happened. All `None` when the request
+/// went out on a pooled connection.
+#[derive(Default, Clone, Copy)]
+pub struct ConnectionEvents {
+    dns_start: Option<Instant>,
+    dns_end: Option<Instant>,
+    connect_start: Option<Instant>,
+    tcp_end: Option<Instant>,
+    connect_end: Option<Instant>,
+}
+
+fn record(update: impl FnOnce(&mut ConnectionEvents)) {
+    // Outside `observe()` (e.g. a connection hyper finishes in the background)
+    // there is nothing to attribute the event to.
+    let _ = CONNECTION_EVENTS.try_with(|events| update(&mut events.borrow_mut()));
+}
+
+/// Whether a DNS lookup has started and not yet finished.
+fn resolving() -> bool {
+    CONNECTION_EVENTS.try_with(|events| {
+        let events = events.borrow();
+        events.dns_start.is_some() && events.dns_end.is_none()
+    }).unwrap_or(false)
+}
+
+/// Run a request future, collecting the connection events it triggers.
+pub async fn observe<F: Future>(request: F) -> (F::Output, ConnectionEvents) {
+    CONNECTION_EVENTS.scope(RefCell::new(ConnectionEvents::default()), async move {
+        let output = request.await;
+        (output, CONNECTION_EVENTS.with(|events| *events.borrow()))
+    }).await
+}
+
+impl ConnectionEvents {
+    /// Split a request into phases. `sent_at` is when the request was handed to
+    /// the client, `headers_at` when the response headers arrived and `finished_at`
+    /// when the body was read.
+    pub fn breakdown(&self, sent_at: Instant, headers_at: Instant, finished_at: Instant) -> RequestTiming {
+        let micros = |from: Instant, to: Instant| to.saturating_duration_since(from).as_micros() as i64;
+
+        let (dns_us, connect_us, tls_us, ready_at) = match (self.connect_start, self.connect_end) {
+            (Some(connect_start), Some(connect_end)) => {
+                let dns_us = match (self.dns_start, self.dns_end) {
+                    (Some(start), Some(end)) => micros(start, end),
+                    _ => 0, // IP address, nothing to resolve
+                };
+                let tcp_start = self.dns_end.unwrap_or(connect_start);
+                // Whatever follows the TCP connect is the TLS handshake
+                let tcp_end = self.tcp_end.unwrap_or(connect_end);
+                (dns_us, micros(tcp_start, tcp_end), micros(tcp_end, connect_end), connect_end)
+            }
+            _ => (0, 0, 0, sent_at),
+        };
+
+        RequestTiming {
+            dns_us,
+            connect_us,
+            tls_us,
+            ttfb_us: micros(ready_at, headers_at),
+            download_us: micros(headers_at, finished_at),
+            total_us: micros(sent_at, finished_at),
+            connection_reused: self.connect_end.is_none(),
+        }
+    }
+}
+
+/// Install the DNS and connection hooks on a client.
+pub fn instrument(builder: ClientBuilder) -> ClientBuilder {
+    builder
+        .dns_resolver(Arc::new(TimedResolver))
+        .connector_layer(ConnectTimingLayer)
+}
+
+/// The system resolver (getaddrinfo on a blocking thread, as reqwest's default).
+struct TimedResolver;
+
+impl Resolve for TimedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            record(|e| e.dns_start = Some(Instant::now()));
+            let addrs = tokio::net::lookup_host((name.as_str(), 0)).await?.collect::<Vec<_>>();
+            record(|e| e.dns_end = Some(Instant::now()));
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Wraps reqwest's connector, which resolves, connects and runs the TLS handshake.
+#[derive(Clone)]
+struct ConnectTimingLayer;
+
+impl<S> Layer<S> for ConnectTimingLayer {
+    type Service = ConnectTiming<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ConnectTiming(inner)
+    }
+}
+
+#[derive(Clone)]
+struct ConnectTiming<S>(S);
+
+impl<S, R> Service<R> for ConnectTiming<S>
+where
+    S: Service<R>,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.0.poll_ready(cx)
+    }
+
+    fn call(&mut self, dst: R) -> Self::Future {
+        Box::pin(Connecting { inner: Box::pin(self.0.call(dst)), polled: false, connecting: false })
+    }
+}
+
+/// The connector's future. It resolves the host, connects and then runs the TLS
+/// handshake; the first wakeup after the TCP connect is issued is the socket
+/// becoming writable, so that poll marks the end of the TCP connect.
+struct Connecting<F> {
+    inner: Pin<Box<F>>,
+    polled: bool,
+    /// A poll returned pending after the DNS lookup, waiting on the TCP connect.
+    connecting: bool,
+}
+
+impl<F, T, E> Future for Connecting<F>
+where
+    F: Future<Output = Result<T, E>>,
+{
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let now = Instant::now();
+        if !self.polled {
+            self.polled = true;
+            record(|e| e.connect_start = Some(now));
+        } else if self.connecting {
+            record(|e| {
+                e.tcp_end.get_or_insert(now);
+            });
+        }
+        let poll = self.inner.as_mut().poll(cx);
+        match poll {
+            Poll::Ready(Ok(_)) => record(|e| e.connect_end = Some(Instant::now())),
+            Poll::Ready(Err(_)) => {}
+            Poll::Pending => self.connecting = !resolving(),
+        }
+        poll
+    }
+}
@@ -0,0 +1,271 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chrono::{DateTime, Utc};
+use reqwest::{Certificate, ClientBuilder, Identity, Url};
+use std::net::IpAddr;
+use x509_parser::extensions::GeneralName;
+
+use crate::connection::ConnectionSettings;
+use crate::error::{describe_error, AppError};
+use crate::models::ServerConfig;
+
+/// How the SCIM server's certificate is verified.
+#[derive(Clone, Copy, PartialEq)]
+pub enum TlsMode {
+    /// Verify against the built-in web PKI roots.
+    Strict,
+    /// Verify against the built-in roots plus the server config's CA bundle.
+    CustomCa,
+    /// Accept any certificate (self-signed dev/test servers).
+    Insecure,
+}
+
+/// TLS settings for one server config, resolved once and applied to every HTTP
+/// client built for it.
+#[derive(Clone)]
+pub struct TlsSettings {
+    mode: TlsMode,
+    ca_certs: Vec<Certificate>,
+    identity: Option<Identity>,
+}
+
+impl TlsSettings {
+    pub fn from_config(config: &ServerConfig) -> Result<Self, String> {
+        let mode = match config.tls_mode.as_deref().unwrap_or("strict") {
+            "strict" => TlsMode::Strict,
+            "custom_ca" => TlsMode::CustomCa,
+            "insecure" => TlsMode::Insecure,
+            other => return Err(format!("Unsupported TLS mode: {}", other)),
+        };
+
+        let ca_certs = if mode == TlsMode::CustomCa {
+            let bundle = config.ca_bundle.as_deref().map(str::trim).unwrap_or("");
+            if bundle.is_empty() {
+                return Err("Custom CA mode requires a CA bundle (PEM)".to_string());
+            }
+            let certs = Certificate::from_pem_bundle(bundle.as_bytes())
+                .map_err(|e| format!("Invalid CA bundle: {}", e))?;
+            if certs.is_empty() {
+                return Err("CA bundle does not contain any PEM certificates".to_string());
+            }
+            certs
+        } else {
+            Vec::new()
+        };
+
+        Ok(TlsSettings {
+            mode,
+            ca_certs,
+            identity: load_client_identity(config)?,
+        })
+    }
+
+    pub fn apply(&self, builder: ClientBuilder) -> ClientBuilder {
+        let builder = self.apply_verifying(builder);
+        if self.mode == TlsMode::Insecure {
+            builder.danger_accept_invalid_certs(true)
+        } else {
+            builder
+        }
+    }
+
+    /// Like `apply()`, but never disables certificate verification.
+    fn apply_verifying(&self, mut builder: ClientBuilder) -> ClientBuilder {
+        for cert in &self.ca_certs {
+            builder = builder.add_root_certificate(cert.clone());
+        }
+        if let Some(ref identity) = self.identity {
+            builder = builder.identity(identity.clone());
+        }
+        builder
+    }
+}
+
+/// Build the client certificate identity for mutual TLS, if the server config has one.
+///
+/// `client_cert_format` selects how `client_cert` is stored:
+/// - `"pem"`: PEM certificate chain; the private key is either appended to it or
+///   kept separately in `client_key`.
+/// - `"pkcs12"`: base64-encoded PKCS#12 (.p12/.pfx) archive, unlocked with
+///   `client_cert_password`. rustls only understands PEM identities, so the
+///   archive is unpacked and re-encoded as PEM here.
+fn load_client_identity(config: &ServerConfig) -> Result<Option<Identity>, String> {
+    let cert = match config.client_cert.as_deref().map(str::trim) {
+        Some(c) if !c.is_empty() => c,
+        _ => return Ok(None),
+    };
+
+    let pem = match config.client_cert_format.as_deref().unwrap_or("pem") {
+        "pem" => {
+            let mut pem = cert.to_string();
+            if let Some(key) = config.client_key.as_deref().map(str::trim).filter(|k| !k.is_empty()) {
+                pem.push('\n');
+                pem.push_str(key);
+            }
+            if !pem.contains("PRIVATE KEY-----") {
+                return Err("Client certificate has no private key — paste the key into the private key field or append it to the certificate PEM".to_string());
+            }
+            pem
+        }
+        "pkcs12" => pkcs12_to_pem(cert, config.client_cert_password.as_deref().unwrap_or(""))?,
+        other => return Err(format!("Unsupported client certificate format: {}", other)),
+    };
+
+    Identity::from_pem(pem.as_bytes())
+        .map(Some)
+        .map_err(|e| format!("Invalid client certificate: {}", e))
+}
+
+fn pkcs12_to_pem(encoded: &str, password: &str) -> Result<String, String> {
+    let der = BASE64.decode(encoded.split_whitespace().collect::<String>())
+        .map_err(|e| format!("PKCS#12 client certificate is not valid base64: {}", e))?;
+    let keystore = p12_keystore::KeyStore::from_pkcs12(&der, password)
+        .map_err(|e| format!("Failed to open PKCS#12 client certificate (wrong password?): {}", e))?;
+    let (_, chain) = keystore.private_key_chain()
+        .ok_or("PKCS#12 archive does not contain a private key")?;
+
+    let mut pem = String::new();
+    for cert in chain.chain() {
+        pem.push_str(&der_to_pem("CERTIFICATE", cert.as_der()));
+    }
+    pem.push_str(&der_to_pem("PRIVATE KEY", chain.key()));
+    Ok(pem)
+}
+
+fn der_to_pem(label: &str, der: &[u8]) -> String {
+    let encoded = BASE64.encode(der);
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+    for line in encoded.as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(line).unwrap_or_default());
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", label));
+    pem
+}
+
+/// What the server presented during the TLS handshake, and whether it verifies.
+pub struct CertificateReport {
+    pub host: String,
+    pub subject: String,
+    pub issuer: String,
+    pub not_before: DateTime<Utc>,
+    pub not_after: DateTime<Utc>,
+    pub dns_names: Vec<String>,
+    pub ip_addresses: Vec<IpAddr>,
+    pub self_signed: bool,
+    /// Handshake error from a verifying connection, `None` if the chain is trusted.
+    pub verification_error: Option<String>,
+}
+
+impl CertificateReport {
+    pub fn is_expired(&self) -> bool {
+        Utc::now() > self.not_after
+    }
+
+    pub fn is_not_yet_valid(&self) -> bool {
+        Utc::now() < self.not_before
+    }
+
+    /// Match the host against the subjectAltName entries (RFC 6125). The subject
+    /// CN is deliberately ignored, as rustls and browsers do.
+    pub fn matches_host(&self) -> bool {
+        if let Ok(ip) = self.host.trim_matches(|c| c == '[' || c == ']').parse::<IpAddr>() {
+            return self.ip_addresses.contains(&ip);
+        }
+        let host = self.host.to_lowercase();
+        self.dns_names.iter().any(|name| {
+            let name = name.to_lowercase();
+            match name.strip_prefix("*.") {
+                // A wildcard covers exactly one left-most label
+                Some(suffix) => host.split_once('.').is_some_and(|(label, rest)| !label.is_empty() && rest == suffix),
+                None => name == host,
+            }
+        })
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "host": self.host,
+            "subject": self.subject,
+            "issuer": self.issuer,
+            "notBefore": self.not_before.to_rfc3339(),
+            "notAfter": self.not_after.to_rfc3339(),
+            "subjectAltNames": self.dns_names.iter().cloned()
+                .chain(self.ip_addresses.iter().map(|ip| ip.to_string()))
+                .collect::<Vec<_>>(),
+            "selfSigned": self.self_signed,
+            "verificationError": self.verification_error,
+        })
+    }
+}
+
+/// Connect to the server twice: once accepting any certificate to capture the leaf
+/// certificate, and once with verification enabled to see whether the chain is
+/// trusted. Returns `Ok(None)` for plain-HTTP base URLs.
+pub async fn inspect_certificate(
+    base_url: &str,
+    settings: &TlsSettings,
+    connection: &ConnectionSettings,
+) -> Result<Option<CertificateReport>, AppError> {
+    let url = Url::parse(base_url).map_err(|e| AppError::Config(format!("Invalid base URL: {}", e)))?;
+    if url.scheme() != "https" {
+        return Ok(None);
+    }
+    let host = url.host_str().ok_or_else(|| AppError::Config("Base URL has no host".to_string()))?.to_string();
+
+    let inspecting = connection.apply(settings.apply_verifying(ClientBuilder::new()))
+        .danger_accept_invalid_certs(true)
+        .tls_info(true)
+        .build()
+        .map_err(|e| AppError::Other(format!("Failed to create HTTP client: {}", e)))?;
+    let resp = inspecting.get(url.clone()).send().await
+        .map_err(|e| AppError::request("Request failed", &e))?;
+    let der = resp.extensions().get::<reqwest::tls::TlsInfo>()
+        .and_then(|info| info.peer_certificate())
+        .ok_or_else(|| AppError::Tls("Server did not present a certificate".to_string()))?
+        .to_vec();
+    // Release the connection first; some test servers accept one at a time
+    drop(resp);
+    drop(inspecting);
+
+    let verifying = connection.apply(settings.apply_verifying(ClientBuilder::new()))
+        .build()
+        .map_err(|e| AppError::Other(format!("Failed to create HTTP client: {}", e)))?;
+    let verification_error = verifying.get(url).send().await
+        .err()
+        .map(|e| describe_error(&e));
+
+    let (_, cert) = x509_parser::parse_x509_certificate(&der)
+        .map_err(|e| AppError::Tls(format!("Failed to parse server certificate: {}", e)))?;
+
+    let mut dns_names = Vec::new();
+    let mut ip_addresses = Vec::new();
+    if let Ok(Some(san)) = cert.subject_alternative_name() {
+        for name in &san.value.general_names {
+            match name {
+                GeneralName::DNSName(dns) => dns_names.push(dns.to_string()),
+                GeneralName::IPAddress(bytes) => {
+                    if let Ok(octets) = <[u8; 4]>::try_from(*bytes) {
+                        ip_addresses.push(IpAddr::from(octets));
+                    } else if let Ok(octets) = <[u8; 16]>::try_from(*bytes) {
+                        ip_addresses.push(IpAddr::from(octets));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let validity = cert.validity();
+    Ok(Some(CertificateReport {
+        host,
+        subject: cert.subject().to_string(),
+        issuer: cert.issuer().to_string(),
+        not_before: DateTime::from_timestamp(validity.not_before.timestamp(), 0).unwrap_or_default(),
+        not_after: DateTime::from_timestamp(validity.not_after.timestamp(), 0).unwrap_or_default(),
+        dns_names,
+        ip_addresses,
+        self_signed: cert.subject().as_raw() == cert.issuer().as_raw(),
+        verification_error,
+    }))
+}
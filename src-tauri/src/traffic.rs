@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+use crate::models::{HttpExchange, HttpHeader, RequestTiming};
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Headers whose values never leave the app, whatever the auth type.
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+];
+
+/// Opt-in capture of every HTTP exchange a `ScimClient` makes during a test run.
+///
+/// Exchanges are kept in memory while the run is in progress and written to the
+/// `http_exchanges` table once it finishes, the same way run results are.
+pub struct TrafficRecorder {
+    test_run_id: String,
+    redacted_headers: HashSet<String>,
+    next_sequence: AtomicI64,
+    exchanges: Mutex<Vec<HttpExchange>>,
+}
+
+/// The request half of an exchange, captured before it is sent.
+pub struct PendingExchange {
+    sequence: i64,
+    started_at: DateTime<Utc>,
+    method: String,
+    url: String,
+    request_headers: Vec<HttpHeader>,
+    request_body: Option<String>,
+}
+
+impl TrafficRecorder {
+    /// `extra_redacted_headers` adds server-specific secrets, such as a custom API key header.
+    pub fn new(test_run_id: &str, extra_redacted_headers: &[&str]) -> Self {
+        let redacted_headers = SENSITIVE_HEADERS.iter()
+            .chain(extra_redacted_headers)
+            .map(|h| h.trim().to_lowercase())
+            .filter(|h| !h.is_empty())
+            .collect();
+        TrafficRecorder {
+            test_run_id: test_run_id.to_string(),
+            redacted_headers,
+            next_sequence: AtomicI64::new(0),
+            exchanges: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn begin(&self, request: &reqwest::Request, body: Option<&str>) -> PendingExchange {
+        PendingExchange {
+            sequence: self.next_sequence.fetch_add(1, Ordering::Relaxed),
+            started_at: Utc::now(),
+            method: request.method().to_string(),
+            url: request.url().to_string(),
+            request_headers: self.capture_headers(request.headers()),
+            request_body: body.map(str::to_string),
+        }
+    }
+
+    /// Record a completed exchange.
+    pub fn finish(&self, pending: PendingExchange, response: &ResponseCapture, duration_ms: i64) {
+        self.push(pending, Some(response), duration_ms, None);
+    }
+
+    /// Record an exchange that never got a response.
+    pub fn fail(&self, pending: PendingExchange, error: &str, duration_ms: i64) {
+        self.push(pending, None, duration_ms, Some(error.to_string()));
+    }
+
+    /// Hand the recorded exchanges over for saving, in the order they were sent.
+    pub fn take(&self) -> Vec<HttpExchange> {
+        let mut exchanges = std::mem::take(&mut *self.exchanges.lock().unwrap());
+        exchanges.sort_by_key(|e| e.sequence);
+        exchanges
+    }
+
+    pub fn capture_headers(&self, headers: &HeaderMap) -> Vec<HttpHeader> {
+        headers.iter().map(|(name, value)| HttpHeader {
+            name: name.to_string(),
+            value: if self.redacted_headers.contains(name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            },
+        }).collect()
+    }
+
+    fn push(&self, pending: PendingExchange, response: Option<&ResponseCapture>, duration_ms: i64, error: Option<String>) {
+        let exchange = HttpExchange {
+            id: Uuid::new_v4().to_string(),
+            test_run_id: self.test_run_id.clone(),
+            sequence: pending.sequence,
+            started_at: pending.started_at.to_rfc3339(),
+            method: pending.method,
+            url: pending.url,
+            request_headers: pending.request_headers,
+            request_body: pending.request_body,
+            response_status: response.map(|r| r.status as i32),
+            response_status_text: response.map(|r| r.status_text.clone()),
+            http_version: response.map(|r| r.http_version.clone()),
+            response_headers: response.map(|r| r.headers.clone()).unwrap_or_default(),
+            response_body: response.map(|r| r.body.clone()),
+            duration_ms,
+            error,
+            timing: response.map(|r| r.timing),
+        };
+        self.exchanges.lock().unwrap().push(exchange);
+    }
+}
+
+/// The response half of an exchange, captured once its body has been read.
+pub struct ResponseCapture {
+    pub status: u16,
+    pub status_text: String,
+    pub http_version: String,
+    pub headers: Vec<HttpHeader>,
+    pub body: String,
+    pub timing: RequestTiming,
+}
@@ -1,12 +1,17 @@
-use chrono::Utc;
+use chrono::{DateTime, FixedOffset, Utc};
 use uuid::Uuid;
+use reqwest::Method;
 use serde_json::Value;
 use tauri::{AppHandle, Emitter};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
+use crate::conformance::SchemaCatalog;
+use crate::filter::{self, Filter};
+use crate::requirements::Requirement;
+use crate::scim::{self, Address, BulkOperation, BulkRequest, EnterpriseUser, Group, ListResponse, Member, Meta, MultiValued, PatchOp, PatchOperation, Schema, ScimError, SearchRequest, ServiceProviderConfig, User};
 use crate::models::*;
-use crate::scim_client::ScimClient;
+use crate::scim_client::{Precondition, ScimClient, ScimResponse};
 
 /// A custom / extension attribute discovered from the SCIM /Schemas endpoint.
 struct SchemaAttribute {
@@ -17,12 +22,44 @@ struct SchemaAttribute {
     attr_type: String, // "boolean", "string", "integer", "decimal", "dateTime", "reference"
 }
 
+/// How many pages of /Users to scan for a sample user when field mapping
+/// cannot create its own.
+const FIELD_MAPPING_SCAN_PAGES: usize = 10;
+
+/// Larger bulk limits are not probed, to keep the oversized requests sane.
+const BULK_MAX_OPERATIONS_TESTED: u64 = 1000;
+const BULK_MAX_PAYLOAD_TESTED: u64 = 10 * 1024 * 1024;
+
+/// Page size for the sort check when it cannot filter down to its own users.
+const SORT_CHECK_PAGE_SIZE: usize = 50;
+
+/// The most users the filter.maxResults check creates to exceed the limit.
+const MAX_RESULTS_FIXTURES: u64 = 25;
+
+/// Users added to the group_operations group on top of the first member.
+const GROUP_EXTRA_MEMBERS: usize = 4;
+
+/// Servers commonly keep `meta.lastModified` to whole seconds, so a write in
+/// the same second as the previous one cannot move it.
+const LAST_MODIFIED_WAIT: std::time::Duration = std::time::Duration::from_millis(1100);
+
+/// Verifies a user read back after a PATCH; returns the failure, if any.
+type UserCheck = fn(&User) -> Option<String>;
+
 pub struct ValidationEngine;
 
 // Public wrapper so commands.rs can call schema discovery
 impl ValidationEngine {
+    /// The server's /ServiceProviderConfig, or `None` when it cannot be read.
+    pub async fn fetch_service_provider_config(client: &ScimClient) -> Option<ServiceProviderConfig> {
+        match client.get("/ServiceProviderConfig").await {
+            Ok(resp) if resp.status == 200 => serde_json::from_str(&resp.body).ok(),
+            _ => None,
+        }
+    }
+
     pub async fn discover_custom_attributes(client: &ScimClient) -> Vec<DiscoveredSchemaAttribute> {
-        let internal = Self::discover_schema_attributes(client).await;
+        let internal = Self::custom_schema_attributes(&Self::fetch_schemas(client).await);
         internal.into_iter().map(|a| DiscoveredSchemaAttribute {
             schema_urn: a.schema_urn,
             schema_name: a.schema_name,
@@ -38,6 +75,7 @@ impl ValidationEngine {
         client: &ScimClient,
         test_run_id: &str,
         categories: &[String],
+        capabilities: Option<&ServiceProviderConfig>,
         field_mapping_rules: &[FieldMappingRule],
         user_joining_property: &str,
         group_joining_property: &str,
@@ -46,25 +84,32 @@ impl ValidationEngine {
         let mut results = Vec::new();
         let all_categories: Vec<&str> = categories.iter().map(|s| s.as_str()).collect();
 
-        // Pre-discover custom schema attributes (needs a network call) so we
-        // can compute an accurate test count for progress reporting.
+        // /Schemas is fetched up front: custom_schema needs its attributes for an
+        // accurate test count, and every returned resource is checked against it.
+        let schemas = Self::fetch_schemas(client).await;
         let custom_attrs = if all_categories.contains(&"custom_schema") {
-            Self::discover_schema_attributes(client).await
+            Self::custom_schema_attributes(&schemas)
         } else {
             Vec::new()
         };
+        let catalog = SchemaCatalog::new(schemas);
 
         let mut total_tests = 0usize;
         for cat in &all_categories {
             total_tests += match *cat {
-                "schema_discovery" => 3,
+                "schema_discovery" => 5,
+                "tls_certificate" => 4,
+                "protocol" => 7,
                 "users_crud" => 6,
                 "groups_crud" => 6,
-                "patch_operations" => 4,
-                "filtering_pagination" => 4,
+                "patch_operations" => 13,
+                "filtering_pagination" => 25,
                 "duplicate_detection" => 4,
                 "soft_delete" => 3,
-                "group_operations" => 6,
+                "etag_versioning" => 6,
+                "resource_metadata" => 7,
+                "bulk_operations" => 7,
+                "group_operations" => 12,
                 "field_mapping" => field_mapping_rules.len().max(1),
                 "custom_schema" => Self::count_custom_schema_tests(&custom_attrs),
                 _ => 0,
@@ -79,28 +124,43 @@ impl ValidationEngine {
             }
             let cat_results = match *category {
                 "schema_discovery" => {
-                    Self::test_schema_discovery(app, client, test_run_id, &mut completed, total_tests).await
+                    Self::test_schema_discovery(app, client, test_run_id, capabilities, &mut completed, total_tests).await
+                }
+                "tls_certificate" => {
+                    Self::test_tls_certificate(app, client, test_run_id, &mut completed, total_tests).await
+                }
+                "protocol" => {
+                    Self::test_protocol(app, client, test_run_id, user_joining_property, &mut completed, total_tests).await
                 }
                 "users_crud" => {
-                    Self::test_users_crud(app, client, test_run_id, user_joining_property, &mut completed, total_tests).await
+                    Self::test_users_crud(app, client, test_run_id, capabilities, user_joining_property, &mut completed, total_tests).await
                 }
                 "groups_crud" => {
-                    Self::test_groups_crud(app, client, test_run_id, group_joining_property, &mut completed, total_tests).await
+                    Self::test_groups_crud(app, client, test_run_id, capabilities, group_joining_property, &mut completed, total_tests).await
                 }
                 "patch_operations" => {
-                    Self::test_patch_operations(app, client, test_run_id, user_joining_property, &mut completed, total_tests).await
+                    Self::test_patch_operations(app, client, test_run_id, capabilities, user_joining_property, &mut completed, total_tests).await
                 }
                 "filtering_pagination" => {
-                    Self::test_filtering_pagination(app, client, test_run_id, &mut completed, total_tests).await
+                    Self::test_filtering_pagination(app, client, test_run_id, capabilities, &mut completed, total_tests).await
                 }
                 "duplicate_detection" => {
                     Self::test_duplicate_detection(app, client, test_run_id, user_joining_property, group_joining_property, &mut completed, total_tests).await
                 }
                 "soft_delete" => {
-                    Self::test_soft_delete(app, client, test_run_id, user_joining_property, &mut completed, total_tests).await
+                    Self::test_soft_delete(app, client, test_run_id, capabilities, user_joining_property, &mut completed, total_tests).await
+                }
+                "etag_versioning" => {
+                    Self::test_etag_versioning(app, client, test_run_id, capabilities, user_joining_property, &mut completed, total_tests).await
+                }
+                "resource_metadata" => {
+                    Self::test_resource_metadata(app, client, test_run_id, capabilities, user_joining_property, &mut completed, total_tests).await
+                }
+                "bulk_operations" => {
+                    Self::test_bulk_operations(app, client, test_run_id, capabilities, &mut completed, total_tests).await
                 }
                 "group_operations" => {
-                    Self::test_group_operations(app, client, test_run_id, group_joining_property, &mut completed, total_tests).await
+                    Self::test_group_operations(app, client, test_run_id, capabilities, group_joining_property, &mut completed, total_tests).await
                 }
                 "field_mapping" => {
                     Self::test_field_mapping(app, client, test_run_id, field_mapping_rules, &mut completed, total_tests).await
@@ -113,9 +173,35 @@ impl ValidationEngine {
             results.extend(cat_results);
         }
 
+        if !catalog.is_empty() {
+            for result in &mut results {
+                result.schema_violations = Self::check_schema_conformance(&catalog, result);
+                if result.outcome == TestOutcome::Passed && !result.schema_violations.is_empty() {
+                    result.outcome = TestOutcome::Warning;
+                }
+            }
+        }
+
         results
     }
 
+    /// Check the resources a test got back against the server's schemas.
+    fn check_schema_conformance(catalog: &SchemaCatalog, result: &ValidationResult) -> Vec<SchemaViolation> {
+        if !result.response_status.is_some_and(|s| (200..300).contains(&s)) {
+            return Vec::new();
+        }
+        let body = match result.response_body.as_deref().and_then(|b| serde_json::from_str::<Value>(b).ok()) {
+            Some(body) => body,
+            None => return Vec::new(),
+        };
+        let sent = result.request_body.as_deref().and_then(|b| serde_json::from_str::<Value>(b).ok());
+        let sent_resource = sent.as_ref().filter(|_| result.http_method == "POST" || result.http_method == "PUT");
+        // attributes / excludedAttributes, in the query or a SearchRequest, leave attributes out on purpose
+        let projected = result.url.to_ascii_lowercase().contains("attributes=")
+            || sent.as_ref().is_some_and(|s| s.get("attributes").is_some() || s.get("excludedAttributes").is_some());
+        catalog.check_response(&body, sent_resource, projected)
+    }
+
     fn emit_progress(app: &AppHandle, test_run_id: &str, test_name: &str, category: &str, completed: usize, total: usize) {
         let _ = app.emit("validation-progress", ValidationProgress {
             test_run_id: test_run_id.to_string(),
@@ -126,71 +212,48 @@ impl ValidationEngine {
         });
     }
 
-    /// Look up the "Resources" key case-insensitively.
-    /// SCIM RFC 7644 uses "Resources" (capital R) but many servers return "resources".
-    fn get_resources(json: &Value) -> Option<&Value> {
-        json.get("Resources").or_else(|| json.get("resources"))
-    }
-
-    /// Fetch /Schemas and extract extension-schema attributes (non-core, non-complex,
-    /// non-multi-valued).  Used by the `custom_schema` validation category.
-    async fn discover_schema_attributes(client: &ScimClient) -> Vec<SchemaAttribute> {
+    /// Fetch /Schemas with the full attribute tree. Empty when the server does
+    /// not publish its schemas.
+    async fn fetch_schemas(client: &ScimClient) -> Vec<Schema> {
         let resp = match client.get("/Schemas").await {
             Ok(r) if r.status == 200 => r,
             _ => return Vec::new(),
         };
 
-        let json: Value = match serde_json::from_str(&resp.body) {
-            Ok(j) => j,
-            Err(_) => return Vec::new(),
-        };
-
         // Schemas may be in a ListResponse (Resources) or a direct array
-        let schemas: Vec<&Value> = if let Some(resources) = Self::get_resources(&json) {
-            resources.as_array().map(|a| a.iter().collect()).unwrap_or_default()
-        } else if let Some(arr) = json.as_array() {
-            arr.iter().collect()
-        } else {
-            return Vec::new();
-        };
-
-        // Core schemas whose attributes are already covered by other test categories
-        let core_schema_prefixes = [
-            "urn:ietf:params:scim:schemas:core:2.0:",
-            "urn:ietf:params:scim:api:messages:2.0:",
-        ];
+        match ListResponse::<Schema>::parse_lenient(&resp.body) {
+            Ok(list) => list.into_resources(),
+            Err(_) => Vec::new(),
+        }
+    }
 
+    /// Extract extension-schema attributes (non-core, non-complex,
+    /// non-multi-valued).  Used by the `custom_schema` validation category.
+    fn custom_schema_attributes(schemas: &[Schema]) -> Vec<SchemaAttribute> {
         let mut attrs = Vec::new();
 
-        for schema in &schemas {
-            let schema_id = schema.get("id").and_then(|v| v.as_str()).unwrap_or("");
-            // Skip core schemas
-            if core_schema_prefixes.iter().any(|p| schema_id.starts_with(p)) {
+        for schema in schemas {
+            // Skip core schemas, whose attributes are covered by other test categories
+            if schema.is_core() {
                 continue;
             }
             // Only consider schemas that relate to User (contain "User" or are generic
             // extensions).  ResourceType-specific filtering could be done via
             // /ResourceTypes, but this heuristic works for most deployments.
-            let schema_name = schema.get("name").and_then(|v| v.as_str()).unwrap_or("Extension");
-
-            if let Some(attributes) = schema.get("attributes").and_then(|v| v.as_array()) {
-                for attr in attributes {
-                    let name = attr.get("name").and_then(|v| v.as_str()).unwrap_or("");
-                    let attr_type = attr.get("type").and_then(|v| v.as_str()).unwrap_or("string");
-                    let multi_valued = attr.get("multiValued").and_then(|v| v.as_bool()).unwrap_or(false);
-
-                    // Skip empty, complex, binary, or multi-valued attributes
-                    if name.is_empty() || attr_type == "complex" || attr_type == "binary" || multi_valued {
-                        continue;
-                    }
+            let schema_name = schema.name.as_deref().unwrap_or("Extension");
 
-                    attrs.push(SchemaAttribute {
-                        schema_urn: schema_id.to_string(),
-                        schema_name: schema_name.to_string(),
-                        attr_name: name.to_string(),
-                        attr_type: attr_type.to_lowercase(),
-                    });
+            for attr in &schema.attributes {
+                // Skip empty, complex, binary, or multi-valued attributes
+                if attr.name.is_empty() || attr.kind == "complex" || attr.kind == "binary" || attr.multi_valued {
+                    continue;
                 }
+
+                attrs.push(SchemaAttribute {
+                    schema_urn: schema.id.clone(),
+                    schema_name: schema_name.to_string(),
+                    attr_name: attr.name.clone(),
+                    attr_type: attr.kind.to_lowercase(),
+                });
             }
         }
 
@@ -210,6 +273,12 @@ impl ValidationEngine {
         }
     }
 
+    /// A short random tag that keeps the names of the resources a run creates
+    /// from colliding with earlier runs.
+    fn unique_suffix() -> String {
+        Uuid::new_v4().to_string().split('-').next().unwrap().to_string()
+    }
+
     /// Count how many tests the custom_schema category will produce for a given
     /// set of discovered attributes.
     fn count_custom_schema_tests(attrs: &[SchemaAttribute]) -> usize {
@@ -226,15 +295,32 @@ impl ValidationEngine {
         test_run_id: &str,
         test_name: &str,
         category: &str,
+        requirement: Requirement,
         http_method: &str,
         url: &str,
         request_body: Option<String>,
         response_status: Option<i32>,
         response_body: Option<String>,
         duration_ms: i64,
-        passed: bool,
+        timing: Option<RequestTiming>,
+        outcome: impl Into<TestOutcome>,
         failure_reason: Option<String>,
     ) -> ValidationResult {
+        let outcome = outcome.into();
+        // Point at the server's own explanation when it answered with a SCIM error
+        let failure_reason = match failure_reason {
+            Some(reason) if outcome == TestOutcome::Failed => {
+                let scim_error = response_status.zip(response_body.as_deref())
+                    .filter(|(status, _)| *status >= 400)
+                    .and_then(|(status, body)| ScimError::parse(status as u16, body))
+                    .and_then(|e| e.summary());
+                match scim_error {
+                    Some(summary) => Some(format!("{} ({})", reason, summary)),
+                    None => Some(reason),
+                }
+            }
+            other => other,
+        };
         ValidationResult {
             id: Uuid::new_v4().to_string(),
             test_run_id: test_run_id.to_string(),
@@ -246,18 +332,70 @@ impl ValidationEngine {
             response_status,
             response_body,
             duration_ms,
-            passed,
+            timing,
+            passed: outcome.is_pass(),
+            outcome,
             failure_reason,
             executed_at: Utc::now().to_rfc3339(),
+            schema_violations: Vec::new(),
+            depends_on: Vec::new(),
+            requirement_level: requirement.level,
+            rfc_reference: requirement.section.map(str::to_string),
+            severity: requirement.severity,
+        }
+    }
+
+    /// Why a test is skipped without sending its request: one of the earlier
+    /// `results` it builds on, at `prerequisites`, did not pass.
+    fn unmet_prerequisite(results: &[ValidationResult], prerequisites: &[usize]) -> Option<String> {
+        let blocking = prerequisites.iter().map(|&i| &results[i]).find(|r| !r.outcome.is_pass())?;
+        let what = match blocking.outcome {
+            TestOutcome::Error => "got no response",
+            TestOutcome::Skipped => "was skipped",
+            _ => "failed",
+        };
+        Some(format!("Skipped: depends on \"{}\", which {}", blocking.test_name, what))
+    }
+
+    /// Why a test is skipped without sending its request: the
+    /// ServiceProviderConfig declares a feature it needs unsupported. A server
+    /// that does not say either way is tested.
+    fn unsupported(capabilities: Option<&ServiceProviderConfig>, feature: &str) -> Option<String> {
+        (capabilities.and_then(|c| c.supports(feature)) == Some(false))
+            .then(|| format!("Skipped: ServiceProviderConfig reports {}.supported = false", feature))
+    }
+
+    /// Record the prerequisites of the test just added to `results`.
+    fn record_prerequisites(results: &mut [ValidationResult], prerequisites: &[usize]) {
+        let names: Vec<String> = prerequisites.iter().map(|&i| results[i].test_name.clone()).collect();
+        if let Some(result) = results.last_mut() {
+            result.depends_on = names;
         }
     }
 
+    /// A test whose request got no response: it could not be sent, or timed out.
+    #[allow(clippy::too_many_arguments)]
+    fn error_result(
+        test_run_id: &str,
+        test_name: &str,
+        category: &str,
+        requirement: Requirement,
+        http_method: &str,
+        url: &str,
+        request_body: Option<String>,
+        error: impl std::fmt::Display,
+    ) -> ValidationResult {
+        Self::make_result(test_run_id, test_name, category, requirement, http_method, url,
+            request_body, None, None, 0, None, TestOutcome::Error, Some(error.to_string()))
+    }
+
     // ── Schema Discovery Tests ──
 
     async fn test_schema_discovery(
         app: &AppHandle,
         client: &ScimClient,
         test_run_id: &str,
+        capabilities: Option<&ServiceProviderConfig>,
         completed: &mut usize,
         total: usize,
     ) -> Vec<ValidationResult> {
@@ -266,6 +404,7 @@ impl ValidationEngine {
 
         // Test 1: GET /ServiceProviderConfig
         let test_name = "GET /ServiceProviderConfig";
+        let requirement = Requirement::must("RFC 7644 §4", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
         match client.get("/ServiceProviderConfig").await {
             Ok(resp) => {
@@ -274,10 +413,15 @@ impl ValidationEngine {
                     Some(format!("Expected status 200, got {}", resp.status))
                 } else {
                     // Validate the response has required fields
-                    match serde_json::from_str::<Value>(&resp.body) {
-                        Ok(json) => {
-                            if json.get("schemas").is_none() {
+                    match serde_json::from_str::<ServiceProviderConfig>(&resp.body) {
+                        Ok(config) => {
+                            let undeclared: Vec<&str> = scim::SPC_FEATURES.iter().copied()
+                                .filter(|f| config.supports(f).is_none())
+                                .collect();
+                            if config.schemas.is_empty() {
                                 Some("Response missing 'schemas' field".to_string())
+                            } else if !undeclared.is_empty() {
+                                Some(format!("Required capabilities are not declared: {}", undeclared.join(", ")))
                             } else {
                                 None
                             }
@@ -286,17 +430,16 @@ impl ValidationEngine {
                     }
                 };
                 results.push(Self::make_result(
-                    test_run_id, test_name, category, "GET",
+                    test_run_id, test_name, category, requirement, "GET",
                     "/ServiceProviderConfig", None,
                     Some(resp.status as i32), Some(resp.body),
-                    resp.duration_ms, failure.is_none(), failure,
+                    resp.duration_ms, Some(resp.timing), failure.is_none(), failure,
                 ));
             }
             Err(e) => {
-                results.push(Self::make_result(
-                    test_run_id, test_name, category, "GET",
-                    "/ServiceProviderConfig", None, None, None,
-                    0, false, Some(e),
+                results.push(Self::error_result(
+                    test_run_id, test_name, category, requirement, "GET",
+                    "/ServiceProviderConfig", None, e,
                 ));
             }
         }
@@ -304,6 +447,7 @@ impl ValidationEngine {
 
         // Test 2: GET /Schemas
         let test_name = "GET /Schemas";
+        let requirement = Requirement::must("RFC 7644 §4", Severity::Medium);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
         match client.get("/Schemas").await {
             Ok(resp) => {
@@ -315,7 +459,8 @@ impl ValidationEngine {
                     match serde_json::from_str::<Value>(&resp.body) {
                         Ok(json) => {
                             // Should contain schemas array or be a ListResponse
-                            let has_schemas = Self::get_resources(&json).is_some() || json.is_array();
+                            let list = serde_json::from_value::<ListResponse<Schema>>(json.clone()).ok();
+                            let has_schemas = list.is_some_and(|l| l.resources.is_some()) || json.is_array();
                             if !has_schemas {
                                 passed = false;
                                 failure = Some("Response should contain 'Resources' array or be an array of schemas".to_string());
@@ -328,17 +473,16 @@ impl ValidationEngine {
                     }
                 }
                 results.push(Self::make_result(
-                    test_run_id, test_name, category, "GET",
+                    test_run_id, test_name, category, requirement, "GET",
                     "/Schemas", None,
                     Some(resp.status as i32), Some(resp.body),
-                    resp.duration_ms, passed, failure,
+                    resp.duration_ms, Some(resp.timing), passed, failure,
                 ));
             }
             Err(e) => {
-                results.push(Self::make_result(
-                    test_run_id, test_name, category, "GET",
-                    "/Schemas", None, None, None,
-                    0, false, Some(e),
+                results.push(Self::error_result(
+                    test_run_id, test_name, category, requirement, "GET",
+                    "/Schemas", None, e,
                 ));
             }
         }
@@ -346,6 +490,7 @@ impl ValidationEngine {
 
         // Test 3: GET /ResourceTypes
         let test_name = "GET /ResourceTypes";
+        let requirement = Requirement::must("RFC 7644 §4", Severity::Medium);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
         match client.get("/ResourceTypes").await {
             Ok(resp) => {
@@ -356,22 +501,166 @@ impl ValidationEngine {
                     None
                 };
                 results.push(Self::make_result(
-                    test_run_id, test_name, category, "GET",
+                    test_run_id, test_name, category, requirement, "GET",
                     "/ResourceTypes", None,
                     Some(resp.status as i32), Some(resp.body),
-                    resp.duration_ms, passed, failure,
+                    resp.duration_ms, Some(resp.timing), passed, failure,
                 ));
             }
             Err(e) => {
-                results.push(Self::make_result(
-                    test_run_id, test_name, category, "GET",
-                    "/ResourceTypes", None, None, None,
-                    0, false, Some(e),
+                results.push(Self::error_result(
+                    test_run_id, test_name, category, requirement, "GET",
+                    "/ResourceTypes", None, e,
                 ));
             }
         }
         *completed += 1;
 
+        // Test 4: a server claiming sort support must honour sortBy in both directions
+        let test_name = "ServiceProviderConfig - sort.supported is honoured";
+        let requirement = Requirement::must("RFC 7644 §3.4.2.3", Severity::Medium);
+        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+        let sort_skip = match capabilities.map(|c| c.supports("sort")) {
+            None => Some("Skipped: ServiceProviderConfig could not be read".to_string()),
+            Some(Some(true)) => None,
+            Some(_) => Some("Skipped: ServiceProviderConfig does not claim sort support".to_string()),
+        };
+        if let Some(reason) = sort_skip {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Users?sortBy=userName", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else {
+            // Two users, so neither creation order nor newest-first passes for both directions
+            let prefix = format!("scim_sort_test_{}", Self::unique_suffix());
+            let mut fixture_ids = Vec::new();
+            for suffix in ["a", "b"] {
+                let body = scim::to_body(&User::new(&format!("{}_{}@test.example.com", prefix, suffix)).with_active(true));
+                if let Ok(resp) = client.post("/Users", &body).await {
+                    if resp.status == 201 {
+                        fixture_ids.extend(scim::resource_id(&resp.body));
+                    }
+                }
+            }
+            // Scope to the fixtures when filtering works; otherwise the first page must still be ordered
+            let scope = if capabilities.and_then(|c| c.supports("filter")) == Some(true) {
+                format!("filter={}&", Filter::sw("userName", &prefix).encoded())
+            } else {
+                String::new()
+            };
+            let mut last = None;
+            let mut failure = None;
+            for order in ["ascending", "descending"] {
+                let path = format!("/Users?{}sortBy=userName&sortOrder={}&count={}", scope, order, SORT_CHECK_PAGE_SIZE);
+                match client.get(&path).await {
+                    Ok(resp) => {
+                        if resp.status != 200 {
+                            failure = Some(format!("sortOrder={}: expected 200, got {}", order, resp.status));
+                        } else {
+                            let names: Vec<String> = ListResponse::<User>::parse_lenient(&resp.body)
+                                .map(|list| list.into_resources().into_iter().map(|u| u.user_name.to_lowercase()).collect())
+                                .unwrap_or_default();
+                            let sorted = names.windows(2).all(|w| if order == "ascending" { w[0] <= w[1] } else { w[0] >= w[1] });
+                            if names.len() < 2 {
+                                failure = Some(format!("sortOrder={} returned {} users; at least 2 are needed to check the order", order, names.len()));
+                            } else if !sorted {
+                                failure = Some(format!("sort.supported is true, but sortBy=userName&sortOrder={} returned users out of order: {:?}", order, names));
+                            }
+                        }
+                        last = Some((path, resp));
+                    }
+                    Err(e) => {
+                        failure = Some(e.to_string());
+                        last = None;
+                    }
+                }
+                if failure.is_some() {
+                    break;
+                }
+            }
+            match last {
+                Some((path, resp)) => results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                    &path, None, Some(resp.status as i32), Some(resp.body),
+                    resp.duration_ms, Some(resp.timing), failure.is_none(), failure)),
+                None => results.push(Self::error_result(test_run_id, test_name, category, requirement, "GET",
+                    "/Users?sortBy=userName", None, failure.unwrap_or_default())),
+            }
+            for id in &fixture_ids {
+                let _ = client.delete(&format!("/Users/{}", id)).await;
+            }
+        }
+        *completed += 1;
+
+        // Test 5: no response may carry more than filter.maxResults resources;
+        // 400 tooMany is the other way to refuse (RFC 7644 §3.4.2.4)
+        let test_name = "ServiceProviderConfig - filter.maxResults is enforced";
+        let requirement = Requirement::must("RFC 7643 §5", Severity::Medium);
+        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+        let filter = capabilities.and_then(|c| c.filter.as_ref());
+        let max_results = match (capabilities, filter) {
+            (None, _) => Err("Skipped: ServiceProviderConfig could not be read".to_string()),
+            (_, Some(f)) if f.supported => f.max_results
+                .ok_or_else(|| "Skipped: ServiceProviderConfig does not advertise filter.maxResults".to_string()),
+            _ => Err("Skipped: ServiceProviderConfig does not claim filter support".to_string()),
+        };
+        match max_results {
+            Err(reason) => {
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                    "/Users?filter=userName pr", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+            }
+            Ok(max_results) => {
+                let path = format!("/Users?filter={}&count={}", Filter::present("userName").encoded(), max_results + 1);
+                let mut fixture_ids = Vec::new();
+                let mut outcome = client.get(&path).await;
+                // Top the directory up to maxResults + 1 users when that takes only a few
+                let matching = outcome.as_ref().ok().filter(|r| r.status == 200)
+                    .and_then(|r| ListResponse::<Value>::parse_lenient(&r.body).ok())
+                    .and_then(|list| list.total_results);
+                let missing = matching.map(|n| (max_results + 1).saturating_sub(n)).unwrap_or(0);
+                if missing > 0 && missing <= MAX_RESULTS_FIXTURES {
+                    let prefix = format!("scim_maxresults_test_{}", Self::unique_suffix());
+                    for i in 0..missing {
+                        let body = scim::to_body(&User::new(&format!("{}_{}@test.example.com", prefix, i)).with_active(true));
+                        if let Ok(resp) = client.post("/Users", &body).await {
+                            if resp.status == 201 {
+                                fixture_ids.extend(scim::resource_id(&resp.body));
+                            }
+                        }
+                    }
+                    outcome = client.get(&path).await;
+                }
+                match outcome {
+                    Ok(resp) => {
+                        let list = ListResponse::<Value>::parse_lenient(&resp.body).ok();
+                        let returned = list.as_ref().map(|l| l.resources().len() as u64).unwrap_or(0);
+                        let total_results = list.as_ref().and_then(|l| l.total_results).unwrap_or(returned);
+                        let too_many = resp.status == 400
+                            && resp.scim_error().and_then(|e| e.scim_type).as_deref() == Some("tooMany");
+                        let (outcome, failure) = if too_many {
+                            (TestOutcome::Passed, None)
+                        } else if resp.status != 200 {
+                            (TestOutcome::Failed, Some(format!("Expected 200 or 400 tooMany, got {}", resp.status)))
+                        } else if returned > max_results {
+                            (TestOutcome::Failed, Some(format!("filter.maxResults is {}, but {} resources were returned", max_results, returned)))
+                        } else if total_results <= max_results {
+                            (TestOutcome::Skipped, Some(format!("Skipped: only {} users match, so filter.maxResults ({}) is never reached", total_results, max_results)))
+                        } else {
+                            (TestOutcome::Passed, None)
+                        };
+                        results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                            &path, None, Some(resp.status as i32), Some(resp.body),
+                            resp.duration_ms, Some(resp.timing), outcome, failure));
+                    }
+                    Err(e) => {
+                        results.push(Self::error_result(test_run_id, test_name, category, requirement, "GET",
+                            &path, None, e));
+                    }
+                }
+                for id in &fixture_ids {
+                    let _ = client.delete(&format!("/Users/{}", id)).await;
+                }
+            }
+        }
+        *completed += 1;
+
         results
     }
 
@@ -381,34 +670,26 @@ impl ValidationEngine {
         app: &AppHandle,
         client: &ScimClient,
         test_run_id: &str,
+        capabilities: Option<&ServiceProviderConfig>,
         joining_property: &str,
         completed: &mut usize,
         total: usize,
     ) -> Vec<ValidationResult> {
         let mut results = Vec::new();
         let category = "users_crud";
-        let uid = Uuid::new_v4().to_string().split('-').next().unwrap().to_string();
+        let uid = Self::unique_suffix();
         let test_user_name = format!("scim_test_user_{}@test.example.com", uid);
         let mut created_user_id: Option<String> = None;
 
         // Test 1: CREATE User (POST /Users)
         let test_name = "POST /Users - Create Test User";
+        let requirement = Requirement::must("RFC 7644 §3.3", Severity::Critical);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        let create_body = serde_json::json!({
-            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
-            "userName": test_user_name,
-            "name": {
-                "givenName": "SCIM",
-                "familyName": "TestUser"
-            },
-            "emails": [{
-                "value": test_user_name,
-                "type": "work",
-                "primary": true
-            }],
-            "displayName": "SCIM Test User",
-            "active": true
-        }).to_string();
+        let create_body = scim::to_body(&User::new(&test_user_name)
+            .with_name("SCIM", "TestUser")
+            .with_work_email(&test_user_name)
+            .with_display_name("SCIM Test User")
+            .with_active(true));
 
         match client.post("/Users", &create_body).await {
             Ok(resp) => {
@@ -417,10 +698,10 @@ impl ValidationEngine {
                 if !passed {
                     failure = Some(format!("Expected status 201, got {}", resp.status));
                 } else {
-                    match serde_json::from_str::<Value>(&resp.body) {
-                        Ok(json) => {
-                            if let Some(id) = json.get("id").and_then(|v| v.as_str()) {
-                                created_user_id = Some(id.to_string());
+                    match serde_json::from_str::<User>(&resp.body) {
+                        Ok(user) => {
+                            if user.id.is_some() {
+                                created_user_id = user.id;
                             } else {
                                 failure = Some("Response missing 'id' field".to_string());
                             }
@@ -431,81 +712,84 @@ impl ValidationEngine {
                     }
                 }
                 results.push(Self::make_result(
-                    test_run_id, test_name, category, "POST",
+                    test_run_id, test_name, category, requirement, "POST",
                     "/Users", Some(create_body.clone()),
                     Some(resp.status as i32), Some(resp.body),
-                    resp.duration_ms, failure.is_none(), failure,
+                    resp.duration_ms, Some(resp.timing), failure.is_none(), failure,
                 ));
             }
             Err(e) => {
-                results.push(Self::make_result(
-                    test_run_id, test_name, category, "POST",
-                    "/Users", Some(create_body.clone()), None, None,
-                    0, false, Some(e),
+                results.push(Self::error_result(
+                    test_run_id, test_name, category, requirement, "POST",
+                    "/Users", Some(create_body.clone()), e,
                 ));
             }
         }
         *completed += 1;
+        let create_user = results.len() - 1;
 
         // Test 2: Verify creation via filter on joining property (like Microsoft validator)
         let test_name = "GET /Users?filter - Verify creation via joining property";
+        let requirement = Requirement::must("RFC 7644 §3.4.2.2", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        let filter_path = format!("/Users?filter={} eq \"{}\"", joining_property, test_user_name);
-        match client.get(&filter_path).await {
-            Ok(resp) => {
-                let mut passed = resp.status == 200;
-                let mut failure = None;
-                if !passed {
-                    failure = Some(format!("Expected status 200, got {}", resp.status));
-                } else {
-                    match serde_json::from_str::<Value>(&resp.body) {
-                        Ok(json) => {
-                            let total_results = json.get("totalResults").and_then(|v| v.as_u64()).unwrap_or(0);
-                            if total_results == 0 {
-                                passed = false;
-                                failure = Some("GET with filter returned 0 results — newly created user not found".to_string());
-                            } else {
-                                // Verify attribute round-trip: check values match what was POSTed
-                                let resources = Self::get_resources(&json).and_then(|v| v.as_array());
-                                if let Some(arr) = resources {
-                                    if let Some(user) = arr.first() {
-                                        let returned_name = user.get("userName").and_then(|v| v.as_str());
-                                        if returned_name != Some(&test_user_name) {
-                                            passed = false;
-                                            failure = Some(format!(
-                                                "Returned userName '{}' does not match POSTed value '{}'",
-                                                returned_name.unwrap_or("null"), test_user_name
-                                            ));
-                                        }
+        let prerequisites = [create_user];
+        let skip_reason = Self::unsupported(capabilities, "filter")
+            .or_else(|| Self::unmet_prerequisite(&results, &prerequisites));
+        if let Some(reason) = skip_reason {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Users?filter=...", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else {
+            let filter_path = format!("/Users?filter={}", Filter::eq(joining_property, &test_user_name).encoded());
+            match client.get(&filter_path).await {
+                Ok(resp) => {
+                    let mut passed = resp.status == 200;
+                    let mut failure = None;
+                    if !passed {
+                        failure = Some(format!("Expected status 200, got {}", resp.status));
+                    } else {
+                        match serde_json::from_str::<ListResponse<User>>(&resp.body) {
+                            Ok(list) => {
+                                if list.total_results.unwrap_or(0) == 0 {
+                                    passed = false;
+                                    failure = Some("GET with filter returned 0 results — newly created user not found".to_string());
+                                } else if let Some(user) = list.resources().first() {
+                                    // Verify attribute round-trip: check values match what was POSTed
+                                    if user.user_name != test_user_name {
+                                        passed = false;
+                                        failure = Some(format!(
+                                            "Returned userName '{}' does not match POSTed value '{}'",
+                                            user.user_name, test_user_name
+                                        ));
                                     }
                                 }
                             }
-                        }
-                        Err(e) => {
-                            passed = false;
-                            failure = Some(format!("Invalid JSON: {}", e));
+                            Err(e) => {
+                                passed = false;
+                                failure = Some(format!("Invalid JSON: {}", e));
+                            }
                         }
                     }
+                    results.push(Self::make_result(
+                        test_run_id, test_name, category, requirement, "GET",
+                        &filter_path, None,
+                        Some(resp.status as i32), Some(resp.body),
+                        resp.duration_ms, Some(resp.timing), passed, failure,
+                    ));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(
+                        test_run_id, test_name, category, requirement, "GET",
+                        &filter_path, None, e,
+                    ));
                 }
-                results.push(Self::make_result(
-                    test_run_id, test_name, category, "GET",
-                    &filter_path, None,
-                    Some(resp.status as i32), Some(resp.body),
-                    resp.duration_ms, passed, failure,
-                ));
-            }
-            Err(e) => {
-                results.push(Self::make_result(
-                    test_run_id, test_name, category, "GET",
-                    &filter_path, None, None, None,
-                    0, false, Some(e),
-                ));
             }
         }
+        Self::record_prerequisites(&mut results, &prerequisites);
         *completed += 1;
 
         // Test 3: LIST Users (GET /Users)
         let test_name = "GET /Users - List Users";
+        let requirement = Requirement::must("RFC 7644 §3.4.2", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
         match client.get("/Users").await {
             Ok(resp) => {
@@ -513,43 +797,32 @@ impl ValidationEngine {
                 let failure = if !passed {
                     Some(format!("Expected status 200, got {}", resp.status))
                 } else {
-                    match serde_json::from_str::<Value>(&resp.body) {
-                        Ok(json) => {
-                            if json.get("totalResults").is_none() {
-                                Some("Response missing 'totalResults' field".to_string())
-                            } else {
-                                let total = json.get("totalResults").and_then(|v| v.as_u64()).unwrap_or(0);
-                                if total > 0 && Self::get_resources(&json).is_none() {
-                                    Some("Response missing 'Resources' field (totalResults > 0 but no Resources array)".to_string())
-                                } else if total > 0 {
-                                    match Self::get_resources(&json).and_then(|v| v.as_array()) {
-                                        Some(arr) if arr.is_empty() => {
-                                            Some("'Resources' array is empty but totalResults > 0".to_string())
-                                        }
-                                        Some(_) => None,
-                                        None => Some("'Resources' is not an array".to_string()),
-                                    }
-                                } else {
-                                    // totalResults == 0: Resources is optional per RFC 7644 §3.4.2
-                                    None
-                                }
+                    match serde_json::from_str::<ListResponse<Value>>(&resp.body) {
+                        Ok(list) => match (list.total_results, list.resources) {
+                            (None, _) => Some("Response missing 'totalResults' field".to_string()),
+                            (Some(total), None) if total > 0 => {
+                                Some("Response missing 'Resources' field (totalResults > 0 but no Resources array)".to_string())
                             }
-                        }
+                            (Some(total), Some(resources)) if total > 0 && resources.is_empty() => {
+                                Some("'Resources' array is empty but totalResults > 0".to_string())
+                            }
+                            // totalResults == 0: Resources is optional per RFC 7644 §3.4.2
+                            _ => None,
+                        },
                         Err(e) => Some(format!("Invalid JSON: {}", e)),
                     }
                 };
                 results.push(Self::make_result(
-                    test_run_id, test_name, category, "GET",
+                    test_run_id, test_name, category, requirement, "GET",
                     "/Users", None,
                     Some(resp.status as i32), Some(resp.body),
-                    resp.duration_ms, failure.is_none(), failure,
+                    resp.duration_ms, Some(resp.timing), failure.is_none(), failure,
                 ));
             }
             Err(e) => {
-                results.push(Self::make_result(
-                    test_run_id, test_name, category, "GET",
-                    "/Users", None, None, None,
-                    0, false, Some(e),
+                results.push(Self::error_result(
+                    test_run_id, test_name, category, requirement, "GET",
+                    "/Users", None, e,
                 ));
             }
         }
@@ -557,33 +830,28 @@ impl ValidationEngine {
 
         // Test 4: UPDATE User (PUT /Users/{id})
         let test_name = "PUT /Users/{id} - Update Test User";
+        let requirement = Requirement::must("RFC 7644 §3.5.1", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        if let Some(ref user_id) = created_user_id {
+        let prerequisites = [create_user];
+        if let Some(reason) = Self::unmet_prerequisite(&results, &prerequisites) {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "PUT",
+                "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else if let Some(ref user_id) = created_user_id {
             let path = format!("/Users/{}", user_id);
-            let update_body = serde_json::json!({
-                "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
-                "userName": test_user_name,
-                "name": {
-                    "givenName": "SCIM",
-                    "familyName": "UpdatedUser"
-                },
-                "emails": [{
-                    "value": test_user_name,
-                    "type": "work",
-                    "primary": true
-                }],
-                "displayName": "SCIM Updated User",
-                "active": true
-            }).to_string();
+            let update_body = scim::to_body(&User::new(&test_user_name)
+                .with_name("SCIM", "UpdatedUser")
+                .with_work_email(&test_user_name)
+                .with_display_name("SCIM Updated User")
+                .with_active(true));
             match client.put(&path, &update_body).await {
                 Ok(resp) => {
                     let passed = resp.status == 200;
                     let failure = if !passed {
                         Some(format!("Expected status 200, got {}", resp.status))
                     } else {
-                        match serde_json::from_str::<Value>(&resp.body) {
-                            Ok(json) => {
-                                let family = json.pointer("/name/familyName").and_then(|v| v.as_str());
+                        match serde_json::from_str::<User>(&resp.body) {
+                            Ok(user) => {
+                                let family = user.name.as_ref().and_then(|n| n.family_name.as_deref());
                                 if family != Some("UpdatedUser") {
                                     Some("familyName was not updated to 'UpdatedUser'".to_string())
                                 } else {
@@ -594,33 +862,38 @@ impl ValidationEngine {
                         }
                     };
                     results.push(Self::make_result(
-                        test_run_id, test_name, category, "PUT",
+                        test_run_id, test_name, category, requirement, "PUT",
                         &path, Some(update_body),
                         Some(resp.status as i32), Some(resp.body),
-                        resp.duration_ms, failure.is_none(), failure,
+                        resp.duration_ms, Some(resp.timing), failure.is_none(), failure,
                     ));
                 }
                 Err(e) => {
-                    results.push(Self::make_result(
-                        test_run_id, test_name, category, "PUT",
-                        &path, Some(update_body), None, None,
-                        0, false, Some(e),
+                    results.push(Self::error_result(
+                        test_run_id, test_name, category, requirement, "PUT",
+                        &path, Some(update_body), e,
                     ));
                 }
             }
         } else {
             results.push(Self::make_result(
-                test_run_id, test_name, category, "PUT",
+                test_run_id, test_name, category, requirement, "PUT",
                 "/Users/{id}", None, None, None,
-                0, false, Some("Skipped: user creation failed".to_string()),
+                0, None, TestOutcome::Skipped, Some("Skipped: user creation failed".to_string()),
             ));
         }
+        Self::record_prerequisites(&mut results, &prerequisites);
         *completed += 1;
 
         // Test 5: DELETE User (DELETE /Users/{id})
         let test_name = "DELETE /Users/{id} - Delete Test User";
+        let requirement = Requirement::must("RFC 7644 §3.6", Severity::Critical);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        if let Some(ref user_id) = created_user_id {
+        let prerequisites = [create_user];
+        if let Some(reason) = Self::unmet_prerequisite(&results, &prerequisites) {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "DELETE",
+                "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else if let Some(ref user_id) = created_user_id {
             let path = format!("/Users/{}", user_id);
             match client.delete(&path).await {
                 Ok(resp) => {
@@ -631,33 +904,39 @@ impl ValidationEngine {
                         None
                     };
                     results.push(Self::make_result(
-                        test_run_id, test_name, category, "DELETE",
+                        test_run_id, test_name, category, requirement, "DELETE",
                         &path, None,
                         Some(resp.status as i32), Some(resp.body),
-                        resp.duration_ms, passed, failure,
+                        resp.duration_ms, Some(resp.timing), passed, failure,
                     ));
                 }
                 Err(e) => {
-                    results.push(Self::make_result(
-                        test_run_id, test_name, category, "DELETE",
-                        &path, None, None, None,
-                        0, false, Some(e),
+                    results.push(Self::error_result(
+                        test_run_id, test_name, category, requirement, "DELETE",
+                        &path, None, e,
                     ));
                 }
             }
         } else {
             results.push(Self::make_result(
-                test_run_id, test_name, category, "DELETE",
+                test_run_id, test_name, category, requirement, "DELETE",
                 "/Users/{id}", None, None, None,
-                0, false, Some("Skipped: user creation failed".to_string()),
+                0, None, TestOutcome::Skipped, Some("Skipped: user creation failed".to_string()),
             ));
         }
+        Self::record_prerequisites(&mut results, &prerequisites);
         *completed += 1;
+        let delete_user = results.len() - 1;
 
         // Test 6: VERIFY deletion (GET /Users/{id} should return 404)
         let test_name = "GET /Users/{id} - Verify Deletion (expect 404)";
+        let requirement = Requirement::must("RFC 7644 §3.6", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        if let Some(ref user_id) = created_user_id {
+        let prerequisites = [delete_user];
+        if let Some(reason) = Self::unmet_prerequisite(&results, &prerequisites) {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else if let Some(ref user_id) = created_user_id {
             let path = format!("/Users/{}", user_id);
             match client.get(&path).await {
                 Ok(resp) => {
@@ -668,27 +947,27 @@ impl ValidationEngine {
                         None
                     };
                     results.push(Self::make_result(
-                        test_run_id, test_name, category, "GET",
+                        test_run_id, test_name, category, requirement, "GET",
                         &path, None,
                         Some(resp.status as i32), Some(resp.body),
-                        resp.duration_ms, passed, failure,
+                        resp.duration_ms, Some(resp.timing), passed, failure,
                     ));
                 }
                 Err(e) => {
-                    results.push(Self::make_result(
-                        test_run_id, test_name, category, "GET",
-                        &path, None, None, None,
-                        0, false, Some(e),
+                    results.push(Self::error_result(
+                        test_run_id, test_name, category, requirement, "GET",
+                        &path, None, e,
                     ));
                 }
             }
         } else {
             results.push(Self::make_result(
-                test_run_id, test_name, category, "GET",
+                test_run_id, test_name, category, requirement, "GET",
                 "/Users/{id}", None, None, None,
-                0, false, Some("Skipped: user creation failed".to_string()),
+                0, None, TestOutcome::Skipped, Some("Skipped: user creation failed".to_string()),
             ));
         }
+        Self::record_prerequisites(&mut results, &prerequisites);
         *completed += 1;
 
         results
@@ -700,23 +979,21 @@ impl ValidationEngine {
         app: &AppHandle,
         client: &ScimClient,
         test_run_id: &str,
+        capabilities: Option<&ServiceProviderConfig>,
         joining_property: &str,
         completed: &mut usize,
         total: usize,
     ) -> Vec<ValidationResult> {
         let mut results = Vec::new();
         let category = "groups_crud";
-        let test_group_name = format!("scim_test_group_{}", Uuid::new_v4().to_string().split('-').next().unwrap());
+        let test_group_name = format!("scim_test_group_{}", Self::unique_suffix());
         let mut created_group_id: Option<String> = None;
 
         // Test 1: CREATE Group
         let test_name = "POST /Groups - Create Test Group";
+        let requirement = Requirement::must("RFC 7644 §3.3", Severity::Critical);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        let create_body = serde_json::json!({
-            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:Group"],
-            "displayName": test_group_name,
-            "members": []
-        }).to_string();
+        let create_body = scim::to_body(&Group::new(&test_group_name));
         match client.post("/Groups", &create_body).await {
             Ok(resp) => {
                 let passed = resp.status == 201;
@@ -724,10 +1001,10 @@ impl ValidationEngine {
                 if !passed {
                     failure = Some(format!("Expected status 201, got {}", resp.status));
                 } else {
-                    match serde_json::from_str::<Value>(&resp.body) {
-                        Ok(json) => {
-                            if let Some(id) = json.get("id").and_then(|v| v.as_str()) {
-                                created_group_id = Some(id.to_string());
+                    match serde_json::from_str::<Group>(&resp.body) {
+                        Ok(group) => {
+                            if group.id.is_some() {
+                                created_group_id = group.id;
                             } else {
                                 failure = Some("Response missing 'id' field".to_string());
                             }
@@ -736,161 +1013,180 @@ impl ValidationEngine {
                     }
                 }
                 results.push(Self::make_result(
-                    test_run_id, test_name, category, "POST",
+                    test_run_id, test_name, category, requirement, "POST",
                     "/Groups", Some(create_body.clone()),
                     Some(resp.status as i32), Some(resp.body),
-                    resp.duration_ms, failure.is_none(), failure,
+                    resp.duration_ms, Some(resp.timing), failure.is_none(), failure,
                 ));
             }
             Err(e) => {
-                results.push(Self::make_result(
-                    test_run_id, test_name, category, "POST",
-                    "/Groups", Some(create_body), None, None,
-                    0, false, Some(e),
+                results.push(Self::error_result(
+                    test_run_id, test_name, category, requirement, "POST",
+                    "/Groups", Some(create_body), e,
                 ));
             }
         }
         *completed += 1;
+        let create_group = results.len() - 1;
 
         // Test 2: Verify creation via filter on joining property
         let test_name = "GET /Groups?filter - Verify creation via joining property";
+        let requirement = Requirement::must("RFC 7644 §3.4.2.2", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        let filter_path = format!("/Groups?filter={} eq \"{}\"", joining_property, test_group_name);
-        match client.get(&filter_path).await {
-            Ok(resp) => {
-                let mut passed = resp.status == 200;
-                let mut failure = None;
-                if !passed {
-                    failure = Some(format!("Expected 200, got {}", resp.status));
-                } else {
-                    match serde_json::from_str::<Value>(&resp.body) {
-                        Ok(json) => {
-                            let total_results = json.get("totalResults").and_then(|v| v.as_u64()).unwrap_or(0);
-                            if total_results == 0 {
-                                passed = false;
-                                failure = Some("GET with filter returned 0 results — newly created group not found".to_string());
-                            } else {
-                                let resources = Self::get_resources(&json).and_then(|v| v.as_array());
-                                if let Some(arr) = resources {
-                                    if let Some(group) = arr.first() {
-                                        let returned_name = group.get("displayName").and_then(|v| v.as_str());
-                                        if returned_name != Some(&test_group_name) {
-                                            passed = false;
-                                            failure = Some(format!(
-                                                "Returned displayName '{}' does not match POSTed value '{}'",
-                                                returned_name.unwrap_or("null"), test_group_name
-                                            ));
-                                        }
+        let prerequisites = [create_group];
+        let skip_reason = Self::unsupported(capabilities, "filter")
+            .or_else(|| Self::unmet_prerequisite(&results, &prerequisites));
+        if let Some(reason) = skip_reason {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Groups?filter=...", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else {
+            let filter_path = format!("/Groups?filter={}", Filter::eq(joining_property, &test_group_name).encoded());
+            match client.get(&filter_path).await {
+                Ok(resp) => {
+                    let mut passed = resp.status == 200;
+                    let mut failure = None;
+                    if !passed {
+                        failure = Some(format!("Expected 200, got {}", resp.status));
+                    } else {
+                        match serde_json::from_str::<ListResponse<Group>>(&resp.body) {
+                            Ok(list) => {
+                                if list.total_results.unwrap_or(0) == 0 {
+                                    passed = false;
+                                    failure = Some("GET with filter returned 0 results — newly created group not found".to_string());
+                                } else if let Some(group) = list.resources().first() {
+                                    if group.display_name != test_group_name {
+                                        passed = false;
+                                        failure = Some(format!(
+                                            "Returned displayName '{}' does not match POSTed value '{}'",
+                                            group.display_name, test_group_name
+                                        ));
                                     }
                                 }
                             }
+                            Err(e) => { passed = false; failure = Some(format!("Invalid JSON: {}", e)); }
                         }
-                        Err(e) => { passed = false; failure = Some(format!("Invalid JSON: {}", e)); }
                     }
+                    results.push(Self::make_result(
+                        test_run_id, test_name, category, requirement, "GET",
+                        &filter_path, None, Some(resp.status as i32), Some(resp.body),
+                        resp.duration_ms, Some(resp.timing), passed, failure,
+                    ));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "GET",
+                        &filter_path, None, e));
                 }
-                results.push(Self::make_result(
-                    test_run_id, test_name, category, "GET",
-                    &filter_path, None, Some(resp.status as i32), Some(resp.body),
-                    resp.duration_ms, passed, failure,
-                ));
-            }
-            Err(e) => {
-                results.push(Self::make_result(test_run_id, test_name, category, "GET",
-                    &filter_path, None, None, None, 0, false, Some(e)));
             }
         }
+        Self::record_prerequisites(&mut results, &prerequisites);
         *completed += 1;
 
         // Test 3: LIST Groups
         let test_name = "GET /Groups - List Groups";
+        let requirement = Requirement::must("RFC 7644 §3.4.2", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
         match client.get("/Groups").await {
             Ok(resp) => {
                 let passed = resp.status == 200;
                 let failure = if !passed { Some(format!("Expected 200, got {}", resp.status)) } else { None };
                 results.push(Self::make_result(
-                    test_run_id, test_name, category, "GET", "/Groups", None,
-                    Some(resp.status as i32), Some(resp.body), resp.duration_ms, passed, failure,
+                    test_run_id, test_name, category, requirement, "GET", "/Groups", None,
+                    Some(resp.status as i32), Some(resp.body), resp.duration_ms, Some(resp.timing), passed, failure,
                 ));
             }
             Err(e) => {
-                results.push(Self::make_result(test_run_id, test_name, category, "GET", "/Groups", None, None, None, 0, false, Some(e)));
+                results.push(Self::error_result(test_run_id, test_name, category, requirement, "GET", "/Groups", None, e));
             }
         }
         *completed += 1;
 
         // Test 4: UPDATE Group
         let test_name = "PUT /Groups/{id} - Update Test Group";
+        let requirement = Requirement::must("RFC 7644 §3.5.1", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        if let Some(ref group_id) = created_group_id {
+        let prerequisites = [create_group];
+        if let Some(reason) = Self::unmet_prerequisite(&results, &prerequisites) {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "PUT",
+                "/Groups/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else if let Some(ref group_id) = created_group_id {
             let path = format!("/Groups/{}", group_id);
-            let update_body = serde_json::json!({
-                "schemas": ["urn:ietf:params:scim:schemas:core:2.0:Group"],
-                "displayName": format!("{}_updated", test_group_name),
-                "members": []
-            }).to_string();
+            let update_body = scim::to_body(&Group::new(&format!("{}_updated", test_group_name)));
             match client.put(&path, &update_body).await {
                 Ok(resp) => {
                     let passed = resp.status == 200;
                     let failure = if !passed { Some(format!("Expected 200, got {}", resp.status)) } else { None };
                     results.push(Self::make_result(
-                        test_run_id, test_name, category, "PUT", &path, Some(update_body),
-                        Some(resp.status as i32), Some(resp.body), resp.duration_ms, passed, failure,
+                        test_run_id, test_name, category, requirement, "PUT", &path, Some(update_body),
+                        Some(resp.status as i32), Some(resp.body), resp.duration_ms, Some(resp.timing), passed, failure,
                     ));
                 }
                 Err(e) => {
-                    results.push(Self::make_result(test_run_id, test_name, category, "PUT", &path, Some(update_body), None, None, 0, false, Some(e)));
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "PUT", &path, Some(update_body), e));
                 }
             }
         } else {
-            results.push(Self::make_result(test_run_id, test_name, category, "PUT", "/Groups/{id}", None, None, None, 0, false, Some("Skipped: group creation failed".to_string())));
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "PUT", "/Groups/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some("Skipped: group creation failed".to_string())));
         }
+        Self::record_prerequisites(&mut results, &prerequisites);
         *completed += 1;
 
         // Test 5: DELETE Group
         let test_name = "DELETE /Groups/{id} - Delete Test Group";
+        let requirement = Requirement::must("RFC 7644 §3.6", Severity::Critical);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        if let Some(ref group_id) = created_group_id {
+        let prerequisites = [create_group];
+        if let Some(reason) = Self::unmet_prerequisite(&results, &prerequisites) {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "DELETE",
+                "/Groups/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else if let Some(ref group_id) = created_group_id {
             let path = format!("/Groups/{}", group_id);
             match client.delete(&path).await {
                 Ok(resp) => {
                     let passed = resp.status == 204 || resp.status == 200;
                     let failure = if !passed { Some(format!("Expected 204/200, got {}", resp.status)) } else { None };
                     results.push(Self::make_result(
-                        test_run_id, test_name, category, "DELETE", &path, None,
-                        Some(resp.status as i32), Some(resp.body), resp.duration_ms, passed, failure,
+                        test_run_id, test_name, category, requirement, "DELETE", &path, None,
+                        Some(resp.status as i32), Some(resp.body), resp.duration_ms, Some(resp.timing), passed, failure,
                     ));
                 }
                 Err(e) => {
-                    results.push(Self::make_result(test_run_id, test_name, category, "DELETE", &path, None, None, None, 0, false, Some(e)));
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "DELETE", &path, None, e));
                 }
             }
         } else {
-            results.push(Self::make_result(test_run_id, test_name, category, "DELETE", "/Groups/{id}", None, None, None, 0, false, Some("Skipped: group creation failed".to_string())));
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "DELETE", "/Groups/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some("Skipped: group creation failed".to_string())));
         }
+        Self::record_prerequisites(&mut results, &prerequisites);
         *completed += 1;
+        let delete_group = results.len() - 1;
 
         // Test 6: VERIFY deletion
         let test_name = "GET /Groups/{id} - Verify Deletion (expect 404)";
+        let requirement = Requirement::must("RFC 7644 §3.6", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        if let Some(ref group_id) = created_group_id {
+        let prerequisites = [delete_group];
+        if let Some(reason) = Self::unmet_prerequisite(&results, &prerequisites) {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Groups/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else if let Some(ref group_id) = created_group_id {
             let path = format!("/Groups/{}", group_id);
             match client.get(&path).await {
                 Ok(resp) => {
                     let passed = resp.status == 404;
                     let failure = if !passed { Some(format!("Expected 404, got {}", resp.status)) } else { None };
                     results.push(Self::make_result(
-                        test_run_id, test_name, category, "GET", &path, None,
-                        Some(resp.status as i32), Some(resp.body), resp.duration_ms, passed, failure,
+                        test_run_id, test_name, category, requirement, "GET", &path, None,
+                        Some(resp.status as i32), Some(resp.body), resp.duration_ms, Some(resp.timing), passed, failure,
                     ));
                 }
                 Err(e) => {
-                    results.push(Self::make_result(test_run_id, test_name, category, "GET", &path, None, None, None, 0, false, Some(e)));
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "GET", &path, None, e));
                 }
             }
         } else {
-            results.push(Self::make_result(test_run_id, test_name, category, "GET", "/Groups/{id}", None, None, None, 0, false, Some("Skipped: group creation failed".to_string())));
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET", "/Groups/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some("Skipped: group creation failed".to_string())));
         }
+        Self::record_prerequisites(&mut results, &prerequisites);
         *completed += 1;
 
         results
@@ -902,40 +1198,41 @@ impl ValidationEngine {
         app: &AppHandle,
         client: &ScimClient,
         test_run_id: &str,
+        capabilities: Option<&ServiceProviderConfig>,
         joining_property: &str,
         completed: &mut usize,
         total: usize,
     ) -> Vec<ValidationResult> {
         let mut results = Vec::new();
         let category = "patch_operations";
-        let test_user_name = format!("scim_patch_test_{}@test.example.com", Uuid::new_v4().to_string().split('-').next().unwrap());
+        let test_user_name = format!("scim_patch_test_{}@test.example.com", Self::unique_suffix());
         let mut created_user_id: Option<String> = None;
+        // Nothing here is sent to a server that declares PATCH unsupported
+        let patch_skip = Self::unsupported(capabilities, "patch");
+        let no_user = patch_skip.clone().unwrap_or_else(|| "Skipped: user creation failed".to_string());
 
         // First create a user for PATCH testing
-        let create_body = serde_json::json!({
-            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
-            "userName": test_user_name,
-            "name": { "givenName": "Patch", "familyName": "TestUser" },
-            "displayName": "Patch Test User",
-            "active": true
-        }).to_string();
-        if let Ok(resp) = client.post("/Users", &create_body).await {
-            if resp.status == 201 {
-                if let Ok(json) = serde_json::from_str::<Value>(&resp.body) {
-                    created_user_id = json.get("id").and_then(|v| v.as_str()).map(|s| s.to_string());
+        let create_body = scim::to_body(&User::new(&test_user_name)
+            .with_name("Patch", "TestUser")
+            .with_display_name("Patch Test User")
+            .with_active(true));
+        if patch_skip.is_none() {
+            if let Ok(resp) = client.post("/Users", &create_body).await {
+                if resp.status == 201 {
+                    created_user_id = scim::resource_id(&resp.body);
                 }
             }
         }
 
         // Test 1: PATCH Add attribute — then verify via filter
         let test_name = "PATCH /Users/{id} - Add attribute (title)";
+        let requirement = Requirement::must("RFC 7644 §3.5.2.1", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
         if let Some(ref user_id) = created_user_id {
             let path = format!("/Users/{}", user_id);
-            let patch_body = serde_json::json!({
-                "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
-                "Operations": [{ "op": "add", "path": "title", "value": "Engineer" }]
-            }).to_string();
+            let patch_body = scim::to_body(&PatchOp::new(vec![
+                PatchOperation::add(Some("title"), Value::from("Engineer")),
+            ]));
             match client.patch(&path, &patch_body).await {
                 Ok(resp) => {
                     let mut passed = resp.status == 200;
@@ -944,45 +1241,43 @@ impl ValidationEngine {
                         failure = Some(format!("Expected 200, got {}", resp.status));
                     } else {
                         // Verify the attribute was actually persisted
-                        let filter_path = format!("/Users?filter={} eq \"{}\"", joining_property, test_user_name);
+                        let filter_path = format!("/Users?filter={}", Filter::eq(joining_property, &test_user_name).encoded());
                         if let Ok(get_resp) = client.get(&filter_path).await {
-                            if let Ok(json) = serde_json::from_str::<Value>(&get_resp.body) {
-                                let resources = Self::get_resources(&json).and_then(|v| v.as_array());
-                                if let Some(arr) = resources {
-                                    if let Some(user) = arr.first() {
-                                        let title = user.get("title").and_then(|v| v.as_str());
-                                        if title != Some("Engineer") {
-                                            passed = false;
-                                            failure = Some(format!(
-                                                "PATCH succeeded but GET shows title='{}' instead of 'Engineer'",
-                                                title.unwrap_or("null")
-                                            ));
-                                        }
+                            if let Ok(list) = serde_json::from_str::<ListResponse<User>>(&get_resp.body) {
+                                if let Some(user) = list.resources().first() {
+                                    let title = user.title.as_deref();
+                                    if title != Some("Engineer") {
+                                        passed = false;
+                                        failure = Some(format!(
+                                            "PATCH succeeded but GET shows title='{}' instead of 'Engineer'",
+                                            title.unwrap_or("null")
+                                        ));
                                     }
                                 }
                             }
                         }
                     }
-                    results.push(Self::make_result(test_run_id, test_name, category, "PATCH", &path, Some(patch_body), Some(resp.status as i32), Some(resp.body), resp.duration_ms, passed, failure));
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH", &path, Some(patch_body), Some(resp.status as i32), Some(resp.body), resp.duration_ms, Some(resp.timing), passed, failure));
                 }
                 Err(e) => {
-                    results.push(Self::make_result(test_run_id, test_name, category, "PATCH", &path, Some(patch_body), None, None, 0, false, Some(e)));
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "PATCH", &path, Some(patch_body), e));
                 }
             }
         } else {
-            results.push(Self::make_result(test_run_id, test_name, category, "PATCH", "/Users/{id}", None, None, None, 0, false, Some("Skipped: user creation failed".to_string())));
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH", "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(no_user.clone())));
         }
         *completed += 1;
+        let add_title = results.len() - 1;
 
         // Test 2: PATCH Replace attribute — then verify via filter
         let test_name = "PATCH /Users/{id} - Replace attribute (displayName)";
+        let requirement = Requirement::must("RFC 7644 §3.5.2.3", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
         if let Some(ref user_id) = created_user_id {
             let path = format!("/Users/{}", user_id);
-            let patch_body = serde_json::json!({
-                "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
-                "Operations": [{ "op": "replace", "path": "displayName", "value": "Updated Patch User" }]
-            }).to_string();
+            let patch_body = scim::to_body(&PatchOp::new(vec![
+                PatchOperation::replace(Some("displayName"), Value::from("Updated Patch User")),
+            ]));
             match client.patch(&path, &patch_body).await {
                 Ok(resp) => {
                     let mut passed = resp.status == 200;
@@ -991,58 +1286,62 @@ impl ValidationEngine {
                         failure = Some(format!("Expected 200, got {}", resp.status));
                     } else {
                         // Verify via GET
-                        let filter_path = format!("/Users?filter={} eq \"{}\"", joining_property, test_user_name);
+                        let filter_path = format!("/Users?filter={}", Filter::eq(joining_property, &test_user_name).encoded());
                         if let Ok(get_resp) = client.get(&filter_path).await {
-                            if let Ok(json) = serde_json::from_str::<Value>(&get_resp.body) {
-                                let resources = Self::get_resources(&json).and_then(|v| v.as_array());
-                                if let Some(arr) = resources {
-                                    if let Some(user) = arr.first() {
-                                        let disp = user.get("displayName").and_then(|v| v.as_str());
-                                        if disp != Some("Updated Patch User") {
-                                            passed = false;
-                                            failure = Some(format!(
-                                                "PATCH succeeded but GET shows displayName='{}' instead of 'Updated Patch User'",
-                                                disp.unwrap_or("null")
-                                            ));
-                                        }
+                            if let Ok(list) = serde_json::from_str::<ListResponse<User>>(&get_resp.body) {
+                                if let Some(user) = list.resources().first() {
+                                    let disp = user.display_name.as_deref();
+                                    if disp != Some("Updated Patch User") {
+                                        passed = false;
+                                        failure = Some(format!(
+                                            "PATCH succeeded but GET shows displayName='{}' instead of 'Updated Patch User'",
+                                            disp.unwrap_or("null")
+                                        ));
                                     }
                                 }
                             }
                         }
                     }
-                    results.push(Self::make_result(test_run_id, test_name, category, "PATCH", &path, Some(patch_body), Some(resp.status as i32), Some(resp.body), resp.duration_ms, passed, failure));
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH", &path, Some(patch_body), Some(resp.status as i32), Some(resp.body), resp.duration_ms, Some(resp.timing), passed, failure));
                 }
                 Err(e) => {
-                    results.push(Self::make_result(test_run_id, test_name, category, "PATCH", &path, Some(patch_body), None, None, 0, false, Some(e)));
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "PATCH", &path, Some(patch_body), e));
                 }
             }
         } else {
-            results.push(Self::make_result(test_run_id, test_name, category, "PATCH", "/Users/{id}", None, None, None, 0, false, Some("Skipped: user creation failed".to_string())));
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH", "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(no_user.clone())));
         }
         *completed += 1;
 
         // Test 3: PATCH Remove attribute
         let test_name = "PATCH /Users/{id} - Remove attribute (title)";
+        let requirement = Requirement::must("RFC 7644 §3.5.2.2", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        if let Some(ref user_id) = created_user_id {
+        let prerequisites = [add_title];
+        let skip_reason = patch_skip.clone()
+            .or_else(|| Self::unmet_prerequisite(&results, &prerequisites));
+        if let Some(reason) = skip_reason {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH",
+                "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else if let Some(ref user_id) = created_user_id {
             let path = format!("/Users/{}", user_id);
-            let patch_body = serde_json::json!({
-                "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
-                "Operations": [{ "op": "remove", "path": "title" }]
-            }).to_string();
+            let patch_body = scim::to_body(&PatchOp::new(vec![
+                PatchOperation::remove("title"),
+            ]));
             match client.patch(&path, &patch_body).await {
                 Ok(resp) => {
                     let passed = resp.status == 200 || resp.status == 204;
                     let failure = if !passed { Some(format!("Expected 200/204, got {}", resp.status)) } else { None };
-                    results.push(Self::make_result(test_run_id, test_name, category, "PATCH", &path, Some(patch_body), Some(resp.status as i32), Some(resp.body), resp.duration_ms, passed, failure));
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH", &path, Some(patch_body), Some(resp.status as i32), Some(resp.body), resp.duration_ms, Some(resp.timing), passed, failure));
                 }
                 Err(e) => {
-                    results.push(Self::make_result(test_run_id, test_name, category, "PATCH", &path, Some(patch_body), None, None, 0, false, Some(e)));
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "PATCH", &path, Some(patch_body), e));
                 }
             }
         } else {
-            results.push(Self::make_result(test_run_id, test_name, category, "PATCH", "/Users/{id}", None, None, None, 0, false, Some("Skipped: user creation failed".to_string())));
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH", "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(no_user.clone())));
         }
+        Self::record_prerequisites(&mut results, &prerequisites);
         *completed += 1;
 
         // Cleanup: delete the test user
@@ -1052,93 +1351,363 @@ impl ValidationEngine {
 
         // Test 4: PATCH on non-existent resource should return 404
         let test_name = "PATCH /Users/{nonexistent} - Expect 404";
+        let requirement = Requirement::must("RFC 7644 §3.12", Severity::Medium);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        let fake_id = Uuid::new_v4().to_string();
-        let path = format!("/Users/{}", fake_id);
-        let patch_body = serde_json::json!({
-            "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
-            "Operations": [{ "op": "add", "path": "title", "value": "Test" }]
-        }).to_string();
-        match client.patch(&path, &patch_body).await {
-            Ok(resp) => {
-                let passed = resp.status == 404;
-                let failure = if !passed { Some(format!("Expected 404 for non-existent resource, got {}", resp.status)) } else { None };
-                results.push(Self::make_result(test_run_id, test_name, category, "PATCH", &path, Some(patch_body), Some(resp.status as i32), Some(resp.body), resp.duration_ms, passed, failure));
-            }
-            Err(e) => {
-                results.push(Self::make_result(test_run_id, test_name, category, "PATCH", &path, Some(patch_body), None, None, 0, false, Some(e)));
+        if let Some(ref reason) = patch_skip {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH", "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(reason.clone())));
+        } else {
+            let fake_id = Uuid::new_v4().to_string();
+            let path = format!("/Users/{}", fake_id);
+            let patch_body = scim::to_body(&PatchOp::new(vec![
+                PatchOperation::add(Some("title"), Value::from("Test")),
+            ]));
+            match client.patch(&path, &patch_body).await {
+                Ok(resp) => {
+                    let passed = resp.status == 404;
+                    let failure = if !passed { Some(format!("Expected 404 for non-existent resource, got {}", resp.status)) } else { None };
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH", &path, Some(patch_body), Some(resp.status as i32), Some(resp.body), resp.duration_ms, Some(resp.timing), passed, failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "PATCH", &path, Some(patch_body), e));
+                }
             }
         }
         *completed += 1;
 
-        results
-    }
-
-    // ── Filtering & Pagination Tests ──
-
-    async fn test_filtering_pagination(
-        app: &AppHandle,
-        client: &ScimClient,
-        test_run_id: &str,
-        completed: &mut usize,
-        total: usize,
-    ) -> Vec<ValidationResult> {
-        let mut results = Vec::new();
-        let category = "filtering_pagination";
+        // Tests 5-12: PATCH the way identity providers send it, against a user with
+        // typed emails and addresses, a nickName and the enterprise extension
+        let adv_user_name = format!("scim_patch_adv_{}@test.example.com", Self::unique_suffix());
+        let mut fixture = User::new(&adv_user_name)
+            .with_name("Patch", "AdvancedUser")
+            .with_display_name("Advanced Patch User")
+            .with_active(true)
+            .with_work_email(&adv_user_name)
+            .with_enterprise(EnterpriseUser { department: Some("Engineering".to_string()), ..Default::default() });
+        fixture.nick_name = Some("Patchy".to_string());
+        fixture.emails.push(MultiValued::new("patch.home@example.org", "home", false));
+        fixture.addresses = vec![Address::new("Seattle", "work", true), Address::new("Portland", "home", false)];
+        let fixture_id = match patch_skip {
+            Some(_) => None,
+            None => match client.post("/Users", &scim::to_body(&fixture)).await {
+                Ok(resp) if resp.status == 201 => scim::resource_id(&resp.body),
+                _ => None,
+            },
+        };
 
-        // Create a test user for filtering
-        let test_user_name = format!("scim_filter_test_{}@test.example.com", Uuid::new_v4().to_string().split('-').next().unwrap());
-        let mut created_user_id: Option<String> = None;
-        let create_body = serde_json::json!({
-            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
-            "userName": test_user_name,
-            "name": { "givenName": "Filter", "familyName": "TestUser" },
-            "displayName": "Filter Test User",
-            "active": true
-        }).to_string();
-        if let Ok(resp) = client.post("/Users", &create_body).await {
-            if resp.status == 201 {
-                if let Ok(json) = serde_json::from_str::<Value>(&resp.body) {
-                    created_user_id = json.get("id").and_then(|v| v.as_str()).map(|s| s.to_string());
+        let updates: [(&str, Requirement, Vec<PatchOperation>, UserCheck); 5] = [
+            (
+                "PATCH /Users/{id} - Replace filtered value path (emails[type eq \"work\"].value)",
+                Requirement::must("RFC 7644 §3.5.2", Severity::High),
+                vec![PatchOperation::replace(Some("emails[type eq \"work\"].value"), Value::from("patched.work@example.org"))],
+                |u| Self::attribute_failure("emails[type eq \"work\"].value", Self::typed_value(&u.emails, "work"), "patched.work@example.org")
+                    .or_else(|| Self::attribute_failure("emails[type eq \"home\"].value", Self::typed_value(&u.emails, "home"), "patch.home@example.org")),
+            ),
+            (
+                "PATCH /Users/{id} - Replace filtered value path (addresses[type eq \"work\"].locality)",
+                Requirement::must("RFC 7644 §3.5.2", Severity::High),
+                vec![PatchOperation::replace(Some("addresses[type eq \"work\"].locality"), Value::from("Austin"))],
+                |u| {
+                    let locality = |kind: &str| u.addresses.iter()
+                        .find(|a| a.kind.as_deref().is_some_and(|k| k.eq_ignore_ascii_case(kind)))
+                        .and_then(|a| a.locality.as_deref());
+                    Self::attribute_failure("addresses[type eq \"work\"].locality", locality("work"), "Austin")
+                        .or_else(|| Self::attribute_failure("addresses[type eq \"home\"].locality", locality("home"), "Portland"))
+                },
+            ),
+            (
+                "PATCH /Users/{id} - Replace without path (value object)",
+                Requirement::must("RFC 7644 §3.5.2.3", Severity::High),
+                vec![PatchOperation::replace(None, serde_json::json!({ "displayName": "Replaced Without Path", "title": "Architect" }))],
+                |u| Self::attribute_failure("displayName", u.display_name.as_deref(), "Replaced Without Path")
+                    .or_else(|| Self::attribute_failure("title", u.title.as_deref(), "Architect")),
+            ),
+            (
+                "PATCH /Users/{id} - Replace extension attribute by URN-qualified path",
+                Requirement::must("RFC 7644 §3.5.2", Severity::Medium),
+                vec![PatchOperation::replace(Some(&format!("{}:department", scim::ENTERPRISE_USER_SCHEMA)), Value::from("Research"))],
+                |u| {
+                    let department = u.extension_attribute(scim::ENTERPRISE_USER_SCHEMA, "department");
+                    Self::attribute_failure("enterprise department", department.as_ref().and_then(Value::as_str), "Research")
+                },
+            ),
+            (
+                "PATCH /Users/{id} - Several operations in one request",
+                Requirement::must("RFC 7644 §3.5.2", Severity::High),
+                vec![
+                    PatchOperation::add(Some("title"), Value::from("Director")),
+                    PatchOperation::replace(Some("name.givenName"), Value::from("Multi")),
+                    PatchOperation::remove("nickName"),
+                ],
+                |u| Self::attribute_failure("title", u.title.as_deref(), "Director")
+                    .or_else(|| Self::attribute_failure("name.givenName", u.name.as_ref().and_then(|n| n.given_name.as_deref()), "Multi"))
+                    .or_else(|| u.nick_name.as_ref().map(|n| format!("PATCH succeeded but GET still shows nickName='{}' after remove", n))),
+            ),
+        ];
+        for (test_name, requirement, operations, check) in updates {
+            Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+            let user_id = match fixture_id {
+                Some(ref user_id) => user_id,
+                None => {
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH", "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(no_user.clone())));
+                    *completed += 1;
+                    continue;
                 }
-            }
-        }
-
-        // Test 1: Filter by userName eq
-        let test_name = "GET /Users?filter - Filter by userName eq";
+            };
+            let path = format!("/Users/{}", user_id);
+            let patch_body = scim::to_body(&PatchOp::new(operations));
+            match client.patch(&path, &patch_body).await {
+                Ok(resp) => {
+                    let failure = if resp.status != 200 && resp.status != 204 {
+                        Some(format!("Expected 200/204, got {}", resp.status))
+                    } else {
+                        match client.get(&path).await.ok().and_then(|r| serde_json::from_str::<User>(&r.body).ok()) {
+                            Some(user) => check(&user),
+                            None => Some("PATCH succeeded but the user could not be read back".to_string()),
+                        }
+                    };
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH", &path, Some(patch_body), Some(resp.status as i32), Some(resp.body), resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "PATCH", &path, Some(patch_body), e));
+                }
+            }
+            *completed += 1;
+        }
+
+        // A failed PATCH must not apply any of its operations (RFC 7644 §3.5.2),
+        // so each rejected request must leave the resource as it was
+        let no_path_remove = PatchOperation { op: "remove".to_string(), path: None, value: None };
+        let rejections = [
+            (
+                "PATCH /Users/{id} - Request with an invalid operation is atomic",
+                Requirement::must("RFC 7644 §3.5.2", Severity::High),
+                vec![
+                    PatchOperation::replace(Some("displayName"), Value::from("Must Not Persist")),
+                    PatchOperation::replace(Some("emails[type eq \"fax\"].value"), Value::from("fax@example.org")),
+                ],
+                None,
+            ),
+            (
+                "PATCH /Users/{id} - remove without path (expect 400 noTarget)",
+                Requirement::must("RFC 7644 §3.5.2.2", Severity::Medium),
+                vec![no_path_remove],
+                Some("noTarget"),
+            ),
+            (
+                "PATCH /Users/{id} - Malformed path (expect 400 invalidPath)",
+                Requirement::must("RFC 7644 §3.5.2", Severity::Medium),
+                vec![PatchOperation::replace(Some("emails[type eq \"work\""), Value::from("broken@example.org"))],
+                Some("invalidPath"),
+            ),
+        ];
+        for (test_name, requirement, operations, expected_type) in rejections {
+            Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+            let user_id = match fixture_id {
+                Some(ref user_id) => user_id,
+                None => {
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH", "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(no_user.clone())));
+                    *completed += 1;
+                    continue;
+                }
+            };
+            let path = format!("/Users/{}", user_id);
+            let patch_body = scim::to_body(&PatchOp::new(operations));
+            let before = Self::resource_without_meta(client, &path).await;
+            match client.patch(&path, &patch_body).await {
+                Ok(resp) => {
+                    let scim_type = resp.scim_error().and_then(|e| e.scim_type);
+                    let mut failure = if resp.status != 400 {
+                        Some(format!("Expected 400, got {}", resp.status))
+                    } else {
+                        match expected_type {
+                            Some(expected) if scim_type.as_deref() != Some(expected) => {
+                                Some(format!("Expected scimType '{}', got '{}'", expected, scim_type.as_deref().unwrap_or("none")))
+                            }
+                            _ => None,
+                        }
+                    };
+                    let after = Self::resource_without_meta(client, &path).await;
+                    if resp.status >= 400 && before.is_some() && after != before {
+                        failure = Some(match failure {
+                            Some(f) => format!("{}; the resource changed anyway", f),
+                            None => "The request was rejected but the resource changed anyway".to_string(),
+                        });
+                    }
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH", &path, Some(patch_body), Some(resp.status as i32), Some(resp.body), resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "PATCH", &path, Some(patch_body), e));
+                }
+            }
+            *completed += 1;
+        }
+
+        // Test 13: set a new password; it is write-only, so the response must not echo it
+        let test_name = "PATCH /Users/{id} - Change password";
+        let requirement = Requirement::may("RFC 7643 §4.1.1", Severity::Medium);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        let filter_path = format!("/Users?filter=userName eq \"{}\"", test_user_name);
-        match client.get(&filter_path).await {
-            Ok(resp) => {
-                let mut passed = resp.status == 200;
-                let mut failure = None;
-                if !passed {
-                    failure = Some(format!("Expected 200, got {}", resp.status));
-                } else {
-                    match serde_json::from_str::<Value>(&resp.body) {
-                        Ok(json) => {
-                            let total_results = json.get("totalResults").and_then(|v| v.as_u64()).unwrap_or(0);
-                            if total_results == 0 {
+        let password_skip = patch_skip.clone()
+            .or_else(|| Self::unsupported(capabilities, "changePassword"))
+            .or_else(|| fixture_id.is_none().then(|| no_user.clone()));
+        match (&fixture_id, password_skip) {
+            (Some(user_id), None) => {
+                let path = format!("/Users/{}", user_id);
+                let password = format!("Sc1m-{}!", Uuid::new_v4().simple());
+                let patch_body = scim::to_body(&PatchOp::new(vec![
+                    PatchOperation::replace(Some("password"), Value::from(password)),
+                ]));
+                match client.patch(&path, &patch_body).await {
+                    Ok(resp) => {
+                        let echoed = serde_json::from_str::<Value>(&resp.body).ok()
+                            .and_then(|body| body.as_object().map(|o| o.keys().any(|k| k.eq_ignore_ascii_case("password"))))
+                            .unwrap_or(false);
+                        let failure = if resp.status != 200 && resp.status != 204 {
+                            Some(format!("Expected 200/204, got {}", resp.status))
+                        } else if echoed {
+                            Some("Response returns 'password', which is never to be returned".to_string())
+                        } else {
+                            None
+                        };
+                        results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH", &path, Some(patch_body), Some(resp.status as i32), Some(resp.body), resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+                    }
+                    Err(e) => {
+                        results.push(Self::error_result(test_run_id, test_name, category, requirement, "PATCH", &path, Some(patch_body), e));
+                    }
+                }
+            }
+            (_, reason) => {
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH", "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped, reason));
+            }
+        }
+        *completed += 1;
+
+        if let Some(ref user_id) = fixture_id {
+            let _ = client.delete(&format!("/Users/{}", user_id)).await;
+        }
+
+        results
+    }
+
+    /// The value of the `kind` entry of a multi-valued attribute.
+    fn typed_value<'a>(items: &'a [MultiValued], kind: &str) -> Option<&'a str> {
+        items.iter()
+            .find(|i| i.kind.as_deref().is_some_and(|k| k.eq_ignore_ascii_case(kind)))
+            .and_then(|i| i.value.as_deref())
+    }
+
+    /// A resource as read back, without `meta`, which a rejected write may still touch.
+    async fn resource_without_meta(client: &ScimClient, path: &str) -> Option<Value> {
+        let resp = client.get(path).await.ok().filter(|r| r.status == 200)?;
+        let mut resource: Value = serde_json::from_str(&resp.body).ok()?;
+        resource.as_object_mut()?.remove("meta");
+        Some(resource)
+    }
+
+    fn attribute_failure(attribute: &str, actual: Option<&str>, expected: &str) -> Option<String> {
+        if actual == Some(expected) {
+            return None;
+        }
+        Some(format!("PATCH succeeded but GET shows {}='{}' instead of '{}'", attribute, actual.unwrap_or("null"), expected))
+    }
+
+    // ── Filtering & Pagination Tests ──
+
+    async fn test_filtering_pagination(
+        app: &AppHandle,
+        client: &ScimClient,
+        test_run_id: &str,
+        capabilities: Option<&ServiceProviderConfig>,
+        completed: &mut usize,
+        total: usize,
+    ) -> Vec<ValidationResult> {
+        let mut results = Vec::new();
+        let category = "filtering_pagination";
+        let unfiltered = Self::unsupported(capabilities, "filter");
+
+        // Fixture users for filtering. Their attributes differ so that each operator
+        // test has one exact expected result set, and they are created a second
+        // apart so their meta.lastModified values can be ordered:
+        //   0: active, title, work email @test.example.com and home email @example.org
+        //   1: inactive, no title, home email
+        //   2: active, title, work email @example.org
+        let uid = Self::unique_suffix();
+        let user_prefix = format!("scim_filter_test_{}", uid);
+        let test_user_name = format!("{}@test.example.com", user_prefix);
+        let user_names = [test_user_name.clone(), format!("{}_b@test.example.com", user_prefix), format!("{}_c@test.example.com", user_prefix)];
+        let mut fixtures: Vec<(String, Option<String>)> = Vec::new(); // id and meta.lastModified
+        // Only the filter tests use them
+        let fixture_names = if unfiltered.is_none() { &user_names[..] } else { &[] };
+        for (i, user_name) in fixture_names.iter().enumerate() {
+            if i > 0 {
+                tokio::time::sleep(LAST_MODIFIED_WAIT).await;
+            }
+            let mut user = User::new(user_name)
+                .with_name("Filter", "TestUser")
+                .with_display_name("Filter Test User")
+                .with_active(i != 1);
+            match i {
+                0 => {
+                    user.title = Some("Engineer".to_string());
+                    user.emails.push(MultiValued::new(user_name, "work", true));
+                    user.emails.push(MultiValued::new(&format!("{}.home@example.org", user_prefix), "home", false));
+                }
+                1 => user.emails.push(MultiValued::new(user_name, "home", true)),
+                _ => {
+                    user.title = Some("Manager".to_string());
+                    user.emails.push(MultiValued::new(&format!("{}_c@example.org", user_prefix), "work", true));
+                }
+            }
+            if let Ok(resp) = client.post("/Users", &scim::to_body(&user)).await {
+                if resp.status == 201 {
+                    if let Ok(created) = serde_json::from_str::<User>(&resp.body) {
+                        let last_modified = created.meta.and_then(|m| m.last_modified);
+                        fixtures.extend(created.id.map(|id| (id, last_modified)));
+                    }
+                }
+            }
+        }
+        let created_user_ids: Vec<String> = fixtures.iter().map(|(id, _)| id.clone()).collect();
+
+        // Test 1: Filter by userName eq
+        let test_name = "GET /Users?filter - Filter by userName eq";
+        let requirement = Requirement::must("RFC 7644 §3.4.2.2", Severity::Critical);
+        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+        let filter_path = format!("/Users?filter={}", Filter::eq("userName", &test_user_name).encoded());
+        if let Some(ref reason) = unfiltered {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET", &filter_path, None, None, None, 0, None, TestOutcome::Skipped, Some(reason.clone())));
+        } else {
+            match client.get(&filter_path).await {
+                Ok(resp) => {
+                    let mut passed = resp.status == 200;
+                    let mut failure = None;
+                    if !passed {
+                        failure = Some(format!("Expected 200, got {}", resp.status));
+                    } else {
+                        match serde_json::from_str::<ListResponse<User>>(&resp.body) {
+                            Ok(list) => {
+                                if list.total_results.unwrap_or(0) == 0 {
+                                    passed = false;
+                                    failure = Some("Filter returned 0 results, expected at least 1".to_string());
+                                }
+                            }
+                            Err(e) => {
                                 passed = false;
-                                failure = Some("Filter returned 0 results, expected at least 1".to_string());
+                                failure = Some(format!("Invalid JSON: {}", e));
                             }
                         }
-                        Err(e) => {
-                            passed = false;
-                            failure = Some(format!("Invalid JSON: {}", e));
-                        }
                     }
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET", &filter_path, None, Some(resp.status as i32), Some(resp.body), resp.duration_ms, Some(resp.timing), passed, failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "GET", &filter_path, None, e));
                 }
-                results.push(Self::make_result(test_run_id, test_name, category, "GET", &filter_path, None, Some(resp.status as i32), Some(resp.body), resp.duration_ms, passed, failure));
-            }
-            Err(e) => {
-                results.push(Self::make_result(test_run_id, test_name, category, "GET", &filter_path, None, None, None, 0, false, Some(e)));
             }
         }
         *completed += 1;
 
         // Test 2: Pagination with startIndex and count
         let test_name = "GET /Users?startIndex&count - Pagination";
+        let requirement = Requirement::should("RFC 7644 §3.4.2.4", Severity::Medium);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
         match client.get("/Users?startIndex=1&count=2").await {
             Ok(resp) => {
@@ -1147,15 +1716,13 @@ impl ValidationEngine {
                 if !passed {
                     failure = Some(format!("Expected 200, got {}", resp.status));
                 } else {
-                    match serde_json::from_str::<Value>(&resp.body) {
-                        Ok(json) => {
-                            if json.get("totalResults").is_none() {
+                    // itemsPerPage is recommended but not strictly required
+                    match serde_json::from_str::<ListResponse<Value>>(&resp.body) {
+                        Ok(list) => {
+                            if list.total_results.is_none() {
                                 passed = false;
                                 failure = Some("Response missing 'totalResults' for pagination".to_string());
                             }
-                            if json.get("itemsPerPage").is_none() && Self::get_resources(&json).is_some() {
-                                // itemsPerPage is recommended but not strictly required
-                            }
                         }
                         Err(e) => {
                             passed = false;
@@ -1163,58 +1730,285 @@ impl ValidationEngine {
                         }
                     }
                 }
-                results.push(Self::make_result(test_run_id, test_name, category, "GET", "/Users?startIndex=1&count=2", None, Some(resp.status as i32), Some(resp.body), resp.duration_ms, passed, failure));
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET", "/Users?startIndex=1&count=2", None, Some(resp.status as i32), Some(resp.body), resp.duration_ms, Some(resp.timing), passed, failure));
             }
             Err(e) => {
-                results.push(Self::make_result(test_run_id, test_name, category, "GET", "/Users?startIndex=1&count=2", None, None, None, 0, false, Some(e)));
+                results.push(Self::error_result(test_run_id, test_name, category, requirement, "GET", "/Users?startIndex=1&count=2", None, e));
             }
         }
         *completed += 1;
 
         // Test 3: Filter with invalid filter — RFC 7644 §3.4.2.2 says
         // servers SHOULD return 400 (invalidFilter), but many servers
-        // silently ignore unknown attributes and return 200 instead.
-        // Treat 400 as a full pass, 200 as a pass-with-warning.
+        // silently ignore unknown attributes and return 200 instead, which
+        // passes with a warning and so still costs weighted score.
         let test_name = "GET /Users?filter - Invalid filter (expect 400)";
+        let requirement = Requirement::should("RFC 7644 §3.4.2.2", Severity::Low);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        match client.get("/Users?filter=invalidAttribute zz \"bad\"").await {
-            Ok(resp) => {
-                let (passed, failure) = match resp.status {
-                    400 => (true, None),
-                    200 => (true, Some("Server returned 200 instead of 400 for an invalid filter — RFC 7644 §3.4.2.2 recommends (SHOULD) returning 400 with scimType \"invalidFilter\"".to_string())),
-                    other => (false, Some(format!("Expected 400 for invalid filter, got {}", other))),
-                };
-                results.push(Self::make_result(test_run_id, test_name, category, "GET", "/Users?filter=invalidAttribute zz \"bad\"", None, Some(resp.status as i32), Some(resp.body), resp.duration_ms, passed, failure));
-            }
-            Err(e) => {
-                results.push(Self::make_result(test_run_id, test_name, category, "GET", "/Users?filter=invalidAttribute zz \"bad\"", None, None, None, 0, false, Some(e)));
+        let invalid_path = format!("/Users?filter={}", filter::encode_query_value("invalidAttribute zz \"bad\""));
+        if let Some(ref reason) = unfiltered {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET", &invalid_path, None, None, None, 0, None, TestOutcome::Skipped, Some(reason.clone())));
+        } else {
+            match client.get(&invalid_path).await {
+                Ok(resp) => {
+                    let (outcome, failure) = match resp.status {
+                        400 => (TestOutcome::Passed, None),
+                        200 => (TestOutcome::Warning, Some("Server returned 200 instead of 400 for an invalid filter; it SHOULD return 400 with scimType \"invalidFilter\"".to_string())),
+                        other => (TestOutcome::Failed, Some(format!("Expected 400 for invalid filter, got {}", other))),
+                    };
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET", &invalid_path, None, Some(resp.status as i32), Some(resp.body), resp.duration_ms, Some(resp.timing), outcome, failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "GET", &invalid_path, None, e));
+                }
             }
         }
         *completed += 1;
 
         // Test 4: Attributes parameter
         let test_name = "GET /Users?attributes - Select specific attributes";
+        let requirement = Requirement::must("RFC 7644 §3.4.2.5", Severity::Medium);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
         match client.get("/Users?attributes=userName,displayName&count=1").await {
             Ok(resp) => {
                 let passed = resp.status == 200;
                 let failure = if !passed { Some(format!("Expected 200, got {}", resp.status)) } else { None };
-                results.push(Self::make_result(test_run_id, test_name, category, "GET", "/Users?attributes=userName,displayName&count=1", None, Some(resp.status as i32), Some(resp.body), resp.duration_ms, passed, failure));
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET", "/Users?attributes=userName,displayName&count=1", None, Some(resp.status as i32), Some(resp.body), resp.duration_ms, Some(resp.timing), passed, failure));
             }
             Err(e) => {
-                results.push(Self::make_result(test_run_id, test_name, category, "GET", "/Users?attributes=userName,displayName&count=1", None, None, None, 0, false, Some(e)));
+                results.push(Self::error_result(test_run_id, test_name, category, requirement, "GET", "/Users?attributes=userName,displayName&count=1", None, e));
+            }
+        }
+        *completed += 1;
+
+        // Tests 5-8: POST /Users/.search returns what the equivalent GET returns
+        let prefix_filter = Filter::sw("userName", &user_prefix);
+        let search = SearchRequest::new().with_filter(&prefix_filter);
+        let requirement = Requirement::should("RFC 7644 §3.4.3", Severity::Medium);
+        let cases = [
+            ("POST /Users/.search - Filter matches GET", search.clone(), None, None),
+            ("POST /Users/.search - attributes matches GET", search.clone().with_attributes(&["userName"]), Some("userName"), Some("displayName")),
+            ("POST /Users/.search - excludedAttributes matches GET", search.clone().with_excluded_attributes(&["displayName"]), None, Some("displayName")),
+            ("POST /Users/.search - sortBy and paging match GET", search.clone().with_sort("userName", true).with_page(2, 1), None, None),
+        ];
+        for (test_name, request, present, absent) in cases {
+            Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+            let body = scim::to_body(&request);
+            let skip_reason = unfiltered.clone()
+                .or_else(|| request.sort_by.as_ref().and_then(|_| Self::unsupported(capabilities, "sort")));
+            if let Some(reason) = skip_reason {
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST", "/Users/.search", Some(body), None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+                *completed += 1;
+                continue;
+            }
+            let get_path = format!("/Users?{}", request.query_string());
+            let reference = client.get(&get_path).await;
+            match client.search("/Users", &request).await {
+                Ok(resp) => {
+                    let paged = request.count.is_some();
+                    let failure = if resp.status != 200 {
+                        Some(format!("Expected 200, got {}", resp.status))
+                    } else {
+                        match &reference {
+                            Ok(get) if get.status == 200 => Self::compare_search_results(&resp.body, &get.body, &get_path, paged)
+                                .or_else(|| Self::check_returned_attributes(&resp.body, present, absent))
+                                .or_else(|| {
+                                    let ids = Self::list_ids(&resp.body);
+                                    let missing = created_user_ids.iter().filter(|id| !ids.contains(id)).count();
+                                    (!paged && missing > 0).then(|| format!("{} of the {} test users are missing from the results", missing, created_user_ids.len()))
+                                }),
+                            Ok(get) => Some(format!("Reference query GET {} returned {}", get_path, get.status)),
+                            Err(e) => Some(format!("Reference query GET {} failed: {}", get_path, e)),
+                        }
+                    };
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST", "/Users/.search", Some(body), Some(resp.status as i32), Some(resp.body), resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "POST", "/Users/.search", Some(body), e));
+                }
+            }
+            *completed += 1;
+        }
+
+        // Test 9: root search spans every resource type; support for it is optional
+        let test_name = "POST /.search - Search from the server root";
+        let requirement = Requirement::may("RFC 7644 §3.4.3", Severity::Low);
+        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+        let request = SearchRequest::new().with_filter(&Filter::eq("userName", &test_user_name));
+        let body = scim::to_body(&request);
+        if let Some(ref reason) = unfiltered {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST", "/.search", Some(body), None, None, 0, None, TestOutcome::Skipped, Some(reason.clone())));
+        } else {
+            match client.search("", &request).await {
+                Ok(resp) => {
+                    let (outcome, failure) = match resp.status {
+                        200 => match created_user_ids.first() {
+                            Some(id) if !Self::list_ids(&resp.body).contains(id) => (TestOutcome::Failed, Some("Root search did not return the test user".to_string())),
+                            _ => (TestOutcome::Passed, None),
+                        },
+                        404 | 405 | 501 => (TestOutcome::Skipped, Some(format!("Skipped: the server does not support root-level search ({})", resp.status))),
+                        other => (TestOutcome::Failed, Some(format!("Expected 200, got {}", other))),
+                    };
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST", "/.search", Some(body), Some(resp.status as i32), Some(resp.body), resp.duration_ms, Some(resp.timing), outcome, failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "POST", "/.search", Some(body), e));
+                }
             }
         }
         *completed += 1;
 
+        // Tests 10-25: each operator against the fixtures, expecting the exact result set
+        let filter_skip = unfiltered.or_else(|| (created_user_ids.len() < user_names.len())
+            .then(|| "Skipped: the fixture users could not be created".to_string()));
+        let sort_skip = Self::unsupported(capabilities, "sort");
+
+        // meta.lastModified comparisons pivot on the middle fixture
+        let modified: Vec<Option<DateTime<FixedOffset>>> = fixtures.iter()
+            .map(|(_, m)| m.as_deref().and_then(|m| DateTime::parse_from_rfc3339(m).ok()))
+            .collect();
+        let pivot = fixtures.get(1).and_then(|(_, m)| m.clone()).zip(modified.get(1).copied().flatten());
+        let time_skip = (pivot.is_none() || modified.iter().any(Option::is_none))
+            .then(|| "Skipped: the fixture users have no readable meta.lastModified".to_string());
+        let (pivot_text, pivot_time) = pivot.unwrap_or_default();
+        let modified_where = |keep: fn(&DateTime<FixedOffset>, &DateTime<FixedOffset>) -> bool| -> Vec<usize> {
+            (0..modified.len()).filter(|&i| modified[i].as_ref().is_some_and(|t| keep(t, &pivot_time))).collect()
+        };
+
+        let scoped = |f: Filter| Filter::sw("userName", &user_prefix).and(f);
+        let operator = Requirement::must("RFC 7644 §3.4.2.2", Severity::High);
+        let cases = [
+            ("co - userName contains", operator, Filter::co("userName", format!("{}_b", uid)), "", vec![1], false, None),
+            ("sw - userName starts with", operator, Filter::sw("userName", format!("{}_", user_prefix)), "", vec![1, 2], false, None),
+            ("ew - userName ends with", operator, Filter::ew("userName", format!("{}_c@test.example.com", uid)), "", vec![2], false, None),
+            ("pr - title present", operator, scoped(Filter::present("title")), "", vec![0, 2], false, None),
+            ("gt - meta.lastModified", operator, scoped(Filter::gt("meta.lastModified", &pivot_text)), "", modified_where(|t, p| t > p), false, time_skip.clone()),
+            ("ge - meta.lastModified", operator, scoped(Filter::ge("meta.lastModified", &pivot_text)), "", modified_where(|t, p| t >= p), false, time_skip.clone()),
+            ("lt - meta.lastModified", operator, scoped(Filter::lt("meta.lastModified", &pivot_text)), "", modified_where(|t, p| t < p), false, time_skip.clone()),
+            ("le - meta.lastModified", operator, scoped(Filter::le("meta.lastModified", &pivot_text)), "", modified_where(|t, p| t <= p), false, time_skip),
+            ("and", operator, scoped(Filter::eq("active", false)), "", vec![1], false, None),
+            ("or", operator, Filter::eq("userName", &user_names[0]).or(Filter::eq("userName", &user_names[2])), "", vec![0, 2], false, None),
+            ("not", operator, scoped(Filter::eq("active", true).negate()), "", vec![1], false, None),
+            ("and binds tighter than or", operator, Filter::eq("userName", &user_names[2]).or(Filter::eq("userName", &user_names[0]).and(Filter::eq("active", false))), "", vec![2], false, None),
+            ("Complex valuePath on emails", operator, scoped(Filter::value_path("emails", Filter::eq("type", "work").and(Filter::ew("value", "@example.org")))), "", vec![2], false, None),
+            ("Case-insensitive attribute names", operator, Filter::sw("USERNAME", &user_prefix).and(Filter::eq("EMAILS.TYPE", "home")), "", vec![0, 1], false, None),
+            ("sortBy userName, sortOrder descending", Requirement::must("RFC 7644 §3.4.2.3", Severity::Medium), Filter::sw("userName", &user_prefix), "&sortBy=userName&sortOrder=descending", vec![2, 1, 0], true, sort_skip),
+            ("excludedAttributes", Requirement::must("RFC 7644 §3.4.2.5", Severity::Medium), Filter::sw("userName", &user_prefix), "&excludedAttributes=emails,title", vec![0, 1, 2], false, None),
+        ];
+        for (label, requirement, filter, extra, expected, ordered, case_skip) in cases {
+            let test_name = format!("GET /Users?filter - {}", label);
+            Self::emit_progress(app, test_run_id, &test_name, category, *completed, total);
+            let path = format!("/Users?filter={}{}", filter.encoded(), extra);
+            if let Some(reason) = filter_skip.clone().or(case_skip) {
+                results.push(Self::make_result(test_run_id, &test_name, category, requirement, "GET",
+                    &path, None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+                *completed += 1;
+                continue;
+            }
+            match client.get(&path).await {
+                Ok(resp) => {
+                    let unsupported = resp.status == 501 || (resp.status == 400
+                        && resp.scim_error().and_then(|e| e.scim_type).is_none_or(|t| t == "invalidFilter"));
+                    if unsupported {
+                        results.push(Self::make_result(test_run_id, &test_name, category, requirement, "GET",
+                            &path, None, Some(resp.status as i32), Some(resp.body),
+                            resp.duration_ms, Some(resp.timing), TestOutcome::Skipped,
+                            Some(format!("Skipped: the server does not support this filter ({})", resp.status))));
+                        *completed += 1;
+                        continue;
+                    }
+                    let failure = if resp.status != 200 {
+                        Some(format!("Expected 200, got {}", resp.status))
+                    } else {
+                        let expected: Vec<&str> = expected.iter().map(|&i| created_user_ids[i].as_str()).collect();
+                        Self::result_set_failure(&Self::list_ids(&resp.body), &expected, ordered)
+                            .or_else(|| if extra.contains("excludedAttributes") {
+                                Self::check_returned_attributes(&resp.body, Some("userName"), Some("emails"))
+                                    .or_else(|| Self::check_returned_attributes(&resp.body, None, Some("title")))
+                            } else {
+                                None
+                            })
+                    };
+                    results.push(Self::make_result(test_run_id, &test_name, category, requirement, "GET",
+                        &path, None, Some(resp.status as i32), Some(resp.body),
+                        resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, &test_name, category, requirement, "GET",
+                        &path, None, e));
+                }
+            }
+            *completed += 1;
+        }
+
         // Cleanup
-        if let Some(ref user_id) = created_user_id {
+        for user_id in &created_user_ids {
             let _ = client.delete(&format!("/Users/{}", user_id)).await;
         }
 
         results
     }
 
+    /// Compare the ids a filter returned with the fixtures it should match.
+    fn result_set_failure(returned: &[String], expected: &[&str], ordered: bool) -> Option<String> {
+        let mut returned: Vec<&str> = returned.iter().map(String::as_str).collect();
+        let mut expected = expected.to_vec();
+        if !ordered {
+            returned.sort_unstable();
+            expected.sort_unstable();
+        }
+        if returned == expected {
+            return None;
+        }
+        let order = if ordered { " in this order" } else { "" };
+        Some(format!("Returned ids {:?}, expected exactly {:?}{}", returned, expected, order))
+    }
+
+    fn list_ids(body: &str) -> Vec<String> {
+        ListResponse::<Value>::parse_lenient(body)
+            .map(|list| list.into_resources().iter().filter_map(|r| scim::resource_id(&r.to_string())).collect())
+            .unwrap_or_default()
+    }
+
+    /// Compare a .search response with its equivalent GET. A paged (and sorted)
+    /// query must return the same resources in the same order.
+    fn compare_search_results(search_body: &str, get_body: &str, get_path: &str, ordered: bool) -> Option<String> {
+        let search = match ListResponse::<Value>::parse_lenient(search_body) {
+            Ok(list) => list,
+            Err(e) => return Some(format!("Invalid ListResponse: {}", e)),
+        };
+        let get = ListResponse::<Value>::parse_lenient(get_body).ok()?;
+        if search.total_results != get.total_results {
+            return Some(format!("totalResults is {:?}, but GET {} reports {:?}", search.total_results, get_path, get.total_results));
+        }
+        let (mut search_ids, mut get_ids) = (Self::list_ids(search_body), Self::list_ids(get_body));
+        if !ordered {
+            search_ids.sort();
+            get_ids.sort();
+        }
+        if search_ids != get_ids {
+            return Some(format!("Returned ids {:?}, but GET {} returned {:?}", search_ids, get_path, get_ids));
+        }
+        None
+    }
+
+    fn check_returned_attributes(body: &str, present: Option<&str>, absent: Option<&str>) -> Option<String> {
+        let resources = ListResponse::<Value>::parse_lenient(body).ok()?.into_resources();
+        for resource in &resources {
+            if let Some(attr) = present {
+                if resource.get(attr).is_none() {
+                    return Some(format!("Requested attribute '{}' is missing from a result", attr));
+                }
+            }
+            if let Some(attr) = absent {
+                if resource.get(attr).is_some() {
+                    return Some(format!("Attribute '{}' should not be returned", attr));
+                }
+            }
+        }
+        None
+    }
+
     // ── Custom Schema Tests ──
 
     async fn test_custom_schema(
@@ -1230,10 +2024,11 @@ impl ValidationEngine {
 
         if attrs.is_empty() {
             let test_name = "No custom schema attributes discovered";
+            let requirement = Requirement::must("RFC 7643 §3.3", Severity::Medium);
             Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
             results.push(Self::make_result(
-                test_run_id, test_name, category, "N/A", "/Schemas", None, None, None, 0, true,
-                Some("Skipped — no extension schema attributes found in /Schemas".to_string()),
+                test_run_id, test_name, category, requirement, "N/A", "/Schemas", None, None, None, 0, None, TestOutcome::Skipped,
+                Some("Skipped: no extension schema attributes found in /Schemas".to_string()),
             ));
             *completed += 1;
             return results;
@@ -1286,36 +2081,22 @@ impl ValidationEngine {
             "POST /Users - Create with {}.{} = {}",
             short_schema, attr.attr_name, value_display
         );
+        let requirement = Requirement::must("RFC 7643 §3.3", Severity::Medium);
         Self::emit_progress(app, test_run_id, &test_name, category, *completed, total);
 
-        let uid = Uuid::new_v4().to_string().split('-').next().unwrap().to_string();
+        let uid = Self::unique_suffix();
         let test_user_name = format!("scim_custom_test_{}@test.example.com", uid);
 
         // Build the create body with the extension attribute
-        let mut body_map = serde_json::Map::new();
-        body_map.insert("schemas".to_string(), serde_json::json!([
-            "urn:ietf:params:scim:schemas:core:2.0:User",
-            &attr.schema_urn
-        ]));
-        body_map.insert("userName".to_string(), Value::String(test_user_name.clone()));
-        body_map.insert("name".to_string(), serde_json::json!({
-            "givenName": "Custom",
-            "familyName": "SchemaTest"
-        }));
-        body_map.insert("displayName".to_string(), Value::String("Custom Schema Test User".to_string()));
-        body_map.insert("emails".to_string(), serde_json::json!([{
-            "value": test_user_name,
-            "type": "work",
-            "primary": true
-        }]));
-        body_map.insert("active".to_string(), Value::Bool(true));
-
+        let mut user = User::new(&test_user_name)
+            .with_name("Custom", "SchemaTest")
+            .with_display_name("Custom Schema Test User")
+            .with_work_email(&test_user_name)
+            .with_active(true);
         // Extension attributes go under the schema URN key
-        let mut ext_map = serde_json::Map::new();
-        ext_map.insert(attr.attr_name.clone(), value.clone());
-        body_map.insert(attr.schema_urn.clone(), Value::Object(ext_map));
+        user.set_extension_attribute(&attr.schema_urn, &attr.attr_name, value.clone());
 
-        let body_str = serde_json::to_string(&Value::Object(body_map)).unwrap_or_default();
+        let body_str = scim::to_body(&user);
 
         let result = match client.post("/Users", &body_str).await {
             Ok(resp) => {
@@ -1325,12 +2106,10 @@ impl ValidationEngine {
                 if !passed {
                     failure = Some(format!("Expected status 201, got {}", resp.status));
                 } else {
-                    match serde_json::from_str::<Value>(&resp.body) {
-                        Ok(json) => {
+                    match serde_json::from_str::<User>(&resp.body) {
+                        Ok(created) => {
                             // Verify the extension attribute is echoed back correctly
-                            let returned_value = json
-                                .get(&attr.schema_urn)
-                                .and_then(|ext| ext.get(&attr.attr_name));
+                            let returned_value = created.extension_attribute(&attr.schema_urn, &attr.attr_name);
 
                             match returned_value {
                                 None => {
@@ -1344,7 +2123,7 @@ impl ValidationEngine {
                                     let values_match = if value.is_boolean() {
                                         rv.as_bool() == value.as_bool()
                                     } else {
-                                        rv == &value
+                                        rv == value
                                     };
                                     if !values_match {
                                         failure = Some(format!(
@@ -1357,7 +2136,7 @@ impl ValidationEngine {
                             }
 
                             // Cleanup: delete the created user
-                            if let Some(id) = json.get("id").and_then(|v| v.as_str()) {
+                            if let Some(id) = created.id {
                                 let _ = client.delete(&format!("/Users/{}", id)).await;
                             }
                         }
@@ -1368,17 +2147,16 @@ impl ValidationEngine {
                 }
 
                 Self::make_result(
-                    test_run_id, &test_name, category, "POST",
+                    test_run_id, &test_name, category, requirement, "POST",
                     "/Users", Some(body_str),
                     Some(resp.status as i32), Some(resp.body),
-                    resp.duration_ms, failure.is_none(), failure,
+                    resp.duration_ms, Some(resp.timing), failure.is_none(), failure,
                 )
             }
             Err(e) => {
-                Self::make_result(
-                    test_run_id, &test_name, category, "POST",
-                    "/Users", Some(body_str), None, None,
-                    0, false, Some(e),
+                Self::error_result(
+                    test_run_id, &test_name, category, requirement, "POST",
+                    "/Users", Some(body_str), e,
                 )
             }
         };
@@ -1402,18 +2180,16 @@ impl ValidationEngine {
         let category = "duplicate_detection";
 
         // ── User Duplicate Detection ──
-        let uid = Uuid::new_v4().to_string().split('-').next().unwrap().to_string();
+        let uid = Self::unique_suffix();
         let dup_user_name = format!("scim_dup_test_{}@test.example.com", uid);
-        let create_body = serde_json::json!({
-            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
-            "userName": dup_user_name,
-            "name": { "givenName": "Dup", "familyName": "TestUser" },
-            "displayName": "Dup Test User",
-            "active": true
-        }).to_string();
+        let create_body = scim::to_body(&User::new(&dup_user_name)
+            .with_name("Dup", "TestUser")
+            .with_display_name("Dup Test User")
+            .with_active(true));
 
         // Test 1: First creation should succeed with 201
         let test_name = "POST /Users - Create user (first, expect 201)";
+        let requirement = Requirement::must("RFC 7644 §3.3", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
         let mut first_user_id: Option<String> = None;
         match client.post("/Users", &create_body).await {
@@ -1421,44 +2197,51 @@ impl ValidationEngine {
                 let passed = resp.status == 201;
                 let mut failure = if !passed { Some(format!("Expected 201, got {}", resp.status)) } else { None };
                 if passed {
-                    if let Ok(json) = serde_json::from_str::<Value>(&resp.body) {
-                        first_user_id = json.get("id").and_then(|v| v.as_str()).map(|s| s.to_string());
-                        if first_user_id.is_none() {
-                            failure = Some("Response missing 'id' field".to_string());
-                        }
+                    first_user_id = scim::resource_id(&resp.body);
+                    if first_user_id.is_none() {
+                        failure = Some("Response missing 'id' field".to_string());
                     }
                 }
-                results.push(Self::make_result(test_run_id, test_name, category, "POST",
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST",
                     "/Users", Some(create_body.clone()),
                     Some(resp.status as i32), Some(resp.body),
-                    resp.duration_ms, failure.is_none(), failure));
+                    resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
             }
             Err(e) => {
-                results.push(Self::make_result(test_run_id, test_name, category, "POST",
-                    "/Users", Some(create_body.clone()), None, None, 0, false, Some(e)));
+                results.push(Self::error_result(test_run_id, test_name, category, requirement, "POST",
+                    "/Users", Some(create_body.clone()), e));
             }
         }
         *completed += 1;
+        let first_user = results.len() - 1;
 
         // Test 2: Second creation with same userName should return 409 Conflict
         let test_name = "POST /Users - Create duplicate user (expect 409)";
+        let requirement = Requirement::must("RFC 7644 §3.3", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        match client.post("/Users", &create_body).await {
-            Ok(resp) => {
-                let passed = resp.status == 409;
-                let failure = if !passed {
-                    Some(format!("Expected 409 Conflict for duplicate {}, got {}", user_joining_property, resp.status))
-                } else { None };
-                results.push(Self::make_result(test_run_id, test_name, category, "POST",
-                    "/Users", Some(create_body.clone()),
-                    Some(resp.status as i32), Some(resp.body),
-                    resp.duration_ms, passed, failure));
-            }
-            Err(e) => {
-                results.push(Self::make_result(test_run_id, test_name, category, "POST",
-                    "/Users", Some(create_body.clone()), None, None, 0, false, Some(e)));
+        let prerequisites = [first_user];
+        if let Some(reason) = Self::unmet_prerequisite(&results, &prerequisites) {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST",
+                "/Users", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else {
+            match client.post("/Users", &create_body).await {
+                Ok(resp) => {
+                    let passed = resp.status == 409;
+                    let failure = if !passed {
+                        Some(format!("Expected 409 Conflict for duplicate {}, got {}", user_joining_property, resp.status))
+                    } else { None };
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST",
+                        "/Users", Some(create_body.clone()),
+                        Some(resp.status as i32), Some(resp.body),
+                        resp.duration_ms, Some(resp.timing), passed, failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "POST",
+                        "/Users", Some(create_body.clone()), e));
+                }
             }
         }
+        Self::record_prerequisites(&mut results, &prerequisites);
         *completed += 1;
 
         // Cleanup first user
@@ -1467,15 +2250,12 @@ impl ValidationEngine {
         }
 
         // ── Group Duplicate Detection ──
-        let dup_group_name = format!("scim_dup_group_{}", Uuid::new_v4().to_string().split('-').next().unwrap());
-        let group_body = serde_json::json!({
-            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:Group"],
-            "displayName": dup_group_name,
-            "members": []
-        }).to_string();
+        let dup_group_name = format!("scim_dup_group_{}", Self::unique_suffix());
+        let group_body = scim::to_body(&Group::new(&dup_group_name));
 
         // Test 3: First group creation should succeed with 201
         let test_name = "POST /Groups - Create group (first, expect 201)";
+        let requirement = Requirement::must("RFC 7644 §3.3", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
         let mut first_group_id: Option<String> = None;
         match client.post("/Groups", &group_body).await {
@@ -1483,44 +2263,52 @@ impl ValidationEngine {
                 let passed = resp.status == 201;
                 let mut failure = if !passed { Some(format!("Expected 201, got {}", resp.status)) } else { None };
                 if passed {
-                    if let Ok(json) = serde_json::from_str::<Value>(&resp.body) {
-                        first_group_id = json.get("id").and_then(|v| v.as_str()).map(|s| s.to_string());
-                        if first_group_id.is_none() {
-                            failure = Some("Response missing 'id' field".to_string());
-                        }
+                    first_group_id = scim::resource_id(&resp.body);
+                    if first_group_id.is_none() {
+                        failure = Some("Response missing 'id' field".to_string());
                     }
                 }
-                results.push(Self::make_result(test_run_id, test_name, category, "POST",
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST",
                     "/Groups", Some(group_body.clone()),
                     Some(resp.status as i32), Some(resp.body),
-                    resp.duration_ms, failure.is_none(), failure));
+                    resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
             }
             Err(e) => {
-                results.push(Self::make_result(test_run_id, test_name, category, "POST",
-                    "/Groups", Some(group_body.clone()), None, None, 0, false, Some(e)));
+                results.push(Self::error_result(test_run_id, test_name, category, requirement, "POST",
+                    "/Groups", Some(group_body.clone()), e));
             }
         }
         *completed += 1;
+        let first_group = results.len() - 1;
 
         // Test 4: Second group creation with same displayName should return 409
         let test_name = "POST /Groups - Create duplicate group (expect 409)";
+        // displayName is not declared unique (RFC 7643 §8.7.1), so rejecting a second group is the server's choice
+        let requirement = Requirement::may("RFC 7644 §3.3", Severity::Low);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        match client.post("/Groups", &group_body).await {
-            Ok(resp) => {
-                let passed = resp.status == 409;
-                let failure = if !passed {
-                    Some(format!("Expected 409 Conflict for duplicate {}, got {}", group_joining_property, resp.status))
-                } else { None };
-                results.push(Self::make_result(test_run_id, test_name, category, "POST",
-                    "/Groups", Some(group_body.clone()),
-                    Some(resp.status as i32), Some(resp.body),
-                    resp.duration_ms, passed, failure));
-            }
-            Err(e) => {
-                results.push(Self::make_result(test_run_id, test_name, category, "POST",
-                    "/Groups", Some(group_body), None, None, 0, false, Some(e)));
+        let prerequisites = [first_group];
+        if let Some(reason) = Self::unmet_prerequisite(&results, &prerequisites) {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST",
+                "/Groups", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else {
+            match client.post("/Groups", &group_body).await {
+                Ok(resp) => {
+                    let passed = resp.status == 409;
+                    let failure = if !passed {
+                        Some(format!("Expected 409 Conflict for duplicate {}, got {}", group_joining_property, resp.status))
+                    } else { None };
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST",
+                        "/Groups", Some(group_body.clone()),
+                        Some(resp.status as i32), Some(resp.body),
+                        resp.duration_ms, Some(resp.timing), passed, failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "POST",
+                        "/Groups", Some(group_body), e));
+                }
             }
         }
+        Self::record_prerequisites(&mut results, &prerequisites);
         *completed += 1;
 
         // Cleanup first group
@@ -1537,88 +2325,101 @@ impl ValidationEngine {
         app: &AppHandle,
         client: &ScimClient,
         test_run_id: &str,
+        capabilities: Option<&ServiceProviderConfig>,
         joining_property: &str,
         completed: &mut usize,
         total: usize,
     ) -> Vec<ValidationResult> {
         let mut results = Vec::new();
         let category = "soft_delete";
-        let uid = Uuid::new_v4().to_string().split('-').next().unwrap().to_string();
+        let uid = Self::unique_suffix();
         let test_user_name = format!("scim_softdel_test_{}@test.example.com", uid);
         let mut created_user_id: Option<String> = None;
 
         // Test 1: Create a user with active=true
         let test_name = "POST /Users - Create user with active=true";
+        let requirement = Requirement::must("RFC 7644 §3.3", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        let create_body = serde_json::json!({
-            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
-            "userName": test_user_name,
-            "name": { "givenName": "SoftDel", "familyName": "TestUser" },
-            "displayName": "SoftDel Test User",
-            "active": true
-        }).to_string();
+        let create_body = scim::to_body(&User::new(&test_user_name)
+            .with_name("SoftDel", "TestUser")
+            .with_display_name("SoftDel Test User")
+            .with_active(true));
 
         match client.post("/Users", &create_body).await {
             Ok(resp) => {
                 let passed = resp.status == 201;
                 let mut failure = if !passed { Some(format!("Expected 201, got {}", resp.status)) } else { None };
                 if passed {
-                    if let Ok(json) = serde_json::from_str::<Value>(&resp.body) {
-                        created_user_id = json.get("id").and_then(|v| v.as_str()).map(|s| s.to_string());
-                        if created_user_id.is_none() {
-                            failure = Some("Response missing 'id' field".to_string());
-                        }
+                    created_user_id = scim::resource_id(&resp.body);
+                    if created_user_id.is_none() {
+                        failure = Some("Response missing 'id' field".to_string());
                     }
                 }
-                results.push(Self::make_result(test_run_id, test_name, category, "POST",
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST",
                     "/Users", Some(create_body.clone()),
                     Some(resp.status as i32), Some(resp.body),
-                    resp.duration_ms, failure.is_none(), failure));
+                    resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
             }
             Err(e) => {
-                results.push(Self::make_result(test_run_id, test_name, category, "POST",
-                    "/Users", Some(create_body), None, None, 0, false, Some(e)));
+                results.push(Self::error_result(test_run_id, test_name, category, requirement, "POST",
+                    "/Users", Some(create_body), e));
             }
         }
         *completed += 1;
+        let create_user = results.len() - 1;
 
         // Test 2: PATCH active to false (soft delete / disable)
         let test_name = "PATCH /Users/{id} - Set active=false (soft delete)";
+        let requirement = Requirement::must("RFC 7644 §3.5.2.3", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        if let Some(ref user_id) = created_user_id {
+        let prerequisites = [create_user];
+        let skip_reason = Self::unsupported(capabilities, "patch")
+            .or_else(|| Self::unmet_prerequisite(&results, &prerequisites));
+        if let Some(reason) = skip_reason {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH",
+                "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else if let Some(ref user_id) = created_user_id {
             let path = format!("/Users/{}", user_id);
-            let patch_body = serde_json::json!({
-                "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
-                "Operations": [{ "op": "replace", "path": "active", "value": false }]
-            }).to_string();
+            let patch_body = scim::to_body(&PatchOp::new(vec![
+                PatchOperation::replace(Some("active"), Value::Bool(false)),
+            ]));
             match client.patch(&path, &patch_body).await {
                 Ok(resp) => {
                     let passed = resp.status == 200 || resp.status == 204;
                     let failure = if !passed {
                         Some(format!("Expected 200/204, got {}", resp.status))
                     } else { None };
-                    results.push(Self::make_result(test_run_id, test_name, category, "PATCH",
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH",
                         &path, Some(patch_body),
                         Some(resp.status as i32), Some(resp.body),
-                        resp.duration_ms, passed, failure));
+                        resp.duration_ms, Some(resp.timing), passed, failure));
                 }
                 Err(e) => {
-                    results.push(Self::make_result(test_run_id, test_name, category, "PATCH",
-                        &path, Some(patch_body), None, None, 0, false, Some(e)));
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "PATCH",
+                        &path, Some(patch_body), e));
                 }
             }
         } else {
-            results.push(Self::make_result(test_run_id, test_name, category, "PATCH",
-                "/Users/{id}", None, None, None, 0, false,
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH",
+                "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped,
                 Some("Skipped: user creation failed".to_string())));
         }
+        Self::record_prerequisites(&mut results, &prerequisites);
         *completed += 1;
+        let deactivate = results.len() - 1;
 
         // Test 3: Verify active=false via filter
         let test_name = "GET /Users?filter - Verify active=false after soft delete";
+        let requirement = Requirement::must("RFC 7643 §4.1.1", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        if created_user_id.is_some() {
-            let filter_path = format!("/Users?filter={} eq \"{}\"", joining_property, test_user_name);
+        let prerequisites = [deactivate];
+        let skip_reason = Self::unsupported(capabilities, "filter")
+            .or_else(|| Self::unmet_prerequisite(&results, &prerequisites));
+        if let Some(reason) = skip_reason {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Users?filter=...", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else if created_user_id.is_some() {
+            let filter_path = format!("/Users?filter={}", Filter::eq(joining_property, &test_user_name).encoded());
             match client.get(&filter_path).await {
                 Ok(resp) => {
                     let mut passed = resp.status == 200;
@@ -1626,31 +2427,23 @@ impl ValidationEngine {
                     if !passed {
                         failure = Some(format!("Expected 200, got {}", resp.status));
                     } else {
-                        match serde_json::from_str::<Value>(&resp.body) {
-                            Ok(json) => {
-                                let resources = Self::get_resources(&json).and_then(|v| v.as_array());
-                                match resources {
-                                    Some(arr) if !arr.is_empty() => {
-                                        if let Some(user) = arr.first() {
-                                            let active = user.get("active");
-                                            match active {
-                                                Some(Value::Bool(false)) => {} // pass
-                                                Some(Value::Bool(true)) => {
-                                                    passed = false;
-                                                    failure = Some("User's 'active' is still true after PATCH to false".to_string());
-                                                }
-                                                Some(other) => {
-                                                    passed = false;
-                                                    failure = Some(format!("User's 'active' has unexpected value: {}", other));
-                                                }
-                                                None => {
-                                                    passed = false;
-                                                    failure = Some("User response does not contain 'active' field".to_string());
-                                                }
+                        match serde_json::from_str::<ListResponse<User>>(&resp.body) {
+                            Ok(list) => {
+                                match list.resources().first() {
+                                    Some(user) => {
+                                        match user.active {
+                                            Some(false) => {} // pass
+                                            Some(true) => {
+                                                passed = false;
+                                                failure = Some("User's 'active' is still true after PATCH to false".to_string());
+                                            }
+                                            None => {
+                                                passed = false;
+                                                failure = Some("User response does not contain a boolean 'active' field".to_string());
                                             }
                                         }
                                     }
-                                    _ => {
+                                    None => {
                                         passed = false;
                                         failure = Some("Disabled user should still be returned on GET request (soft delete ≠ hard delete)".to_string());
                                     }
@@ -1659,21 +2452,21 @@ impl ValidationEngine {
                             Err(e) => { passed = false; failure = Some(format!("Invalid JSON: {}", e)); }
                         }
                     }
-                    results.push(Self::make_result(test_run_id, test_name, category, "GET",
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
                         &filter_path, None, Some(resp.status as i32), Some(resp.body),
-                        resp.duration_ms, passed, failure));
+                        resp.duration_ms, Some(resp.timing), passed, failure));
                 }
                 Err(e) => {
-                    results.push(Self::make_result(test_run_id, test_name, category, "GET",
-                        &format!("/Users?filter={} eq \"{}\"", joining_property, test_user_name),
-                        None, None, None, 0, false, Some(e)));
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "GET",
+                        &filter_path, None, e));
                 }
             }
         } else {
-            results.push(Self::make_result(test_run_id, test_name, category, "GET",
-                "/Users?filter=...", None, None, None, 0, false,
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Users?filter=...", None, None, None, 0, None, TestOutcome::Skipped,
                 Some("Skipped: user creation failed".to_string())));
         }
+        Self::record_prerequisites(&mut results, &prerequisites);
         *completed += 1;
 
         // Cleanup
@@ -1684,194 +2477,1245 @@ impl ValidationEngine {
         results
     }
 
-    // ── Group Operations Tests (PATCH attrs, membership, joining property update) ──
+    // ── ETag Versioning Tests (RFC 7644 §3.14) ──
 
-    async fn test_group_operations(
+    async fn test_etag_versioning(
         app: &AppHandle,
         client: &ScimClient,
         test_run_id: &str,
+        capabilities: Option<&ServiceProviderConfig>,
         joining_property: &str,
         completed: &mut usize,
         total: usize,
     ) -> Vec<ValidationResult> {
         let mut results = Vec::new();
-        let category = "group_operations";
+        let category = "etag_versioning";
+        let test_names = [
+            ("POST /Users - Response includes ETag", Requirement::must("RFC 7644 §3.14", Severity::Medium)),
+            ("GET /Users/{id} - ETag matches meta.version", Requirement::must("RFC 7643 §3.1", Severity::Medium)),
+            ("GET /Users/{id} with If-None-Match - 304 Not Modified", Requirement::should("RFC 7644 §3.14", Severity::Low)),
+            ("PATCH /Users/{id} with current If-Match - Succeeds", Requirement::must("RFC 7644 §3.14", Severity::Medium)),
+            ("PUT /Users/{id} with stale If-Match - 412 Precondition Failed", Requirement::must("RFC 7644 §3.14", Severity::High)),
+            ("PATCH /Users/{id} with stale If-Match - 412 Precondition Failed", Requirement::must("RFC 7644 §3.14", Severity::High)),
+        ];
 
-        // Create a group for testing
-        let group_name = format!("scim_grpops_{}", Uuid::new_v4().to_string().split('-').next().unwrap());
-        let mut created_group_id: Option<String> = None;
+        // Versioning is optional; servers that declare it unsupported are not tested
+        Self::emit_progress(app, test_run_id, test_names[0].0, category, *completed, total);
+        if let Some(reason) = Self::unsupported(capabilities, "etag") {
+            for (test_name, requirement) in test_names {
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                    "/ServiceProviderConfig", None, None, None, 0, None, TestOutcome::Skipped, Some(reason.clone())));
+                *completed += 1;
+            }
+            return results;
+        }
 
-        let create_body = serde_json::json!({
-            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:Group"],
-            "displayName": group_name,
-            "members": []
-        }).to_string();
+        let uid = Self::unique_suffix();
+        let test_user_name = format!("scim_etag_test_{}@test.example.com", uid);
+        let mut user = User::new(&test_user_name)
+            .with_name("ETag", "TestUser")
+            .with_display_name("ETag Test User")
+            .with_active(true);
+        match joining_property {
+            "userName" => {}
+            "externalId" => user.external_id = Some(test_user_name.clone()),
+            other => {
+                user.extra.insert(other.to_string(), Value::String(test_user_name.clone()));
+            }
+        }
+        let create_body = scim::to_body(&user);
+        let mut created_user_id: Option<String> = None;
+        let mut original_etag: Option<String> = None;
 
-        // Test 1: Create group for operations
-        let test_name = "POST /Groups - Create group for PATCH tests";
-        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        match client.post("/Groups", &create_body).await {
+        // Test 1: POST returns an ETag header
+        let (test_name, requirement) = test_names[0];
+        match client.post("/Users", &create_body).await {
             Ok(resp) => {
-                let passed = resp.status == 201;
-                let mut failure = if !passed { Some(format!("Expected 201, got {}", resp.status)) } else { None };
-                if passed {
-                    if let Ok(json) = serde_json::from_str::<Value>(&resp.body) {
-                        created_group_id = json.get("id").and_then(|v| v.as_str()).map(|s| s.to_string());
-                        if created_group_id.is_none() { failure = Some("Response missing 'id'".to_string()); }
+                let mut failure = if resp.status != 201 { Some(format!("Expected 201, got {}", resp.status)) } else { None };
+                if failure.is_none() {
+                    created_user_id = scim::resource_id(&resp.body);
+                    if created_user_id.is_none() {
+                        failure = Some("Response missing 'id' field".to_string());
+                    } else if resp.etag.is_none() {
+                        failure = Some("Response has no ETag header".to_string());
                     }
                 }
-                results.push(Self::make_result(test_run_id, test_name, category, "POST",
-                    "/Groups", Some(create_body.clone()),
+                original_etag = resp.etag.clone();
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST",
+                    "/Users", Some(create_body.clone()),
                     Some(resp.status as i32), Some(resp.body),
-                    resp.duration_ms, failure.is_none(), failure));
+                    resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
             }
             Err(e) => {
-                results.push(Self::make_result(test_run_id, test_name, category, "POST",
-                    "/Groups", Some(create_body), None, None, 0, false, Some(e)));
+                results.push(Self::error_result(test_run_id, test_name, category, requirement, "POST",
+                    "/Users", Some(create_body.clone()), e));
             }
         }
         *completed += 1;
 
-        // Test 2: PATCH group displayName via replace
-        let updated_group_name = format!("{}_patched", group_name);
-        let test_name = "PATCH /Groups/{id} - Replace displayName";
+        // Test 2: GET returns the same version in the ETag header and meta.version
+        let (test_name, requirement) = test_names[1];
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        if let Some(ref group_id) = created_group_id {
-            let path = format!("/Groups/{}", group_id);
-            let patch_body = serde_json::json!({
-                "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
-                "Operations": [{ "op": "replace", "path": "displayName", "value": updated_group_name }]
-            }).to_string();
-            match client.patch(&path, &patch_body).await {
-                Ok(resp) => {
+        if let Some(ref user_id) = created_user_id {
+            let path = format!("/Users/{}", user_id);
+            match client.get(&path).await {
+                Ok(resp) => {
+                    let meta_version = serde_json::from_str::<User>(&resp.body).ok()
+                        .and_then(|user| user.meta?.version);
+                    let failure = if resp.status != 200 {
+                        Some(format!("Expected 200, got {}", resp.status))
+                    } else {
+                        match (&resp.etag, &meta_version) {
+                            (None, _) => Some("Response has no ETag header".to_string()),
+                            (Some(_), None) => Some("Resource has no meta.version".to_string()),
+                            (Some(etag), Some(version)) if etag.trim() != version.trim() => {
+                                Some(format!("ETag header {} does not match meta.version {}", etag, version))
+                            }
+                            _ => None,
+                        }
+                    };
+                    // A POST without an ETag header still lets the later tests run
+                    if original_etag.is_none() {
+                        original_etag = resp.etag.clone().or(meta_version);
+                    }
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                        &path, None, Some(resp.status as i32), Some(resp.body),
+                        resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "GET",
+                        &path, None, e));
+                }
+            }
+        } else {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped,
+                Some("Skipped: user creation failed".to_string())));
+        }
+        *completed += 1;
+
+        let target = created_user_id.clone().zip(original_etag.clone());
+
+        // Test 3: If-None-Match with the current version
+        let (test_name, requirement) = test_names[2];
+        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+        if let Some((ref user_id, ref etag)) = target {
+            let path = format!("/Users/{}", user_id);
+            match client.request_conditional(Method::GET, &path, None, Precondition::IfNoneMatch(etag)).await {
+                Ok(resp) => {
+                    let passed = resp.status == 304;
+                    let failure = if !passed { Some(format!("Expected 304 for If-None-Match: {}, got {}", etag, resp.status)) } else { None };
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                        &path, None, Some(resp.status as i32), Some(resp.body),
+                        resp.duration_ms, Some(resp.timing), passed, failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "GET",
+                        &path, None, e));
+                }
+            }
+        } else {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped,
+                Some("Skipped: no user or ETag to test with".to_string())));
+        }
+        *completed += 1;
+
+        // Test 4: PATCH with the current version succeeds and changes it, which
+        // makes the original ETag stale for the last two tests
+        let (test_name, requirement) = test_names[3];
+        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+        let mut modified = false;
+        let patch_skip = Self::unsupported(capabilities, "patch");
+        if let Some(ref reason) = patch_skip {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH",
+                "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(reason.clone())));
+        } else if let Some((ref user_id, ref etag)) = target {
+            let path = format!("/Users/{}", user_id);
+            let patch_body = scim::to_body(&PatchOp::new(vec![
+                PatchOperation::replace(Some("displayName"), Value::from("ETag Test User (modified)")),
+            ]));
+            match client.request_conditional(Method::PATCH, &path, Some(&patch_body), Precondition::IfMatch(etag)).await {
+                Ok(resp) => {
+                    modified = resp.status == 200 || resp.status == 204;
+                    let mut failure = if !modified { Some(format!("Expected 200/204 for If-Match: {}, got {}", etag, resp.status)) } else { None };
+                    if modified && resp.etag.as_deref().is_some_and(|new| new.trim() == etag.trim()) {
+                        failure = Some(format!("ETag did not change after the resource was modified ({})", etag));
+                    }
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH",
+                        &path, Some(patch_body), Some(resp.status as i32), Some(resp.body),
+                        resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "PATCH",
+                        &path, Some(patch_body), e));
+                }
+            }
+        } else {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH",
+                "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped,
+                Some("Skipped: no user or ETag to test with".to_string())));
+        }
+        *completed += 1;
+
+        // Tests 5 and 6: changes based on the stale version are rejected
+        user.display_name = Some("ETag Test User (stale write)".to_string());
+        let put_body = scim::to_body(&user);
+        let stale_patch_body = scim::to_body(&PatchOp::new(vec![
+            PatchOperation::replace(Some("displayName"), Value::from("ETag Test User (stale write)")),
+        ]));
+        let stale_writes = [
+            (test_names[4], Method::PUT, put_body),
+            (test_names[5], Method::PATCH, stale_patch_body),
+        ];
+        for ((test_name, requirement), method, body) in stale_writes {
+            Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+            match target {
+                _ if method == Method::PATCH && patch_skip.is_some() => {
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, method.as_str(),
+                        "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped, patch_skip.clone()));
+                }
+                Some((ref user_id, ref etag)) if modified => {
+                    let path = format!("/Users/{}", user_id);
+                    match client.request_conditional(method.clone(), &path, Some(&body), Precondition::IfMatch(etag)).await {
+                        Ok(resp) => {
+                            let passed = resp.status == 412;
+                            let failure = if !passed { Some(format!("Expected 412 for stale If-Match: {}, got {}", etag, resp.status)) } else { None };
+                            results.push(Self::make_result(test_run_id, test_name, category, requirement, method.as_str(),
+                                &path, Some(body), Some(resp.status as i32), Some(resp.body),
+                                resp.duration_ms, Some(resp.timing), passed, failure));
+                        }
+                        Err(e) => {
+                            results.push(Self::error_result(test_run_id, test_name, category, requirement, method.as_str(),
+                                &path, Some(body), e));
+                        }
+                    }
+                }
+                _ => {
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, method.as_str(),
+                        "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped,
+                        Some("Skipped: the user could not be modified to make its ETag stale".to_string())));
+                }
+            }
+            *completed += 1;
+        }
+
+        // Cleanup
+        if let Some(ref user_id) = created_user_id {
+            let _ = client.delete(&format!("/Users/{}", user_id)).await;
+        }
+
+        results
+    }
+
+    // ── Protocol Tests (RFC 7644 §3.1, §3.12, §8.1) ──
+
+    async fn test_protocol(
+        app: &AppHandle,
+        client: &ScimClient,
+        test_run_id: &str,
+        joining_property: &str,
+        completed: &mut usize,
+        total: usize,
+    ) -> Vec<ValidationResult> {
+        let mut results = Vec::new();
+        let category = "protocol";
+
+        // Tests 1 and 2: responses are labelled application/scim+json
+        for path in ["/ServiceProviderConfig", "/Users?count=1"] {
+            let test_name = format!("GET {} - Content-Type is application/scim+json", path.split('?').next().unwrap_or(path));
+            let requirement = Requirement::must("RFC 7644 §3.1", Severity::Medium);
+            Self::emit_progress(app, test_run_id, &test_name, category, *completed, total);
+            match client.get(path).await {
+                Ok(resp) => {
+                    let failure = if resp.status != 200 {
+                        Some(format!("Expected 200, got {}", resp.status))
+                    } else {
+                        Self::content_type_failure(&resp, &[scim::SCIM_MEDIA_TYPE])
+                    };
+                    results.push(Self::make_result(test_run_id, &test_name, category, requirement, "GET",
+                        path, None, Some(resp.status as i32), Some(resp.body),
+                        resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, &test_name, category, requirement, "GET",
+                        path, None, e));
+                }
+            }
+            *completed += 1;
+        }
+
+        // Test 3: plain application/json is accepted (RFC 7644 §8.1)
+        let test_name = "POST /Users with application/json - Accepted";
+        let requirement = Requirement::should("RFC 7644 §8.1", Severity::Medium);
+        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+        let uid = Self::unique_suffix();
+        let test_user_name = format!("scim_protocol_test_{}@test.example.com", uid);
+        let mut user = User::new(&test_user_name)
+            .with_name("Protocol", "TestUser")
+            .with_display_name("Protocol Test User")
+            .with_active(true);
+        match joining_property {
+            "userName" => {}
+            "externalId" => user.external_id = Some(test_user_name.clone()),
+            other => {
+                user.extra.insert(other.to_string(), Value::String(test_user_name.clone()));
+            }
+        }
+        let create_body = scim::to_body(&user);
+        let mut created_user_id: Option<String> = None;
+        match client.request_as(Method::POST, "/Users", Some(&create_body), "application/json").await {
+            Ok(resp) => {
+                let failure = if resp.status != 201 {
+                    Some(format!("Expected 201 for a request sent as application/json, got {}", resp.status))
+                } else {
+                    created_user_id = scim::resource_id(&resp.body);
+                    Self::content_type_failure(&resp, &["application/json", scim::SCIM_MEDIA_TYPE])
+                };
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST",
+                    "/Users", Some(create_body), Some(resp.status as i32), Some(resp.body),
+                    resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+            }
+            Err(e) => {
+                results.push(Self::error_result(test_run_id, test_name, category, requirement, "POST",
+                    "/Users", Some(create_body), e));
+            }
+        }
+        *completed += 1;
+
+        // Tests 4-7: errors come back with the right status and the SCIM error schema
+        let missing_path = format!("/Users/{}", Uuid::new_v4());
+        let malformed_body = r#"{"schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"], "userName": "#;
+        let error_cases = [
+            (
+                "GET /Users/{id} for a missing id - 404 with SCIM error", Requirement::must("RFC 7644 §3.12", Severity::Medium),
+                Method::GET, missing_path, None, vec![404],
+            ),
+            (
+                "POST /Users with malformed JSON - 400 with SCIM error", Requirement::must("RFC 7644 §3.12", Severity::Medium),
+                Method::POST, "/Users".to_string(), Some(malformed_body), vec![400],
+            ),
+            (
+                "DELETE /ServiceProviderConfig - 405 or 501", Requirement::should("RFC 7644 §3.12", Severity::Low),
+                Method::DELETE, "/ServiceProviderConfig".to_string(), None, vec![405, 501],
+            ),
+            (
+                "GET unknown endpoint - 404 with SCIM error", Requirement::should("RFC 7644 §3.12", Severity::Low),
+                Method::GET, "/ScimInspectorUnknownEndpoint".to_string(), None, vec![404],
+            ),
+        ];
+        for (test_name, requirement, method, path, body, expected) in error_cases {
+            // A 405 usually comes from the web framework, not the SCIM layer, so its body is not checked
+            let scim_error = !expected.contains(&405);
+            Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+            match client.request(method.clone(), &path, body).await {
+                Ok(resp) => {
+                    let failure = if !expected.contains(&resp.status) {
+                        let expected = expected.iter().map(u16::to_string).collect::<Vec<_>>().join(" or ");
+                        Some(format!("Expected {}, got {}", expected, resp.status))
+                    } else if scim_error {
+                        let problems = Self::error_response_problems(resp.status, &resp.body);
+                        if problems.is_empty() { None } else { Some(problems.join("; ")) }
+                    } else {
+                        None
+                    };
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, method.as_str(),
+                        &path, body.map(str::to_string), Some(resp.status as i32), Some(resp.body),
+                        resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, method.as_str(),
+                        &path, body.map(str::to_string), e));
+                }
+            }
+            *completed += 1;
+        }
+
+        // Cleanup
+        if let Some(ref user_id) = created_user_id {
+            let _ = client.delete(&format!("/Users/{}", user_id)).await;
+        }
+
+        results
+    }
+
+    /// A failure when the response's media type is not one of `accepted`.
+    fn content_type_failure(resp: &ScimResponse, accepted: &[&str]) -> Option<String> {
+        let content_type = match resp.header("content-type") {
+            Some(value) => value,
+            None => return Some("Response has no Content-Type header".to_string()),
+        };
+        let media_type = content_type.split(';').next().unwrap_or("").trim();
+        if accepted.iter().any(|a| a.eq_ignore_ascii_case(media_type)) {
+            None
+        } else {
+            Some(format!("Content-Type is '{}', expected {}", content_type, accepted.join(" or ")))
+        }
+    }
+
+    /// How an error body departs from RFC 7644 §3.12: the error schema, `status`
+    /// as a string matching the HTTP status, and a known `scimType`.
+    fn error_response_problems(status: u16, body: &str) -> Vec<String> {
+        let json = match serde_json::from_str::<Value>(body) {
+            Ok(json) if json.is_object() => json,
+            _ => return vec!["Error response is not a JSON object".to_string()],
+        };
+        let mut problems = Vec::new();
+        let declared = json.get("schemas")
+            .and_then(Value::as_array)
+            .is_some_and(|s| s.iter().any(|v| v.as_str() == Some(scim::ERROR_SCHEMA)));
+        if !declared {
+            problems.push(format!("schemas does not contain {}", scim::ERROR_SCHEMA));
+        }
+        match json.get("status") {
+            None => problems.push("status is missing".to_string()),
+            Some(Value::String(s)) if s.trim() == status.to_string() => {}
+            Some(Value::String(s)) => problems.push(format!("status is \"{}\" but the HTTP status is {}", s, status)),
+            Some(other) => problems.push(format!("status must be a string, got {}", other)),
+        }
+        match json.get("scimType") {
+            None | Some(Value::Null) => {}
+            Some(Value::String(t)) if scim::SCIM_ERROR_TYPES.contains(&t.as_str()) => {}
+            Some(other) => problems.push(format!("scimType {} is not one of the RFC 7644 keywords", other)),
+        }
+        problems
+    }
+
+    // ── Resource Metadata Tests (RFC 7643 §3.1, RFC 7644 §3.3) ──
+
+    async fn test_resource_metadata(
+        app: &AppHandle,
+        client: &ScimClient,
+        test_run_id: &str,
+        capabilities: Option<&ServiceProviderConfig>,
+        joining_property: &str,
+        completed: &mut usize,
+        total: usize,
+    ) -> Vec<ValidationResult> {
+        let mut results = Vec::new();
+        let category = "resource_metadata";
+        let test_names = [
+            ("POST /Users - Location header matches meta.location", Requirement::must("RFC 7644 §3.3", Severity::Medium)),
+            ("POST /Users - meta attributes present and well-formed", Requirement::must("RFC 7643 §3.1", Severity::Medium)),
+            ("GET /Users/{id} - meta unchanged by a read", Requirement::should("RFC 7643 §3.1", Severity::Low)),
+            ("PUT /Users/{id} - lastModified advances, created unchanged", Requirement::should("RFC 7643 §3.1", Severity::Low)),
+            ("PATCH /Users/{id} - lastModified advances, created unchanged", Requirement::should("RFC 7643 §3.1", Severity::Low)),
+            ("POST /Groups - Location header matches meta.location", Requirement::must("RFC 7644 §3.3", Severity::Medium)),
+            ("POST /Groups - meta attributes present and well-formed", Requirement::must("RFC 7643 §3.1", Severity::Medium)),
+        ];
+
+        // meta.version is only required of servers that support ETags
+        let versioned = capabilities.and_then(|c| c.supports("etag")) == Some(true);
+
+        let uid = Self::unique_suffix();
+        let test_user_name = format!("scim_meta_test_{}@test.example.com", uid);
+        let mut user = User::new(&test_user_name)
+            .with_name("Meta", "TestUser")
+            .with_display_name("Meta Test User")
+            .with_active(true);
+        match joining_property {
+            "userName" => {}
+            "externalId" => user.external_id = Some(test_user_name.clone()),
+            other => {
+                user.extra.insert(other.to_string(), Value::String(test_user_name.clone()));
+            }
+        }
+        let create_body = scim::to_body(&user);
+        let mut created_user_id: Option<String> = None;
+        let mut created_meta: Option<Meta> = None;
+
+        // Tests 1 and 2: the create response
+        Self::emit_progress(app, test_run_id, test_names[0].0, category, *completed, total);
+        match client.post("/Users", &create_body).await {
+            Ok(resp) => {
+                let created = serde_json::from_str::<User>(&resp.body).ok();
+                created_user_id = created.as_ref().and_then(|u| u.id.clone());
+                created_meta = created.and_then(|u| u.meta);
+                let (location_failure, meta_failure) = Self::created_resource_failures(
+                    &resp, created_meta.as_ref(), "User", "/Users", created_user_id.as_deref(), versioned);
+                results.push(Self::make_result(test_run_id, test_names[0].0, category, test_names[0].1, "POST",
+                    "/Users", Some(create_body.clone()), Some(resp.status as i32), Some(resp.body.clone()),
+                    resp.duration_ms, Some(resp.timing), location_failure.is_none(), location_failure));
+                *completed += 1;
+                Self::emit_progress(app, test_run_id, test_names[1].0, category, *completed, total);
+                results.push(Self::make_result(test_run_id, test_names[1].0, category, test_names[1].1, "POST",
+                    "/Users", Some(create_body.clone()), Some(resp.status as i32), Some(resp.body),
+                    resp.duration_ms, Some(resp.timing), meta_failure.is_none(), meta_failure));
+            }
+            Err(e) => {
+                for &(test_name, requirement) in &test_names[..2] {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "POST",
+                        "/Users", Some(create_body.clone()), &e));
+                }
+                *completed += 1;
+            }
+        }
+        *completed += 1;
+
+        // Test 3: a read changes nothing in meta
+        let (test_name, requirement) = test_names[2];
+        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+        if let Some(ref user_id) = created_user_id {
+            let path = format!("/Users/{}", user_id);
+            match client.get(&path).await {
+                Ok(resp) => {
+                    let meta = serde_json::from_str::<User>(&resp.body).ok().and_then(|u| u.meta);
+                    let failure = if resp.status != 200 {
+                        Some(format!("Expected 200, got {}", resp.status))
+                    } else {
+                        let mut problems = Self::meta_problems(meta.as_ref(), "User", &path, versioned);
+                        if let (Some(before), Some(after)) = (&created_meta, &meta) {
+                            let fields = [
+                                ("created", &before.created, &after.created),
+                                ("lastModified", &before.last_modified, &after.last_modified),
+                                ("version", &before.version, &after.version),
+                            ];
+                            for (name, before, after) in fields {
+                                if before.is_some() && before != after {
+                                    problems.push(format!("meta.{} changed from {} to {} without a write",
+                                        name, before.as_deref().unwrap_or("-"), after.as_deref().unwrap_or("-")));
+                                }
+                            }
+                        }
+                        if problems.is_empty() { None } else { Some(problems.join("; ")) }
+                    };
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                        &path, None, Some(resp.status as i32), Some(resp.body),
+                        resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "GET",
+                        &path, None, e));
+                }
+            }
+        } else {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped,
+                Some("Skipped: user creation failed".to_string())));
+        }
+        *completed += 1;
+
+        // Tests 4 and 5: each write moves lastModified forward from the previous one
+        let mut last_times = Self::meta_times(created_meta.as_ref());
+        user.display_name = Some("Meta Test User (replaced)".to_string());
+        let put_body = scim::to_body(&user);
+        let patch_body = scim::to_body(&PatchOp::new(vec![
+            PatchOperation::replace(Some("displayName"), Value::from("Meta Test User (patched)")),
+        ]));
+        let updates = [
+            (test_names[3], Method::PUT, put_body),
+            (test_names[4], Method::PATCH, patch_body),
+        ];
+        for ((test_name, requirement), method, body) in updates {
+            Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+            let patch_skip = (method == Method::PATCH).then(|| Self::unsupported(capabilities, "patch")).flatten();
+            match (&created_user_id, last_times) {
+                _ if patch_skip.is_some() => {
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, method.as_str(),
+                        "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped, patch_skip));
+                }
+                (Some(user_id), Some((created, last_modified))) => {
+                    tokio::time::sleep(LAST_MODIFIED_WAIT).await;
+                    let path = format!("/Users/{}", user_id);
+                    match client.request(method.clone(), &path, Some(&body)).await {
+                        Ok(resp) => {
+                            let failure = if resp.status != 200 && resp.status != 204 {
+                                Some(format!("Expected 200/204, got {}", resp.status))
+                            } else {
+                                // A 204 carries no resource, so read it back
+                                let updated = if resp.status == 204 {
+                                    client.get(&path).await.ok().and_then(|r| serde_json::from_str::<User>(&r.body).ok())
+                                } else {
+                                    serde_json::from_str::<User>(&resp.body).ok()
+                                };
+                                match Self::meta_times(updated.and_then(|u| u.meta).as_ref()) {
+                                    None => Some("Updated resource has no readable meta.created and meta.lastModified".to_string()),
+                                    Some((after, _)) if after != created => {
+                                        Some(format!("meta.created changed from {} to {}", created.to_rfc3339(), after.to_rfc3339()))
+                                    }
+                                    Some((_, after)) if after <= last_modified => {
+                                        Some(format!("meta.lastModified did not advance ({} before, {} after)", last_modified.to_rfc3339(), after.to_rfc3339()))
+                                    }
+                                    times => {
+                                        last_times = times;
+                                        None
+                                    }
+                                }
+                            };
+                            results.push(Self::make_result(test_run_id, test_name, category, requirement, method.as_str(),
+                                &path, Some(body), Some(resp.status as i32), Some(resp.body),
+                                resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+                        }
+                        Err(e) => {
+                            results.push(Self::error_result(test_run_id, test_name, category, requirement, method.as_str(),
+                                &path, Some(body), e));
+                        }
+                    }
+                }
+                (Some(_), None) => {
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, method.as_str(),
+                        "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped,
+                        Some("Skipped: the user has no readable meta.created and meta.lastModified to compare with".to_string())));
+                }
+                (None, _) => {
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, method.as_str(),
+                        "/Users/{id}", None, None, None, 0, None, TestOutcome::Skipped,
+                        Some("Skipped: user creation failed".to_string())));
+                }
+            }
+            *completed += 1;
+        }
+
+        // Tests 6 and 7: the same create checks for a group
+        let group_body = scim::to_body(&Group::new(&format!("SCIM Meta Test Group {}", uid)));
+        let mut created_group_id: Option<String> = None;
+        Self::emit_progress(app, test_run_id, test_names[5].0, category, *completed, total);
+        match client.post("/Groups", &group_body).await {
+            Ok(resp) => {
+                let created = serde_json::from_str::<Group>(&resp.body).ok();
+                created_group_id = created.as_ref().and_then(|g| g.id.clone());
+                let meta = created.and_then(|g| g.meta);
+                let (location_failure, meta_failure) = Self::created_resource_failures(
+                    &resp, meta.as_ref(), "Group", "/Groups", created_group_id.as_deref(), versioned);
+                results.push(Self::make_result(test_run_id, test_names[5].0, category, test_names[5].1, "POST",
+                    "/Groups", Some(group_body.clone()), Some(resp.status as i32), Some(resp.body.clone()),
+                    resp.duration_ms, Some(resp.timing), location_failure.is_none(), location_failure));
+                *completed += 1;
+                Self::emit_progress(app, test_run_id, test_names[6].0, category, *completed, total);
+                results.push(Self::make_result(test_run_id, test_names[6].0, category, test_names[6].1, "POST",
+                    "/Groups", Some(group_body.clone()), Some(resp.status as i32), Some(resp.body),
+                    resp.duration_ms, Some(resp.timing), meta_failure.is_none(), meta_failure));
+            }
+            Err(e) => {
+                for &(test_name, requirement) in &test_names[5..] {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "POST",
+                        "/Groups", Some(group_body.clone()), &e));
+                }
+                *completed += 1;
+            }
+        }
+        *completed += 1;
+
+        // Cleanup
+        if let Some(ref group_id) = created_group_id {
+            let _ = client.delete(&format!("/Groups/{}", group_id)).await;
+        }
+        if let Some(ref user_id) = created_user_id {
+            let _ = client.delete(&format!("/Users/{}", user_id)).await;
+        }
+
+        results
+    }
+
+    /// The Location header and `meta` failures for a create response. The
+    /// Location header must carry the same URI as `meta.location` (RFC 7644 §3.3).
+    fn created_resource_failures(
+        resp: &ScimResponse,
+        meta: Option<&Meta>,
+        resource_type: &str,
+        endpoint: &str,
+        id: Option<&str>,
+        versioned: bool,
+    ) -> (Option<String>, Option<String>) {
+        let id = match id {
+            Some(id) if resp.status == 201 => id,
+            Some(_) => {
+                let failure = Some(format!("Expected 201, got {}", resp.status));
+                return (failure.clone(), failure);
+            }
+            None => {
+                let failure = Some(format!("Response (status {}) has no 'id'", resp.status));
+                return (failure.clone(), failure);
+            }
+        };
+        let location_failure = match (resp.location().map(str::trim), meta.and_then(|m| m.location.as_deref())) {
+            (None, _) => Some("Response has no Location header".to_string()),
+            (Some(_), None) => Some("Resource has no meta.location".to_string()),
+            (Some(header), Some(location)) if header != location.trim() => {
+                Some(format!("Location header {} does not match meta.location {}", header, location))
+            }
+            _ => None,
+        };
+        let problems = Self::meta_problems(meta, resource_type, &format!("{}/{}", endpoint, id), versioned);
+        let meta_failure = if problems.is_empty() { None } else { Some(problems.join("; ")) };
+        (location_failure, meta_failure)
+    }
+
+    /// What is missing or malformed in a resource's `meta` (RFC 7643 §3.1).
+    fn meta_problems(meta: Option<&Meta>, resource_type: &str, resource_path: &str, versioned: bool) -> Vec<String> {
+        let meta = match meta {
+            Some(meta) => meta,
+            None => return vec!["Resource has no meta attribute".to_string()],
+        };
+        let mut problems = Vec::new();
+        match meta.resource_type.as_deref() {
+            None => problems.push("meta.resourceType is missing".to_string()),
+            Some(t) if t != resource_type => problems.push(format!("meta.resourceType is '{}', expected '{}'", t, resource_type)),
+            _ => {}
+        }
+        let mut timestamp = |name: &str, value: &Option<String>| match value.as_deref() {
+            None => {
+                problems.push(format!("meta.{} is missing", name));
+                None
+            }
+            Some(v) => match DateTime::parse_from_rfc3339(v) {
+                Ok(t) => Some(t),
+                Err(_) => {
+                    problems.push(format!("meta.{} '{}' is not a valid dateTime", name, v));
+                    None
+                }
+            },
+        };
+        let created = timestamp("created", &meta.created);
+        let last_modified = timestamp("lastModified", &meta.last_modified);
+        if let (Some(created), Some(last_modified)) = (created, last_modified) {
+            if last_modified < created {
+                problems.push(format!("meta.lastModified {} is before meta.created {}", last_modified.to_rfc3339(), created.to_rfc3339()));
+            }
+        }
+        match meta.location.as_deref() {
+            None => problems.push("meta.location is missing".to_string()),
+            Some(l) if !l.starts_with("http://") && !l.starts_with("https://") => {
+                problems.push(format!("meta.location '{}' is not an absolute URI", l));
+            }
+            Some(l) if !l.trim_end_matches('/').ends_with(resource_path) => {
+                problems.push(format!("meta.location '{}' does not point at {}", l, resource_path));
+            }
+            _ => {}
+        }
+        if versioned && meta.version.is_none() {
+            problems.push("meta.version is missing although the server supports ETags".to_string());
+        }
+        problems
+    }
+
+    /// `meta.created` and `meta.lastModified`, when both parse.
+    fn meta_times(meta: Option<&Meta>) -> Option<(DateTime<FixedOffset>, DateTime<FixedOffset>)> {
+        let meta = meta?;
+        let created = DateTime::parse_from_rfc3339(meta.created.as_deref()?).ok()?;
+        let last_modified = DateTime::parse_from_rfc3339(meta.last_modified.as_deref()?).ok()?;
+        Some((created, last_modified))
+    }
+
+    // ── Bulk Operations Tests (RFC 7644 §3.7) ──
+
+    async fn test_bulk_operations(
+        app: &AppHandle,
+        client: &ScimClient,
+        test_run_id: &str,
+        capabilities: Option<&ServiceProviderConfig>,
+        completed: &mut usize,
+        total: usize,
+    ) -> Vec<ValidationResult> {
+        let mut results = Vec::new();
+        let category = "bulk_operations";
+        let test_names = [
+            ("GET /ServiceProviderConfig - Bulk limits advertised", Requirement::must("RFC 7643 §5", Severity::Medium)),
+            ("POST /Bulk - Create User and Group with bulkId reference", Requirement::must("RFC 7644 §3.7.2", Severity::High)),
+            ("GET /Groups/{id} - bulkId reference resolved to the User's id", Requirement::must("RFC 7644 §3.7.2", Severity::High)),
+            ("POST /Bulk - PATCH and DELETE in one request", Requirement::must("RFC 7644 §3.7", Severity::High)),
+            ("POST /Bulk - failOnErrors stops after the first error", Requirement::must("RFC 7644 §3.7.3", Severity::Medium)),
+            ("POST /Bulk - More than maxOperations rejected with 413", Requirement::must("RFC 7644 §3.7.4", Severity::Medium)),
+            ("POST /Bulk - Payload over maxPayloadSize rejected with 413", Requirement::must("RFC 7644 §3.7.4", Severity::Medium)),
+        ];
+
+        // Test 1: bulk is optional; servers that declare it unsupported are not tested
+        let (test_name, requirement) = test_names[0];
+        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+        if let Some(reason) = Self::unsupported(capabilities, "bulk") {
+            for (test_name, requirement) in test_names {
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                    "/ServiceProviderConfig", None, None, None, 0, None, TestOutcome::Skipped, Some(reason.clone())));
+                *completed += 1;
+            }
+            return results;
+        }
+        let bulk = capabilities.and_then(|c| c.bulk.clone()).unwrap_or_default();
+        let (outcome, failure) = match capabilities.map(|c| c.bulk.as_ref()) {
+            None => (TestOutcome::Skipped, Some("Skipped: ServiceProviderConfig could not be read".to_string())),
+            Some(None) => (TestOutcome::Failed, Some("ServiceProviderConfig does not declare bulk".to_string())),
+            Some(Some(_)) => {
+                let mut missing = Vec::new();
+                if bulk.max_operations.unwrap_or(0) == 0 { missing.push("maxOperations"); }
+                if bulk.max_payload_size.unwrap_or(0) == 0 { missing.push("maxPayloadSize"); }
+                if missing.is_empty() {
+                    (TestOutcome::Passed, None)
+                } else {
+                    (TestOutcome::Failed, Some(format!("bulk is supported but {} is not set", missing.join(" and "))))
+                }
+            }
+        };
+        results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+            "/ServiceProviderConfig", None, None, capabilities.and_then(|c| serde_json::to_string(c).ok()),
+            0, None, outcome, failure));
+        *completed += 1;
+
+        let uid = Self::unique_suffix();
+        let test_user_name = format!("scim_bulk_test_{}@test.example.com", uid);
+        let test_group_name = format!("SCIM Bulk Test Group {}", uid);
+        let mut created_user_id: Option<String> = None;
+        let mut created_group_id: Option<String> = None;
+
+        // Test 2: the group's member refers to the user created earlier in the request
+        let (test_name, requirement) = test_names[1];
+        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+        let mut group = Group::new(&test_group_name);
+        group.members.push(Member::new(&scim::bulk_ref("user")));
+        let create_request = BulkRequest::new(vec![
+            BulkOperation::post("user", "/Users", &User::new(&test_user_name)
+                .with_name("Bulk", "TestUser")
+                .with_display_name("Bulk Test User")
+                .with_active(true)),
+            BulkOperation::post("group", "/Groups", &group),
+        ]);
+        let create_body = scim::to_body(&create_request);
+        match client.bulk(&create_request).await {
+            Ok(resp) => {
+                let operations = resp.bulk_response().map(|r| r.operations).unwrap_or_default();
+                for op in &operations {
+                    match op.bulk_id.as_deref() {
+                        Some("user") if op.is_success() => created_user_id = op.resource_id(),
+                        Some("group") if op.is_success() => created_group_id = op.resource_id(),
+                        _ => {}
+                    }
+                }
+                let failure = if resp.status != 200 {
+                    Some(format!("Expected 200, got {}", resp.status))
+                } else if operations.len() != 2 {
+                    Some(format!("Expected results for 2 operations, got {}", operations.len()))
+                } else if let Some(op) = operations.iter().find(|op| op.status != Some(201)) {
+                    Some(Self::bulk_operation_failure(op, "201"))
+                } else if created_user_id.is_none() || created_group_id.is_none() {
+                    Some("Operation results are missing 'location'".to_string())
+                } else {
+                    None
+                };
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST",
+                    "/Bulk", Some(create_body), Some(resp.status as i32), Some(resp.body),
+                    resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+            }
+            Err(e) => {
+                results.push(Self::error_result(test_run_id, test_name, category, requirement, "POST",
+                    "/Bulk", Some(create_body), e));
+            }
+        }
+        *completed += 1;
+        let bulk_create = results.len() - 1;
+
+        // Test 3: the server replaced "bulkId:user" with the new user's id
+        let (test_name, requirement) = test_names[2];
+        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+        let prerequisites = [bulk_create];
+        if let Some(reason) = Self::unmet_prerequisite(&results, &prerequisites) {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Groups/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else if let (Some(ref user_id), Some(ref group_id)) = (&created_user_id, &created_group_id) {
+            let path = format!("/Groups/{}", group_id);
+            match client.get(&path).await {
+                Ok(resp) => {
+                    let failure = if resp.status != 200 {
+                        Some(format!("Expected 200, got {}", resp.status))
+                    } else {
+                        match serde_json::from_str::<Group>(&resp.body) {
+                            Ok(group) if group.has_member(user_id) => None,
+                            Ok(group) if group.members.iter().any(|m| m.value.as_deref() == Some(&scim::bulk_ref("user"))) => {
+                                Some("Member value was stored as \"bulkId:user\" instead of the User's id".to_string())
+                            }
+                            Ok(_) => Some(format!("Group does not have the bulk-created User {} as a member", user_id)),
+                            Err(e) => Some(format!("Invalid Group JSON: {}", e)),
+                        }
+                    };
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                        &path, None, Some(resp.status as i32), Some(resp.body),
+                        resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "GET",
+                        &path, None, e));
+                }
+            }
+        } else {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Groups/{id}", None, None, None, 0, None, TestOutcome::Skipped,
+                Some("Skipped: bulk create failed".to_string())));
+        }
+        Self::record_prerequisites(&mut results, &prerequisites);
+        *completed += 1;
+
+        // Test 4: mixed methods in one request; also cleans up tests 2 and 3
+        let (test_name, requirement) = test_names[3];
+        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+        if let (Some(user_id), Some(group_id)) = (created_user_id.clone(), created_group_id.clone()) {
+            let user_path = format!("/Users/{}", user_id);
+            let group_path = format!("/Groups/{}", group_id);
+            let request = BulkRequest::new(vec![
+                BulkOperation::patch(&user_path, &PatchOp::new(vec![
+                    PatchOperation::replace(Some("displayName"), Value::from("Bulk Test User (patched)")),
+                ])),
+                BulkOperation::delete(&group_path),
+                BulkOperation::delete(&user_path),
+            ]);
+            let body = scim::to_body(&request);
+            match client.bulk(&request).await {
+                Ok(resp) => {
+                    let operations = resp.bulk_response().map(|r| r.operations).unwrap_or_default();
+                    let expected: [&[u16]; 3] = [&[200, 204], &[204], &[204]];
+                    let failure = if resp.status != 200 {
+                        Some(format!("Expected 200, got {}", resp.status))
+                    } else if operations.len() != expected.len() {
+                        Some(format!("Expected results for {} operations, got {}", expected.len(), operations.len()))
+                    } else {
+                        operations.iter().zip(expected)
+                            .find(|(op, statuses)| !op.status.is_some_and(|s| statuses.contains(&s)))
+                            .map(|(op, statuses)| {
+                                let statuses = statuses.iter().map(u16::to_string).collect::<Vec<_>>().join(" or ");
+                                Self::bulk_operation_failure(op, &statuses)
+                            })
+                    };
+                    // Results come back in request order
+                    if operations.get(1).is_some_and(|op| op.is_success()) { created_group_id = None; }
+                    if operations.get(2).is_some_and(|op| op.is_success()) { created_user_id = None; }
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST",
+                        "/Bulk", Some(body), Some(resp.status as i32), Some(resp.body),
+                        resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "POST",
+                        "/Bulk", Some(body), e));
+                }
+            }
+        } else {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST",
+                "/Bulk", None, None, None, 0, None, TestOutcome::Skipped,
+                Some("Skipped: bulk create failed".to_string())));
+        }
+        *completed += 1;
+
+        // Test 5: with failOnErrors = 1 the first (404) error ends processing,
+        // so the POST after it must not run
+        let (test_name, requirement) = test_names[4];
+        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+        let request = BulkRequest::new(vec![
+            BulkOperation::delete(&format!("/Users/{}", Uuid::new_v4())),
+            BulkOperation::post("after-error", "/Users", &User::new(&format!("scim_bulk_after_error_{}@test.example.com", uid))),
+        ]).with_fail_on_errors(1);
+        let body = scim::to_body(&request);
+        match client.bulk(&request).await {
+            Ok(resp) => {
+                let operations = resp.bulk_response().map(|r| r.operations).unwrap_or_default();
+                let executed_post = operations.iter()
+                    .find(|op| op.bulk_id.as_deref() == Some("after-error") && op.is_success());
+                if let Some(id) = executed_post.and_then(|op| op.resource_id()) {
+                    let _ = client.delete(&format!("/Users/{}", id)).await;
+                }
+                let failure = if resp.status != 200 {
+                    Some(format!("Expected 200, got {}", resp.status))
+                } else if operations.first().is_none_or(|op| op.is_success()) {
+                    Some("Expected the DELETE of a nonexistent User to fail".to_string())
+                } else if executed_post.is_some() {
+                    Some("The server kept processing after failOnErrors was reached".to_string())
+                } else {
+                    None
+                };
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST",
+                    "/Bulk", Some(body), Some(resp.status as i32), Some(resp.body),
+                    resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+            }
+            Err(e) => {
+                results.push(Self::error_result(test_run_id, test_name, category, requirement, "POST",
+                    "/Bulk", Some(body), e));
+            }
+        }
+        *completed += 1;
+
+        // Tests 6 and 7: requests over the advertised limits. Both only target
+        // nonexistent users, so a server that accepts them changes nothing.
+        let (test_name, requirement) = test_names[5];
+        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+        match bulk.max_operations {
+            Some(max) if max > 0 && max <= BULK_MAX_OPERATIONS_TESTED => {
+                let request = BulkRequest::new((0..=max)
+                    .map(|_| BulkOperation::delete(&format!("/Users/{}", Uuid::new_v4())))
+                    .collect());
+                let body = scim::to_body(&request);
+                match client.bulk(&request).await {
+                    Ok(resp) => {
+                        let passed = resp.status == 413;
+                        let failure = if !passed { Some(format!("Expected 413 for {} operations (maxOperations = {}), got {}", max + 1, max, resp.status)) } else { None };
+                        results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST",
+                            "/Bulk", Some(body), Some(resp.status as i32), Some(resp.body),
+                            resp.duration_ms, Some(resp.timing), passed, failure));
+                    }
+                    Err(e) => {
+                        results.push(Self::error_result(test_run_id, test_name, category, requirement, "POST",
+                            "/Bulk", Some(body), e));
+                    }
+                }
+            }
+            other => {
+                let reason = match other {
+                    Some(max) if max > 0 => format!("Skipped: maxOperations ({}) is too large to exceed", max),
+                    _ => "Skipped: maxOperations is not advertised".to_string(),
+                };
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST",
+                    "/Bulk", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+            }
+        }
+        *completed += 1;
+
+        let (test_name, requirement) = test_names[6];
+        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+        match bulk.max_payload_size {
+            Some(max) if max > 0 && max <= BULK_MAX_PAYLOAD_TESTED => {
+                let filler = "x".repeat(max as usize + 1);
+                let request = BulkRequest::new(vec![
+                    BulkOperation::patch(&format!("/Users/{}", Uuid::new_v4()), &PatchOp::new(vec![
+                        PatchOperation::replace(Some("displayName"), Value::String(filler)),
+                    ])),
+                ]);
+                let size = scim::to_body(&request).len();
+                // The body itself is not stored; it is mostly filler
+                let described = format!("<{} byte request: PATCH of a nonexistent User with an oversized displayName>", size);
+                match client.bulk(&request).await {
+                    Ok(resp) => {
+                        let passed = resp.status == 413;
+                        let failure = if !passed { Some(format!("Expected 413 for a {} byte request (maxPayloadSize = {}), got {}", size, max, resp.status)) } else { None };
+                        results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST",
+                            "/Bulk", Some(described), Some(resp.status as i32), Some(resp.body),
+                            resp.duration_ms, Some(resp.timing), passed, failure));
+                    }
+                    Err(e) => {
+                        results.push(Self::error_result(test_run_id, test_name, category, requirement, "POST",
+                            "/Bulk", Some(described), e));
+                    }
+                }
+            }
+            other => {
+                let reason = match other {
+                    Some(max) if max > 0 => format!("Skipped: maxPayloadSize ({} bytes) is too large to exceed", max),
+                    _ => "Skipped: maxPayloadSize is not advertised".to_string(),
+                };
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST",
+                    "/Bulk", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+            }
+        }
+        *completed += 1;
+
+        // Cleanup whatever test 4 did not delete
+        if let Some(ref group_id) = created_group_id {
+            let _ = client.delete(&format!("/Groups/{}", group_id)).await;
+        }
+        if let Some(ref user_id) = created_user_id {
+            let _ = client.delete(&format!("/Users/{}", user_id)).await;
+        }
+
+        results
+    }
+
+    /// "Operation POST user: expected 201, got 409 (uniqueness: ...)"
+    fn bulk_operation_failure(op: &scim::BulkOperationResponse, expected: &str) -> String {
+        let status = op.status.map(|s| s.to_string()).unwrap_or_else(|| "no status".to_string());
+        let label = op.bulk_id.as_deref().map(|id| format!("{} {}", op.method, id)).unwrap_or_else(|| op.method.clone());
+        match op.error().and_then(|e| e.summary()) {
+            Some(summary) => format!("Operation {}: expected {}, got {} ({})", label, expected, status, summary),
+            None => format!("Operation {}: expected {}, got {}", label, expected, status),
+        }
+    }
+
+    // ── Group Operations Tests (PATCH attrs, membership, joining property update) ──
+
+    async fn test_group_operations(
+        app: &AppHandle,
+        client: &ScimClient,
+        test_run_id: &str,
+        capabilities: Option<&ServiceProviderConfig>,
+        joining_property: &str,
+        completed: &mut usize,
+        total: usize,
+    ) -> Vec<ValidationResult> {
+        let mut results = Vec::new();
+        let category = "group_operations";
+        let patch_skip = Self::unsupported(capabilities, "patch");
+
+        // Create a group for testing
+        let group_name = format!("scim_grpops_{}", Self::unique_suffix());
+        let mut created_group_id: Option<String> = None;
+
+        let create_body = scim::to_body(&Group::new(&group_name));
+
+        // Test 1: Create group for operations
+        let test_name = "POST /Groups - Create group for PATCH tests";
+        let requirement = Requirement::must("RFC 7644 §3.3", Severity::High);
+        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+        match client.post("/Groups", &create_body).await {
+            Ok(resp) => {
+                let passed = resp.status == 201;
+                let mut failure = if !passed { Some(format!("Expected 201, got {}", resp.status)) } else { None };
+                if passed {
+                    created_group_id = scim::resource_id(&resp.body);
+                    if created_group_id.is_none() { failure = Some("Response missing 'id'".to_string()); }
+                }
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST",
+                    "/Groups", Some(create_body.clone()),
+                    Some(resp.status as i32), Some(resp.body),
+                    resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+            }
+            Err(e) => {
+                results.push(Self::error_result(test_run_id, test_name, category, requirement, "POST",
+                    "/Groups", Some(create_body), e));
+            }
+        }
+        *completed += 1;
+        let create_group = results.len() - 1;
+
+        // Test 2: PATCH group displayName via replace
+        let updated_group_name = format!("{}_patched", group_name);
+        let test_name = "PATCH /Groups/{id} - Replace displayName";
+        let requirement = Requirement::must("RFC 7644 §3.5.2.3", Severity::High);
+        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+        let prerequisites = [create_group];
+        let skip_reason = patch_skip.clone()
+            .or_else(|| Self::unmet_prerequisite(&results, &prerequisites));
+        if let Some(reason) = skip_reason {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH",
+                "/Groups/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else if let Some(ref group_id) = created_group_id {
+            let path = format!("/Groups/{}", group_id);
+            let patch_body = scim::to_body(&PatchOp::new(vec![
+                PatchOperation::replace(Some("displayName"), Value::String(updated_group_name.clone())),
+            ]));
+            match client.patch(&path, &patch_body).await {
+                Ok(resp) => {
                     let passed = resp.status == 200 || resp.status == 204;
                     let failure = if !passed { Some(format!("Expected 200/204, got {}", resp.status)) } else { None };
-                    results.push(Self::make_result(test_run_id, test_name, category, "PATCH",
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH",
                         &path, Some(patch_body), Some(resp.status as i32), Some(resp.body),
-                        resp.duration_ms, passed, failure));
+                        resp.duration_ms, Some(resp.timing), passed, failure));
                 }
                 Err(e) => {
-                    results.push(Self::make_result(test_run_id, test_name, category, "PATCH",
-                        &path, Some(patch_body), None, None, 0, false, Some(e)));
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "PATCH",
+                        &path, Some(patch_body), e));
                 }
             }
         } else {
-            results.push(Self::make_result(test_run_id, test_name, category, "PATCH",
-                "/Groups/{id}", None, None, None, 0, false,
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH",
+                "/Groups/{id}", None, None, None, 0, None, TestOutcome::Skipped,
                 Some("Skipped: group creation failed".to_string())));
         }
+        Self::record_prerequisites(&mut results, &prerequisites);
         *completed += 1;
+        let rename_group = results.len() - 1;
 
         // Test 3: Verify PATCH via filter on the updated name
         let test_name = "GET /Groups?filter - Verify PATCH updated displayName";
+        let requirement = Requirement::must("RFC 7644 §3.4.2.2", Severity::Medium);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        if created_group_id.is_some() {
-            let filter_path = format!("/Groups?filter={} eq \"{}\"", joining_property, updated_group_name);
+        let prerequisites = [rename_group];
+        let skip_reason = Self::unsupported(capabilities, "filter")
+            .or_else(|| Self::unmet_prerequisite(&results, &prerequisites));
+        if let Some(reason) = skip_reason {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Groups?filter=...", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else if created_group_id.is_some() {
+            let filter_path = format!("/Groups?filter={}", Filter::eq(joining_property, &updated_group_name).encoded());
             match client.get(&filter_path).await {
                 Ok(resp) => {
                     let mut passed = resp.status == 200;
                     let mut failure = None;
                     if !passed {
                         failure = Some(format!("Expected 200, got {}", resp.status));
-                    } else if let Ok(json) = serde_json::from_str::<Value>(&resp.body) {
-                        let total_results = json.get("totalResults").and_then(|v| v.as_u64()).unwrap_or(0);
-                        if total_results == 0 {
+                    } else if let Ok(list) = serde_json::from_str::<ListResponse<Group>>(&resp.body) {
+                        if list.total_results.unwrap_or(0) == 0 {
                             passed = false;
                             failure = Some("PATCH'd group not found via filter on updated displayName".to_string());
-                        } else {
-                            let resources = Self::get_resources(&json).and_then(|v| v.as_array());
-                            if let Some(arr) = resources {
-                                if let Some(group) = arr.first() {
-                                    let dn = group.get("displayName").and_then(|v| v.as_str());
-                                    if dn != Some(&updated_group_name) {
-                                        passed = false;
-                                        failure = Some(format!(
-                                            "Returned displayName '{}' does not match PATCH'd value '{}'",
-                                            dn.unwrap_or("null"), updated_group_name
-                                        ));
-                                    }
-                                }
+                        } else if let Some(group) = list.resources().first() {
+                            if group.display_name != updated_group_name {
+                                passed = false;
+                                failure = Some(format!(
+                                    "Returned displayName '{}' does not match PATCH'd value '{}'",
+                                    group.display_name, updated_group_name
+                                ));
                             }
                         }
                     }
-                    results.push(Self::make_result(test_run_id, test_name, category, "GET",
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
                         &filter_path, None, Some(resp.status as i32), Some(resp.body),
-                        resp.duration_ms, passed, failure));
+                        resp.duration_ms, Some(resp.timing), passed, failure));
                 }
                 Err(e) => {
-                    results.push(Self::make_result(test_run_id, test_name, category, "GET",
-                        "/Groups?filter=...", None, None, None, 0, false, Some(e)));
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "GET",
+                        "/Groups?filter=...", None, e));
                 }
             }
         } else {
-            results.push(Self::make_result(test_run_id, test_name, category, "GET",
-                "/Groups?filter=...", None, None, None, 0, false,
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Groups?filter=...", None, None, None, 0, None, TestOutcome::Skipped,
                 Some("Skipped: group creation failed".to_string())));
         }
+        Self::record_prerequisites(&mut results, &prerequisites);
         *completed += 1;
 
         // Create a user to add as group member
-        let member_user_name = format!("scim_member_{}@test.example.com", Uuid::new_v4().to_string().split('-').next().unwrap());
+        let member_user_name = format!("scim_member_{}@test.example.com", Self::unique_suffix());
         let mut member_user_id: Option<String> = None;
-        let member_body = serde_json::json!({
-            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
-            "userName": member_user_name,
-            "name": { "givenName": "Member", "familyName": "TestUser" },
-            "displayName": "Member Test User",
-            "active": true
-        }).to_string();
+        let member_body = scim::to_body(&User::new(&member_user_name)
+            .with_name("Member", "TestUser")
+            .with_display_name("Member Test User")
+            .with_active(true));
 
         // Test 4: Create user to be added as member
         let test_name = "POST /Users - Create user for group membership";
+        let requirement = Requirement::must("RFC 7644 §3.3", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
         match client.post("/Users", &member_body).await {
             Ok(resp) => {
                 let passed = resp.status == 201;
                 let mut failure = if !passed { Some(format!("Expected 201, got {}", resp.status)) } else { None };
                 if passed {
-                    if let Ok(json) = serde_json::from_str::<Value>(&resp.body) {
-                        member_user_id = json.get("id").and_then(|v| v.as_str()).map(|s| s.to_string());
-                        if member_user_id.is_none() { failure = Some("Response missing 'id'".to_string()); }
-                    }
+                    member_user_id = scim::resource_id(&resp.body);
+                    if member_user_id.is_none() { failure = Some("Response missing 'id'".to_string()); }
                 }
-                results.push(Self::make_result(test_run_id, test_name, category, "POST",
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "POST",
                     "/Users", Some(member_body.clone()),
                     Some(resp.status as i32), Some(resp.body),
-                    resp.duration_ms, failure.is_none(), failure));
+                    resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
             }
             Err(e) => {
-                results.push(Self::make_result(test_run_id, test_name, category, "POST",
-                    "/Users", Some(member_body), None, None, 0, false, Some(e)));
+                results.push(Self::error_result(test_run_id, test_name, category, requirement, "POST",
+                    "/Users", Some(member_body), e));
             }
         }
         *completed += 1;
+        let create_member = results.len() - 1;
 
         // Test 5: PATCH group to add member
         let test_name = "PATCH /Groups/{id} - Add member to group";
+        let requirement = Requirement::must("RFC 7644 §3.5.2.1", Severity::Critical);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        if let (Some(ref group_id), Some(ref user_id)) = (&created_group_id, &member_user_id) {
+        let prerequisites = [create_group, create_member];
+        let skip_reason = patch_skip.clone()
+            .or_else(|| Self::unmet_prerequisite(&results, &prerequisites));
+        if let Some(reason) = skip_reason {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH",
+                "/Groups/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else if let (Some(ref group_id), Some(ref user_id)) = (&created_group_id, &member_user_id) {
             let path = format!("/Groups/{}", group_id);
-            let patch_body = serde_json::json!({
-                "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
-                "Operations": [{
-                    "op": "add",
-                    "path": "members",
-                    "value": [{ "value": user_id }]
-                }]
-            }).to_string();
+            let patch_body = scim::to_body(&PatchOp::new(vec![
+                PatchOperation::add(Some("members"), serde_json::json!([Member::new(user_id)])),
+            ]));
             match client.patch(&path, &patch_body).await {
                 Ok(resp) => {
                     let passed = resp.status == 200 || resp.status == 204;
                     let failure = if !passed {
                         Some(format!("Expected 200/204, got {}", resp.status))
                     } else { None };
-                    results.push(Self::make_result(test_run_id, test_name, category, "PATCH",
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH",
                         &path, Some(patch_body), Some(resp.status as i32), Some(resp.body),
-                        resp.duration_ms, passed, failure));
+                        resp.duration_ms, Some(resp.timing), passed, failure));
                 }
                 Err(e) => {
-                    results.push(Self::make_result(test_run_id, test_name, category, "PATCH",
-                        &path, Some(patch_body), None, None, 0, false, Some(e)));
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "PATCH",
+                        &path, Some(patch_body), e));
                 }
             }
         } else {
@@ -1880,15 +3724,22 @@ impl ValidationEngine {
             } else {
                 "Skipped: member user creation failed"
             };
-            results.push(Self::make_result(test_run_id, test_name, category, "PATCH",
-                "/Groups/{id}", None, None, None, 0, false, Some(skip_reason.to_string())));
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH",
+                "/Groups/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(skip_reason.to_string())));
         }
+        Self::record_prerequisites(&mut results, &prerequisites);
         *completed += 1;
+        let add_member = results.len() - 1;
 
         // Test 6: Verify member was added via GET
         let test_name = "GET /Groups/{id} - Verify member was added";
+        let requirement = Requirement::must("RFC 7644 §3.4.1", Severity::High);
         Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
-        if let (Some(ref group_id), Some(ref user_id)) = (&created_group_id, &member_user_id) {
+        let prerequisites = [add_member];
+        if let Some(reason) = Self::unmet_prerequisite(&results, &prerequisites) {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Groups/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else if let (Some(ref group_id), Some(ref user_id)) = (&created_group_id, &member_user_id) {
             let path = format!("/Groups/{}", group_id);
             match client.get(&path).await {
                 Ok(resp) => {
@@ -1896,77 +3747,382 @@ impl ValidationEngine {
                     let mut failure = None;
                     if !passed {
                         failure = Some(format!("Expected 200, got {}", resp.status));
-                    } else if let Ok(json) = serde_json::from_str::<Value>(&resp.body) {
-                        let members = json.get("members").and_then(|v| v.as_array());
-                        match members {
-                            Some(arr) => {
-                                let has_member = arr.iter().any(|m| {
-                                    m.get("value").and_then(|v| v.as_str()) == Some(user_id)
-                                });
-                                if !has_member {
-                                    passed = false;
-                                    failure = Some(format!(
-                                        "Group members array does not contain user '{}' after PATCH add",
-                                        user_id
-                                    ));
-                                }
-                            }
-                            None => {
-                                passed = false;
-                                failure = Some("Group response does not contain 'members' array".to_string());
-                            }
+                    } else if let Ok(group) = serde_json::from_str::<Group>(&resp.body) {
+                        if group.members.is_empty() {
+                            passed = false;
+                            failure = Some("Group response does not contain 'members' array".to_string());
+                        } else if !group.has_member(user_id) {
+                            passed = false;
+                            failure = Some(format!(
+                                "Group members array does not contain user '{}' after PATCH add",
+                                user_id
+                            ));
                         }
                     }
-                    results.push(Self::make_result(test_run_id, test_name, category, "GET",
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
                         &path, None, Some(resp.status as i32), Some(resp.body),
-                        resp.duration_ms, passed, failure));
+                        resp.duration_ms, Some(resp.timing), passed, failure));
                 }
                 Err(e) => {
-                    results.push(Self::make_result(test_run_id, test_name, category, "GET",
-                        &path, None, None, None, 0, false, Some(e)));
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "GET",
+                        &path, None, e));
                 }
             }
         } else {
-            results.push(Self::make_result(test_run_id, test_name, category, "GET",
-                "/Groups/{id}", None, None, None, 0, false,
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Groups/{id}", None, None, None, 0, None, TestOutcome::Skipped,
                 Some("Skipped: group or member creation failed".to_string())));
         }
+        Self::record_prerequisites(&mut results, &prerequisites);
+        *completed += 1;
+
+        // Tests 7-10: membership changes on a group with several members, each
+        // checked by reading the group back and comparing the member set
+        let mut extra_member_ids: Vec<String> = Vec::new();
+        if patch_skip.is_none() && created_group_id.is_some() && member_user_id.is_some() {
+            for i in 1..=GROUP_EXTRA_MEMBERS {
+                let user_name = member_user_name.replace('@', &format!("_{}@", i));
+                let body = scim::to_body(&User::new(&user_name).with_display_name(&format!("Member Test User {}", i)).with_active(true));
+                if let Ok(resp) = client.post("/Users", &body).await {
+                    if resp.status == 201 {
+                        extra_member_ids.extend(scim::resource_id(&resp.body));
+                    }
+                }
+            }
+        }
+        let member_ids: Vec<String> = member_user_id.iter().chain(&extra_member_ids).cloned().collect();
+        let id = |i: usize| member_ids.get(i).cloned().unwrap_or_default();
+        let members_of = |indices: &[usize]| serde_json::json!(indices.iter().map(|&i| Member::new(&id(i))).collect::<Vec<_>>());
+        let membership_skip = if created_group_id.is_none() {
+            Some("Skipped: group creation failed")
+        } else if member_ids.len() < GROUP_EXTRA_MEMBERS + 1 {
+            Some("Skipped: member user creation failed")
+        } else {
+            None
+        };
+        let everyone: Vec<usize> = (0..=GROUP_EXTRA_MEMBERS).collect();
+        // Each case adds one result, so the first one's lands here
+        let add_several = results.len();
+        let membership_cases = [
+            (
+                "PATCH /Groups/{id} - Add several members in one request",
+                Requirement::must("RFC 7644 §3.5.2.1", Severity::High),
+                vec![PatchOperation::add(Some("members"), members_of(&everyone[1..]))],
+                // Member 0 is already in the group from the single add
+                everyone.clone(),
+                vec![create_group, create_member, add_member],
+            ),
+            (
+                "PATCH /Groups/{id} - Remove a member by filtered path (members[value eq ...])",
+                Requirement::must("RFC 7644 §3.5.2.2", Severity::Critical),
+                vec![PatchOperation::remove(&format!("members[value eq \"{}\"]", id(1)))],
+                everyone.iter().copied().filter(|&i| i != 1).collect(),
+                vec![add_several],
+            ),
+            (
+                "PATCH /Groups/{id} - Replace the members list",
+                Requirement::must("RFC 7644 §3.5.2.3", Severity::High),
+                vec![PatchOperation::replace(Some("members"), members_of(&[2, 3]))],
+                vec![2, 3],
+                vec![create_group],
+            ),
+            (
+                "PATCH /Groups/{id} - Remove all members",
+                Requirement::must("RFC 7644 §3.5.2.2", Severity::High),
+                vec![PatchOperation::remove("members")],
+                vec![],
+                vec![create_group],
+            ),
+        ];
+        for (test_name, requirement, operations, expected, prerequisites) in membership_cases {
+            Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+            let skip_reason = patch_skip.clone()
+                .or_else(|| Self::unmet_prerequisite(&results, &prerequisites))
+                .or_else(|| membership_skip.map(str::to_string));
+            let (Some(ref group_id), None) = (&created_group_id, &skip_reason) else {
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH",
+                    "/Groups/{id}", None, None, None, 0, None, TestOutcome::Skipped, skip_reason));
+                Self::record_prerequisites(&mut results, &prerequisites);
+                *completed += 1;
+                continue;
+            };
+            let path = format!("/Groups/{}", group_id);
+            let patch_body = scim::to_body(&PatchOp::new(operations));
+            match client.patch(&path, &patch_body).await {
+                Ok(resp) => {
+                    let failure = if resp.status != 200 && resp.status != 204 {
+                        Some(format!("Expected 200/204, got {}", resp.status))
+                    } else {
+                        let expected: Vec<String> = expected.iter().map(|&i| id(i)).collect();
+                        match client.get(&path).await.ok().and_then(|r| serde_json::from_str::<Group>(&r.body).ok()) {
+                            Some(group) => Self::membership_failure(&group, &expected),
+                            None => Some("PATCH succeeded but the group could not be read back".to_string()),
+                        }
+                    };
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "PATCH",
+                        &path, Some(patch_body), Some(resp.status as i32), Some(resp.body),
+                        resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "PATCH",
+                        &path, Some(patch_body), e));
+                }
+            }
+            Self::record_prerequisites(&mut results, &prerequisites);
+            *completed += 1;
+        }
+
+        // Test 11: deleting a user takes it out of its groups (RFC 7644 §3.6)
+        let test_name = "DELETE /Users/{id} - Deleted user is removed from its groups";
+        let requirement = Requirement::should("RFC 7644 §3.6", Severity::Medium);
+        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+        let prerequisites = [create_group, create_member];
+        let skip_reason = patch_skip.clone()
+            .or_else(|| Self::unmet_prerequisite(&results, &prerequisites));
+        if let Some(reason) = skip_reason {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Groups/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else if let (Some(ref group_id), None) = (&created_group_id, membership_skip) {
+            let path = format!("/Groups/{}", group_id);
+            let add_body = scim::to_body(&PatchOp::new(vec![PatchOperation::add(Some("members"), members_of(&[0, 4]))]));
+            let added = client.patch(&path, &add_body).await
+                .is_ok_and(|r| r.status == 200 || r.status == 204);
+            let deleted = client.delete(&format!("/Users/{}", id(0))).await
+                .is_ok_and(|r| r.status == 204 || r.status == 200);
+            if !added || !deleted {
+                let step = if !added { "adding the user to the group" } else { "deleting the user" };
+                results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                    &path, None, None, None, 0, None, TestOutcome::Skipped,
+                    Some(format!("Skipped: {} failed", step))));
+            } else {
+                member_user_id = None;
+                match client.get(&path).await {
+                    Ok(resp) => {
+                        let failure = if resp.status != 200 {
+                            Some(format!("Expected 200, got {}", resp.status))
+                        } else {
+                            match serde_json::from_str::<Group>(&resp.body) {
+                                Ok(group) if group.has_member(&id(0)) => {
+                                    Some(format!("Deleted user '{}' is still listed in the group's members", id(0)))
+                                }
+                                Ok(group) => Self::membership_failure(&group, &[id(4)]),
+                                Err(e) => Some(format!("Group response could not be parsed: {}", e)),
+                            }
+                        };
+                        results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                            &path, None, Some(resp.status as i32), Some(resp.body),
+                            resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+                    }
+                    Err(e) => {
+                        results.push(Self::error_result(test_run_id, test_name, category, requirement, "GET",
+                            &path, None, e));
+                    }
+                }
+            }
+        } else {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Groups/{id}", None, None, None, 0, None, TestOutcome::Skipped,
+                Some(membership_skip.unwrap_or_default().to_string())));
+        }
+        Self::record_prerequisites(&mut results, &prerequisites);
+        *completed += 1;
+
+        // Test 12: large groups are read without their members
+        let test_name = "GET /Groups/{id}?excludedAttributes=members";
+        let requirement = Requirement::must("RFC 7644 §3.4.2.5", Severity::Medium);
+        Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+        let prerequisites = [create_group];
+        if let Some(reason) = Self::unmet_prerequisite(&results, &prerequisites) {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Groups/{id}", None, None, None, 0, None, TestOutcome::Skipped, Some(reason)));
+        } else if let Some(ref group_id) = created_group_id {
+            let path = format!("/Groups/{}?excludedAttributes=members", group_id);
+            match client.get(&path).await {
+                Ok(resp) => {
+                    let group = serde_json::from_str::<Value>(&resp.body).ok();
+                    let has = |attr: &str| group.as_ref().and_then(Value::as_object)
+                        .is_some_and(|g| g.keys().any(|k| k.eq_ignore_ascii_case(attr)));
+                    let failure = if resp.status != 200 {
+                        Some(format!("Expected 200, got {}", resp.status))
+                    } else if has("members") {
+                        Some("Attribute 'members' should not be returned".to_string())
+                    } else if !has("displayName") {
+                        Some("Attribute 'displayName' is missing; only 'members' was excluded".to_string())
+                    } else {
+                        None
+                    };
+                    results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                        &path, None, Some(resp.status as i32), Some(resp.body),
+                        resp.duration_ms, Some(resp.timing), failure.is_none(), failure));
+                }
+                Err(e) => {
+                    results.push(Self::error_result(test_run_id, test_name, category, requirement, "GET",
+                        &path, None, e));
+                }
+            }
+        } else {
+            results.push(Self::make_result(test_run_id, test_name, category, requirement, "GET",
+                "/Groups/{id}", None, None, None, 0, None, TestOutcome::Skipped,
+                Some("Skipped: group creation failed".to_string())));
+        }
+        Self::record_prerequisites(&mut results, &prerequisites);
         *completed += 1;
 
         // Cleanup
         if let Some(ref gid) = created_group_id {
             let _ = client.delete(&format!("/Groups/{}", gid)).await;
         }
-        if let Some(ref uid) = member_user_id {
+        for uid in member_user_id.iter().chain(&extra_member_ids) {
             let _ = client.delete(&format!("/Users/{}", uid)).await;
         }
 
         results
     }
 
+    fn membership_failure(group: &Group, expected: &[String]) -> Option<String> {
+        let mut members: Vec<&str> = group.members.iter().filter_map(|m| m.value.as_deref()).collect();
+        let mut expected: Vec<&str> = expected.iter().map(String::as_str).collect();
+        members.sort_unstable();
+        expected.sort_unstable();
+        if members == expected {
+            return None;
+        }
+        Some(format!("Group members are {:?}, expected exactly {:?}", members, expected))
+    }
+
     pub fn compute_summary(results: &[ValidationResult]) -> ValidationSummary {
+        let count = |outcomes: &[TestOutcome]| results.iter().filter(|r| outcomes.contains(&r.outcome)).count();
         let total = results.len();
-        let passed = results.iter().filter(|r| r.passed).count();
-        let failed = results.iter().filter(|r| !r.passed && !r.failure_reason.as_ref().is_some_and(|r| r.starts_with("Skipped"))).count();
-        let skipped = results.iter().filter(|r| r.failure_reason.as_ref().is_some_and(|r| r.starts_with("Skipped"))).count();
+        let passed = count(&[TestOutcome::Passed, TestOutcome::Warning]);
+        let failed = count(&[TestOutcome::Failed, TestOutcome::Error]);
+        let skipped = count(&[TestOutcome::Skipped]);
+        let warnings = count(&[TestOutcome::Warning]);
+        let errors = count(&[TestOutcome::Error]);
+        // Skipped tests say nothing about the server, so they are out of the score
         let compliance_score = if total - skipped > 0 {
             (passed as f64 / (total - skipped) as f64) * 100.0
         } else {
             0.0
         };
+        // The same, with a critical test counting eight times a low one and a
+        // warning earning half the weight of a clean pass
+        let counted = results.iter().filter(|r| r.outcome != TestOutcome::Skipped);
+        let possible: f64 = counted.clone().map(|r| r.severity.weight()).sum();
+        let earned: f64 = counted.map(|r| r.severity.weight() * r.outcome.credit()).sum();
+        let weighted_score = if possible > 0.0 {
+            earned / possible * 100.0
+        } else {
+            0.0
+        };
         let duration_ms: i64 = results.iter().map(|r| r.duration_ms).sum();
 
-        let mut category_map: std::collections::HashMap<String, (usize, usize, usize)> = std::collections::HashMap::new();
+        let mut category_map: std::collections::HashMap<String, CategorySummary> = std::collections::HashMap::new();
         for r in results {
-            let entry = category_map.entry(r.category.clone()).or_insert((0, 0, 0));
-            entry.0 += 1;
-            if r.passed { entry.1 += 1; } else { entry.2 += 1; }
+            let entry = category_map.entry(r.category.clone()).or_insert_with(|| CategorySummary {
+                name: r.category.clone(), total: 0, passed: 0, failed: 0, skipped: 0,
+            });
+            entry.total += 1;
+            match r.outcome {
+                TestOutcome::Passed | TestOutcome::Warning => entry.passed += 1,
+                TestOutcome::Failed | TestOutcome::Error => entry.failed += 1,
+                TestOutcome::Skipped => entry.skipped += 1,
+            }
         }
-        let categories = category_map.into_iter().map(|(name, (t, p, f))| CategorySummary {
-            name, total: t, passed: p, failed: f,
-        }).collect();
+        let categories = category_map.into_values().collect();
 
-        ValidationSummary { total, passed, failed, skipped, compliance_score, duration_ms, categories }
+        ValidationSummary { total, passed, failed, skipped, warnings, errors, compliance_score, weighted_score, duration_ms, categories }
+    }
+
+    // ── TLS Certificate Tests ──
+
+    /// Report certificate problems as findings. These run even when the server
+    /// config is set to accept invalid certificates, so an insecure setup cannot
+    /// hide a broken chain behind an otherwise compliant report.
+    async fn test_tls_certificate(
+        app: &AppHandle,
+        client: &ScimClient,
+        test_run_id: &str,
+        completed: &mut usize,
+        total: usize,
+    ) -> Vec<ValidationResult> {
+        let mut results = Vec::new();
+        let category = "tls_certificate";
+        let test_names = [
+            ("Certificate chain is trusted", Requirement::must("RFC 7644 §7.1", Severity::Critical)),
+            ("Certificate is within its validity period", Requirement::must("RFC 7644 §7.1", Severity::Critical)),
+            ("Certificate matches hostname", Requirement::must("RFC 7644 §7.1", Severity::Critical)),
+            ("Certificate is not self-signed", Requirement::should("RFC 7644 §7.1", Severity::High)),
+        ];
+
+        Self::emit_progress(app, test_run_id, test_names[0].0, category, *completed, total);
+        let start = std::time::Instant::now();
+        let inspection = client.inspect_certificate().await;
+        let duration_ms = start.elapsed().as_millis() as i64;
+
+        let report = match inspection {
+            Ok(Some(report)) => report,
+            other => {
+                for (test_name, requirement) in test_names {
+                    results.push(match &other {
+                        Ok(_) => Self::make_result(
+                            test_run_id, test_name, category, requirement, "GET", "/", None, None, None,
+                            0, None, TestOutcome::Skipped, Some("Skipped: base URL does not use HTTPS".to_string()),
+                        ),
+                        Err(e) => Self::error_result(test_run_id, test_name, category, requirement, "GET", "/", None, e),
+                    });
+                    *completed += 1;
+                }
+                return results;
+            }
+        };
+        let details = serde_json::to_string_pretty(&report.to_json()).ok();
+
+        let chain_failure = report.verification_error.as_ref()
+            .map(|e| format!("Certificate chain failed verification: {}", e));
+
+        let validity_failure = if report.is_expired() {
+            Some(format!("Certificate expired on {}", report.not_after.to_rfc3339()))
+        } else if report.is_not_yet_valid() {
+            Some(format!("Certificate is not valid until {}", report.not_before.to_rfc3339()))
+        } else {
+            None
+        };
+
+        let hostname_failure = if report.matches_host() {
+            None
+        } else if report.dns_names.is_empty() && report.ip_addresses.is_empty() {
+            Some(format!("Certificate has no subjectAltName entries; '{}' cannot be matched", report.host))
+        } else {
+            Some(format!(
+                "Host '{}' does not match subjectAltName entries: {}",
+                report.host,
+                report.dns_names.iter().cloned()
+                    .chain(report.ip_addresses.iter().map(|ip| ip.to_string()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+        };
+
+        let self_signed_failure = if report.self_signed {
+            Some(format!("Certificate is self-signed (issuer and subject are both '{}')", report.subject))
+        } else {
+            None
+        };
+
+        let failures = [chain_failure, validity_failure, hostname_failure, self_signed_failure];
+        for (i, ((test_name, requirement), failure)) in test_names.into_iter().zip(failures).enumerate() {
+            Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
+            results.push(Self::make_result(
+                test_run_id, test_name, category, requirement, "GET", "/", None,
+                None, details.clone(),
+                // The handshakes are shared by all four checks; charge them once
+                if i == 0 { duration_ms } else { 0 }, None,
+                failure.is_none(), failure,
+            ));
+            *completed += 1;
+        }
+
+        results
     }
 
     // ── Field Mapping Validation ──
@@ -1984,10 +4140,11 @@ impl ValidationEngine {
 
         if rules.is_empty() {
             let test_name = "No field mapping rules defined";
+            let requirement = Requirement::unreferenced(Severity::Medium);
             Self::emit_progress(app, test_run_id, test_name, category, *completed, total);
             results.push(Self::make_result(
-                test_run_id, test_name, category, "N/A", "", None, None, None, 0, true,
-                Some("Skipped — no field mapping rules configured".to_string()),
+                test_run_id, test_name, category, requirement, "N/A", "", None, None, None, 0, None, TestOutcome::Skipped,
+                Some("Skipped: no field mapping rules configured".to_string()),
             ));
             *completed += 1;
             return results;
@@ -1995,48 +4152,45 @@ impl ValidationEngine {
 
         // Create a temporary test user so we always have one to validate
         // field mapping rules against, even on an empty database.
-        let uid = Uuid::new_v4().to_string().split('-').next().unwrap().to_string();
+        let uid = Self::unique_suffix();
         let fm_user_name = format!("scim_fieldmap_test_{}@test.example.com", uid);
-        let create_body = serde_json::json!({
-            "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User"],
-            "userName": fm_user_name,
-            "name": { "givenName": "FieldMap", "familyName": "TestUser" },
-            "emails": [{ "value": fm_user_name, "type": "work", "primary": true }],
-            "displayName": "FieldMap Test User",
-            "active": true
-        }).to_string();
+        let create_body = scim::to_body(&User::new(&fm_user_name)
+            .with_name("FieldMap", "TestUser")
+            .with_work_email(&fm_user_name)
+            .with_display_name("FieldMap Test User")
+            .with_active(true));
 
         let (user_json, created_user_id) = match client.post("/Users", &create_body).await {
             Ok(resp) if resp.status == 201 => {
+                // Rules address arbitrary attribute paths, so keep the raw JSON
                 match serde_json::from_str::<Value>(&resp.body) {
-                    Ok(json) => {
-                        let id = json.get("id").and_then(|v| v.as_str()).map(|s| s.to_string());
-                        (Some(json), id)
-                    }
+                    Ok(json) => (Some(json), scim::resource_id(&resp.body)),
                     Err(_) => (None, None),
                 }
             }
             _ => {
-                // If we can't create, try fetching an existing user as fallback
-                let mut found: Option<Value> = None;
-                for endpoint in &["/Users?count=1", "/Users"] {
-                    if let Ok(resp) = client.get(endpoint).await {
-                        if resp.status == 200 {
-                            if let Ok(json) = serde_json::from_str::<Value>(&resp.body) {
-                                found = Self::get_resources(&json)
-                                    .and_then(|r| r.as_array())
-                                    .and_then(|arr| arr.first().cloned());
-                                if found.is_some() { break; }
-                            }
+                // If we can't create, fall back to the existing user that fills
+                // in the most of the mapped attributes
+                let mut found: Option<(usize, Value)> = None;
+                let mut pager = client.paginate::<Value>("/Users").max_pages(FIELD_MAPPING_SCAN_PAGES);
+                while let Some(Ok(page)) = pager.next_page().await {
+                    for user in page.resources {
+                        let filled = rules.iter()
+                            .filter(|r| Self::resolve_path(&user, &r.scim_attribute).is_some_and(|v| !v.is_null()))
+                            .count();
+                        if found.as_ref().is_none_or(|(best, _)| filled > *best) {
+                            found = Some((filled, user));
                         }
                     }
+                    if found.as_ref().is_some_and(|(filled, _)| *filled == rules.len()) { break; }
                 }
-                (found, None)
+                (found.map(|(_, user)| user), None)
             }
         };
 
         for rule in rules {
             let test_name = format!("Field: {} ({})", rule.display_name, rule.scim_attribute);
+            let requirement = Requirement::unreferenced(Severity::Medium);
             Self::emit_progress(app, test_run_id, &test_name, category, *completed, total);
 
             let start = std::time::Instant::now();
@@ -2049,10 +4203,10 @@ impl ValidationEngine {
 
             let duration_ms = start.elapsed().as_millis() as i64;
             results.push(Self::make_result(
-                test_run_id, &test_name, category, "GET", "/Users",
+                test_run_id, &test_name, category, requirement, "GET", "/Users",
                 None, None,
                 user_json.as_ref().map(|u| serde_json::to_string_pretty(u).unwrap_or_default()),
-                duration_ms, passed, failure,
+                duration_ms, None, passed, failure,
             ));
             *completed += 1;
         }
@@ -2,16 +2,48 @@ export interface ServerConfig {
   id: string;
   name: string;
   base_url: string;
-  auth_type: 'bearer' | 'basic' | 'apikey';
+  auth_type: 'bearer' | 'basic' | 'apikey' | 'oauth2_client_credentials';
   auth_token?: string;
   auth_username?: string;
   auth_password?: string;
   api_key_header?: string;
   api_key_value?: string;
+  oauth_token_url?: string;
+  oauth_client_id?: string;
+  oauth_client_secret?: string;
+  oauth_scope?: string;
+  oauth_audience?: string;
+  client_cert_format?: 'pem' | 'pkcs12';
+  client_cert?: string;
+  client_key?: string;
+  client_cert_password?: string;
+  tls_mode?: 'strict' | 'custom_ca' | 'insecure';
+  ca_bundle?: string;
+  rate_limit_rps?: number;    // client-side request quota; empty or 0 = unlimited
+  rate_limit_burst?: number;
+  connect_timeout_secs?: number;  // empty = 10s
+  read_timeout_secs?: number;     // max wait between reads of a response; empty = 30s
+  request_timeout_secs?: number;  // max time for a whole request; empty = 120s
+  proxy_url?: string;
+  proxy_username?: string;
+  proxy_password?: string;
+  custom_headers?: HttpHeader[];  // sent with every request
   created_at: string;
   updated_at: string;
 }
 
+export interface HttpHeader {
+  name: string;
+  value: string;
+}
+
+// Secret fields are encrypted in the local database with a key file, or with a
+// key derived from a passphrase that has to be entered once per session
+export interface CredentialProtection {
+  mode: 'key_file' | 'passphrase';
+  locked: boolean;
+}
+
 export interface TestRun {
   id: string;
   server_config_id: string;
@@ -20,6 +52,14 @@ export interface TestRun {
   started_at: string;
   completed_at?: string;
   summary_json?: string;
+  capabilities_json?: string; // /ServiceProviderConfig as parsed when a validation run started
+}
+
+/** A ServiceProviderConfig feature as shown in reports; `supported` is null when undeclared. */
+export interface ServerCapability {
+  name: string;
+  supported: boolean | null;
+  detail?: string;
 }
 
 export interface ValidationResult {
@@ -33,17 +73,39 @@ export interface ValidationResult {
   response_status?: number;
   response_body?: string;
   duration_ms: number;
-  passed: boolean;
+  timing?: RequestTiming;  // absent when no response was received
+  passed: boolean;  // outcome is 'passed' or 'warning'
+  outcome: TestOutcome;
   failure_reason?: string;
   executed_at: string;
+  schema_violations: SchemaViolation[];  // returned resources that contradict the server's /Schemas
+  depends_on?: string[];  // tests in the same category that must pass for this one to count
+  requirement_level: RequirementLevel;
+  rfc_reference?: string;  // e.g. 'RFC 7644 §3.5.2'; absent for user-defined checks
+  severity: Severity;
+}
+
+export type RequirementLevel = 'MUST' | 'SHOULD' | 'MAY';
+export type Severity = 'critical' | 'high' | 'medium' | 'low';
+
+// 'warning': passed, with schema violations. 'error': no response was received.
+export type TestOutcome = 'passed' | 'failed' | 'skipped' | 'warning' | 'error';
+
+export interface SchemaViolation {
+  attribute: string;
+  rule: string;  // 'type' | 'required' | 'multiValued' | 'canonicalValues' | 'mutability' | 'returned' | 'caseExact'
+  message: string;
 }
 
 export interface ValidationSummary {
   total: number;
-  passed: number;
-  failed: number;
+  passed: number;  // includes warnings
+  failed: number;  // includes errors
   skipped: number;
-  compliance_score: number;
+  warnings?: number;
+  errors?: number;
+  compliance_score: number;  // skipped tests excluded
+  weighted_score?: number;  // compliance_score with each test weighted by its severity
   duration_ms: number;
   categories: CategorySummary[];
 }
@@ -53,9 +115,10 @@ export interface CategorySummary {
   total: number;
   passed: number;
   failed: number;
+  skipped?: number;
 }
 
-export type LoadTestScenario = 'create_users' | 'create_update' | 'full_lifecycle' | 'list_users' | 'create_groups' | 'group_lifecycle' | 'add_remove_members' | 'update_groups';
+export type LoadTestScenario = 'create_users' | 'create_update' | 'full_lifecycle' | 'list_users' | 'create_groups' | 'group_lifecycle' | 'add_remove_members' | 'update_groups' | 'bulk_import';
 
 export interface LoadTestConfig {
   server_config_id: string;
@@ -65,6 +128,17 @@ export interface LoadTestConfig {
   total_requests: number;
   concurrency: number;
   ramp_up_seconds?: number;
+  retry_policy?: RetryPolicy;
+  target_rps?: number;  // overrides the server's rate limit for this run
+  capture_traffic?: boolean;
+}
+
+export interface RetryPolicy {
+  max_attempts: number;        // total tries including the first
+  initial_backoff_ms: number;  // doubled after every attempt
+  max_backoff_ms: number;      // also caps Retry-After
+  jitter: boolean;
+  respect_retry_after: boolean;
 }
 
 export interface LoadTestEndpoint {
@@ -86,6 +160,8 @@ export interface LoadTestResult {
   success: boolean;
   error_message?: string;
   timestamp: string;
+  attempt: number;  // retries share the request_index of the first attempt
+  timing?: RequestTiming;
 }
 
 export interface LoadTestSummary {
@@ -104,6 +180,39 @@ export interface LoadTestSummary {
   p99_latency_ms: number;
   requests_per_second: number;
   status_code_distribution: Record<number, number>;
+  retried_attempts: number;
+  throttled_responses: number;
+  timing_breakdown?: TimingBreakdown;
+}
+
+// Microseconds; DNS, connect and TLS are 0 on a reused connection
+export interface RequestTiming {
+  dns_us: number;
+  connect_us: number;
+  tls_us: number;
+  ttfb_us: number;
+  download_us: number;
+  total_us: number;
+  connection_reused: boolean;
+}
+
+// Connection phases only count requests that opened a new connection
+export interface TimingBreakdown {
+  new_connections: number;
+  dns: PhaseStats;
+  connect: PhaseStats;
+  tls: PhaseStats;
+  ttfb: PhaseStats;
+  download: PhaseStats;
+}
+
+export interface PhaseStats {
+  avg_us: number;
+  p50_us: number;
+  p90_us: number;
+  p95_us: number;
+  p99_us: number;
+  max_us: number;
 }
 
 export interface ValidationRunConfig {
@@ -112,6 +221,8 @@ export interface ValidationRunConfig {
   field_mapping_rules?: FieldMappingRule[];
   user_joining_property?: string;   // e.g. 'userName' (default)
   group_joining_property?: string;  // e.g. 'displayName' (default)
+  retry_policy?: RetryPolicy;
+  capture_traffic?: boolean;  // record every HTTP exchange for HAR export
 }
 
 export interface ValidationProgress {
@@ -137,12 +248,25 @@ export interface TestConnectionResult {
   status_code?: number;
   response_body?: string;
   error?: string;
+  error_code?: AppErrorCode;
   duration_ms: number;
 }
 
+// Commands reject with this shape; SCIM error responses add status, scim_type and detail
+export type AppErrorCode = 'transport' | 'timeout' | 'tls' | 'auth' | 'scim' | 'database' | 'invalid_filter'
+  | 'credentials_locked' | 'encryption' | 'config' | 'not_found' | 'export' | 'other';
+
+export interface AppError {
+  code: AppErrorCode;
+  message: string;
+  status?: number;
+  scim_type?: string;
+  detail?: string;
+}
+
 export interface ExportRequest {
   test_run_id: string;
-  format: 'pdf' | 'csv' | 'json' | 'excel';
+  format: 'pdf' | 'csv' | 'json' | 'excel' | 'har';
   output_path: string;
 }
 
@@ -193,6 +317,7 @@ export interface ExplorerResponse {
   headers: Record<string, string>;
   body: string;
   duration_ms: number;
+  timing: RequestTiming;
   request_url: string;
 }
 
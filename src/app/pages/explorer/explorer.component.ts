@@ -26,6 +26,7 @@ import {
   ExplorerResponse,
   ExplorerHistoryEntry,
   FieldMappingRule,
+  RequestTiming,
 } from '../../models/interfaces';
 
 // ── Operation Definitions ──
@@ -64,6 +65,43 @@ const SCIM_OPERATIONS: ScimOperation[] = [
     needsId: false,
     aiGeneratable: false,
   },
+  {
+    id: 'search_users',
+    name: 'Search Users',
+    method: 'POST',
+    pathTemplate: '/Users/.search',
+    bodyTemplate: JSON.stringify({
+      schemas: ['urn:ietf:params:scim:api:messages:2.0:SearchRequest'],
+      filter: 'userName sw "john"',
+      attributes: ['userName', 'displayName', 'emails'],
+      sortBy: 'userName',
+      sortOrder: 'ascending',
+      startIndex: 1,
+      count: 10,
+    }, null, 2),
+    description: 'Query users with a POST body instead of URL parameters',
+    icon: 'manage_search',
+    category: 'user',
+    needsId: false,
+    aiGeneratable: false,
+  },
+  {
+    id: 'search_root',
+    name: 'Search All Resources',
+    method: 'POST',
+    pathTemplate: '/.search',
+    bodyTemplate: JSON.stringify({
+      schemas: ['urn:ietf:params:scim:api:messages:2.0:SearchRequest'],
+      filter: 'meta.lastModified gt "2024-01-01T00:00:00Z"',
+      startIndex: 1,
+      count: 10,
+    }, null, 2),
+    description: 'Query across all resource types from the server root',
+    icon: 'travel_explore',
+    category: 'user',
+    needsId: false,
+    aiGeneratable: false,
+  },
   {
     id: 'get_user',
     name: 'Get User',
@@ -210,6 +248,24 @@ const SCIM_OPERATIONS: ScimOperation[] = [
     needsId: false,
     aiGeneratable: false,
   },
+  {
+    id: 'search_groups',
+    name: 'Search Groups',
+    method: 'POST',
+    pathTemplate: '/Groups/.search',
+    bodyTemplate: JSON.stringify({
+      schemas: ['urn:ietf:params:scim:api:messages:2.0:SearchRequest'],
+      filter: 'displayName co "Engineering"',
+      excludedAttributes: ['members'],
+      startIndex: 1,
+      count: 10,
+    }, null, 2),
+    description: 'Query groups with a POST body instead of URL parameters',
+    icon: 'manage_search',
+    category: 'group',
+    needsId: false,
+    aiGeneratable: false,
+  },
   {
     id: 'get_group',
     name: 'Get Group',
@@ -350,6 +406,7 @@ export class ExplorerComponent implements OnInit, OnDestroy {
   requestPath = signal('');
   requestBody = signal('');
   queryParams = signal('');
+  filterError = signal<string | null>(null);
   resourceId = signal('');
 
   // Response
@@ -397,6 +454,7 @@ export class ExplorerComponent implements OnInit, OnDestroy {
     this.scimSchemaService.addFileMatch('user', 'scim://explorer/user.json');
     this.scimSchemaService.addFileMatch('group', 'scim://explorer/group.json');
     this.scimSchemaService.addFileMatch('patchop', 'scim://explorer/patchop.json');
+    this.scimSchemaService.addFileMatch('searchrequest', 'scim://explorer/searchrequest.json');
 
     // Re-apply Monaco model when selected operation changes (swaps schema)
     effect(() => {
@@ -406,7 +464,27 @@ export class ExplorerComponent implements OnInit, OnDestroy {
         setTimeout(() => this.applyEditorModel(), 0);
       }
     });
+
+    // Flag filter syntax errors while typing; the request can still be sent
+    effect(() => {
+      const filter = this.filterParam();
+      if (!filter) {
+        this.filterError.set(null);
+        return;
+      }
+      this.tauriService.validateScimFilter(filter).then(error => {
+        if (this.filterParam() === filter) this.filterError.set(error);
+      });
+    });
   }
+
+  /** The decoded `filter` value from the query parameters, if any. */
+  filterParam = computed(() => {
+    const pair = this.queryParams().split('&').find(p => p.startsWith('filter='));
+    if (!pair) return '';
+    const value = pair.slice('filter='.length);
+    try { return decodeURIComponent(value); } catch { return value; }
+  });
   filteredUsers = computed(() => {
     const picked = new Set(this.pickedUsers().map(u => u.id));
     const candidates = this.availableUsers().filter(u => !picked.has(u.id));
@@ -519,10 +597,11 @@ export class ExplorerComponent implements OnInit, OnDestroy {
   }
 
   /** Determine the schema type based on the current operation. */
-  private getEditorSchemaType(): 'user' | 'group' | 'patchop' {
+  private getEditorSchemaType(): 'user' | 'group' | 'patchop' | 'searchrequest' {
     const op = this.selectedOperation();
     if (!op) return 'user';
     if (op.method === 'PATCH') return 'patchop';
+    if (op.pathTemplate.endsWith('/.search')) return 'searchrequest';
     return op.category === 'group' ? 'group' : 'user';
   }
 
@@ -869,7 +948,7 @@ export class ExplorerComponent implements OnInit, OnDestroy {
       }
 
       // If list operation, refresh the available resources
-      if (this.selectedOperation()?.id === 'list_users' && resp.status >= 200 && resp.status < 300) {
+      if (['list_users', 'search_users'].includes(this.selectedOperation()?.id ?? '') && resp.status >= 200 && resp.status < 300) {
         try {
           const data = JSON.parse(resp.body);
           const resources = data.Resources || data.resources || [];
@@ -882,7 +961,7 @@ export class ExplorerComponent implements OnInit, OnDestroy {
           );
         } catch { /* ignore */ }
       }
-      if (this.selectedOperation()?.id === 'list_groups' && resp.status >= 200 && resp.status < 300) {
+      if (['list_groups', 'search_groups'].includes(this.selectedOperation()?.id ?? '') && resp.status >= 200 && resp.status < 300) {
         try {
           const data = JSON.parse(resp.body);
           const resources = data.Resources || data.resources || [];
@@ -1275,4 +1354,15 @@ export class ExplorerComponent implements OnInit, OnDestroy {
 
     return segments;
   }
+
+  timingTooltip(t?: RequestTiming): string {
+    if (!t) {
+      return '';
+    }
+    const ms = (us: number) => (us / 1000).toFixed(2) + 'ms';
+    const connection = t.connection_reused
+      ? 'Reused connection'
+      : `DNS ${ms(t.dns_us)} · Connect ${ms(t.connect_us)} · TLS ${ms(t.tls_us)}`;
+    return `${connection} · TTFB ${ms(t.ttfb_us)} · Download ${ms(t.download_us)}`;
+  }
 }
@@ -12,12 +12,13 @@ import { MatDividerModule } from '@angular/material/divider';
 import { MatSliderModule } from '@angular/material/slider';
 import { MatTabsModule } from '@angular/material/tabs';
 import { MatTooltipModule } from '@angular/material/tooltip';
+import { MatCheckboxModule } from '@angular/material/checkbox';
 import { BaseChartDirective } from 'ng2-charts';
 import { ChartConfiguration, ChartData } from 'chart.js';
 import { TauriService } from '../../services/tauri.service';
 import { ServerConfigService } from '../../services/server-config.service';
 import { NotificationService } from '../../services/notification.service';
-import { LoadTestConfig, LoadTestScenario, LoadTestSummary, LoadTestProgress, LoadTestResult } from '../../models/interfaces';
+import { LoadTestConfig, LoadTestScenario, LoadTestSummary, LoadTestProgress, LoadTestResult, RequestTiming, TimingBreakdown, PhaseStats } from '../../models/interfaces';
 
 interface ScenarioInfo {
   id: LoadTestScenario;
@@ -34,7 +35,7 @@ interface ScenarioInfo {
   imports: [
     CommonModule, FormsModule, MatCardModule, MatButtonModule, MatIconModule,
     MatFormFieldModule, MatInputModule, MatSelectModule, MatProgressBarModule,
-    MatDividerModule, MatSliderModule, MatTabsModule, MatTooltipModule, BaseChartDirective
+    MatDividerModule, MatSliderModule, MatTabsModule, MatTooltipModule, MatCheckboxModule, BaseChartDirective
   ],
   templateUrl: './load-test.component.html',
   styleUrl: './load-test.component.scss'
@@ -48,6 +49,10 @@ export class LoadTestComponent implements OnInit, OnDestroy {
   totalRequests = signal(100);
   concurrency = signal(10);
   rampUpSeconds = signal(0);
+  retryAttempts = signal(1);
+  retryBackoffMs = signal(500);
+  targetRps = signal<number | null>(null);
+  captureTraffic = signal(false);
   selectedScenarios = signal<Set<LoadTestScenario>>(new Set(['create_users']));
 
   scenarios: ScenarioInfo[] = [
@@ -83,6 +88,14 @@ export class LoadTestComponent implements OnInit, OnDestroy {
       operations: ['GET /Users?startIndex=N&count=10'],
       requestLabel: 'List requests'
     },
+    {
+      id: 'bulk_import',
+      name: 'Bulk Import',
+      icon: 'upload',
+      description: 'Create users in /Bulk batches sized to the server\'s maxOperations, then clean up.',
+      operations: ['POST /Bulk (POST /Users × batch)', 'DELETE /Users/{id} (cleanup)'],
+      requestLabel: 'Users to import'
+    },
     {
       id: 'create_groups',
       name: 'Create Groups',
@@ -117,7 +130,7 @@ export class LoadTestComponent implements OnInit, OnDestroy {
     },
   ];
 
-  userScenarios = this.scenarios.filter(s => ['create_users', 'create_update', 'full_lifecycle', 'list_users'].includes(s.id));
+  userScenarios = this.scenarios.filter(s => ['create_users', 'create_update', 'full_lifecycle', 'list_users', 'bulk_import'].includes(s.id));
   groupScenarios = this.scenarios.filter(s => ['create_groups', 'group_lifecycle', 'add_remove_members', 'update_groups'].includes(s.id));
 
   // State
@@ -215,6 +228,15 @@ export class LoadTestComponent implements OnInit, OnDestroy {
         total_requests: this.totalRequests(),
         concurrency: this.concurrency(),
         ramp_up_seconds: this.rampUpSeconds(),
+        retry_policy: this.retryAttempts() > 1 ? {
+          max_attempts: this.retryAttempts(),
+          initial_backoff_ms: this.retryBackoffMs(),
+          max_backoff_ms: 30000,
+          jitter: true,
+          respect_retry_after: true
+        } : undefined,
+        target_rps: this.targetRps() || undefined,
+        capture_traffic: this.captureTraffic(),
         endpoints: [],
         scenario: selectedArr[0] || 'create_users',
         scenarios: selectedArr.length > 1 ? selectedArr : undefined
@@ -320,7 +342,8 @@ export class LoadTestComponent implements OnInit, OnDestroy {
         total_requests: 0, successful: 0, failed: 0, error_rate: 0,
         total_duration_ms: 0, min_latency_ms: 0, max_latency_ms: 0,
         avg_latency_ms: 0, p50_latency_ms: 0, p75_latency_ms: 0, p90_latency_ms: 0, p95_latency_ms: 0,
-        p99_latency_ms: 0, requests_per_second: 0, status_code_distribution: {}
+        p99_latency_ms: 0, requests_per_second: 0, status_code_distribution: {},
+        retried_attempts: 0, throttled_responses: 0
       };
     }
 
@@ -349,11 +372,54 @@ export class LoadTestComponent implements OnInit, OnDestroy {
       status_code_distribution[code] = (status_code_distribution[code] || 0) + 1;
     }
 
+    // Retries are stored as extra rows sharing the request_index of the first attempt
+    const retried_attempts = results.filter(r => (r.attempt ?? 1) > 1).length;
+    const throttled_responses = results.filter(r => r.status_code === 429).length;
+
     return {
       total_requests: total, successful, failed, error_rate,
       total_duration_ms, min_latency_ms, max_latency_ms, avg_latency_ms,
       p50_latency_ms, p75_latency_ms, p90_latency_ms, p95_latency_ms, p99_latency_ms,
-      requests_per_second, status_code_distribution
+      requests_per_second, status_code_distribution, retried_attempts, throttled_responses,
+      timing_breakdown: this.computeTimingBreakdown(results)
+    };
+  }
+
+  timingPhases(breakdown: TimingBreakdown): { label: string; stats: PhaseStats }[] {
+    return [
+      { label: 'DNS', stats: breakdown.dns },
+      { label: 'TCP Connect', stats: breakdown.connect },
+      { label: 'TLS Handshake', stats: breakdown.tls },
+      { label: 'Time to First Byte', stats: breakdown.ttfb },
+      { label: 'Download', stats: breakdown.download },
+    ];
+  }
+
+  private computeTimingBreakdown(results: LoadTestResult[]): TimingBreakdown | undefined {
+    const timings = results.map(r => r.timing).filter((t): t is RequestTiming => !!t);
+    if (timings.length === 0) {
+      return undefined;
+    }
+    const fresh = timings.filter(t => !t.connection_reused);
+    const stats = (values: number[]): PhaseStats => {
+      const sorted = [...values].sort((a, b) => a - b);
+      const n = sorted.length;
+      if (n === 0) {
+        return { avg_us: 0, p50_us: 0, p90_us: 0, p95_us: 0, p99_us: 0, max_us: 0 };
+      }
+      const at = (p: number) => sorted[Math.round(p * (n - 1))];
+      return {
+        avg_us: sorted.reduce((a, b) => a + b, 0) / n,
+        p50_us: at(0.5), p90_us: at(0.9), p95_us: at(0.95), p99_us: at(0.99), max_us: sorted[n - 1]
+      };
+    };
+    return {
+      new_connections: fresh.length,
+      dns: stats(fresh.map(t => t.dns_us)),
+      connect: stats(fresh.map(t => t.connect_us)),
+      tls: stats(fresh.map(t => t.tls_us)),
+      ttfb: stats(timings.map(t => t.ttfb_us)),
+      download: stats(timings.map(t => t.download_us))
     };
   }
 }
@@ -18,7 +18,7 @@ import { BaseChartDirective } from 'ng2-charts';
 import { ChartData, ChartConfiguration } from 'chart.js';
 import { TauriService } from '../../services/tauri.service';
 import { NotificationService } from '../../services/notification.service';
-import { TestRun, ExportRequest, ValidationResult, LoadTestResult, LoadTestSummary, ValidationSummary, CategorySummary, ServerConfig } from '../../models/interfaces';
+import { TestRun, ExportRequest, ValidationResult, LoadTestResult, LoadTestSummary, ValidationSummary, CategorySummary, ServerCapability, ServerConfig, RequestTiming, TimingBreakdown, PhaseStats } from '../../models/interfaces';
 
 @Component({
   selector: 'app-reports',
@@ -53,6 +53,7 @@ export class ReportsComponent implements OnInit {
   validationResults = signal<ValidationResult[]>([]);
   validationSummary = signal<ValidationSummary | null>(null);
   validationCategories = signal<{ name: string; results: ValidationResult[]; expanded: boolean }[]>([]);
+  serverCapabilities = signal<ServerCapability[]>([]);
 
   // Load test detail
   loadTestResults = signal<LoadTestResult[]>([]);
@@ -181,6 +182,7 @@ export class ReportsComponent implements OnInit {
       const ext = format === 'pdf' ? 'html' : format === 'excel' ? 'xlsx' : format;
       const filterName = format === 'pdf' ? 'HTML Report (print to PDF)'
                        : format === 'excel' ? 'Excel Workbook'
+                       : format === 'har' ? 'HTTP Archive'
                        : format.toUpperCase();
       const outputPath = await save({
         defaultPath: `report.${ext}`,
@@ -223,6 +225,7 @@ export class ReportsComponent implements OnInit {
     this.validationResults.set([]);
     this.validationSummary.set(null);
     this.validationCategories.set([]);
+    this.serverCapabilities.set([]);
     this.loadTestResults.set([]);
     this.loadTestSummary.set(null);
   }
@@ -235,6 +238,7 @@ export class ReportsComponent implements OnInit {
       // Parse summary from run
       const summary = run.summary_json ? JSON.parse(run.summary_json) as ValidationSummary : null;
       this.validationSummary.set(summary);
+      this.serverCapabilities.set(this.parseCapabilities(run.capabilities_json));
 
       // Group results by category
       const catMap = new Map<string, ValidationResult[]>();
@@ -251,6 +255,25 @@ export class ReportsComponent implements OnInit {
     }
   }
 
+  /** Features from the stored ServiceProviderConfig, in RFC 7643 §5 order. */
+  private parseCapabilities(json?: string): ServerCapability[] {
+    if (!json) return [];
+    let spc: any;
+    try { spc = JSON.parse(json); } catch { return []; }
+    const supported = (f: any): boolean | null => (f && typeof f.supported === 'boolean') ? f.supported : null;
+    const limits = (parts: [string, any][]) =>
+      parts.filter(([, v]) => v != null).map(([k, v]) => `${k} ${v}`).join(', ') || undefined;
+    return [
+      { name: 'patch', supported: supported(spc.patch) },
+      { name: 'bulk', supported: supported(spc.bulk),
+        detail: limits([['maxOperations', spc.bulk?.maxOperations], ['maxPayloadSize', spc.bulk?.maxPayloadSize]]) },
+      { name: 'filter', supported: supported(spc.filter), detail: limits([['maxResults', spc.filter?.maxResults]]) },
+      { name: 'changePassword', supported: supported(spc.changePassword) },
+      { name: 'sort', supported: supported(spc.sort) },
+      { name: 'etag', supported: supported(spc.etag) },
+    ];
+  }
+
   private async loadLoadTestDetail(run: TestRun) {
     try {
       const results = await this.tauriService.getLoadTestResults(run.id);
@@ -316,7 +339,8 @@ export class ReportsComponent implements OnInit {
         total_requests: 0, successful: 0, failed: 0, error_rate: 0,
         total_duration_ms: 0, min_latency_ms: 0, max_latency_ms: 0,
         avg_latency_ms: 0, p50_latency_ms: 0, p75_latency_ms: 0, p90_latency_ms: 0, p95_latency_ms: 0,
-        p99_latency_ms: 0, requests_per_second: 0, status_code_distribution: {}
+        p99_latency_ms: 0, requests_per_second: 0, status_code_distribution: {},
+        retried_attempts: 0, throttled_responses: 0
       };
     }
 
@@ -345,23 +369,94 @@ export class ReportsComponent implements OnInit {
       status_code_distribution[code] = (status_code_distribution[code] || 0) + 1;
     }
 
+    // Retries are stored as extra rows sharing the request_index of the first attempt
+    const retried_attempts = results.filter(r => (r.attempt ?? 1) > 1).length;
+    const throttled_responses = results.filter(r => r.status_code === 429).length;
+
     return {
       total_requests: total, successful, failed, error_rate,
       total_duration_ms: sumDuration, min_latency_ms, max_latency_ms, avg_latency_ms,
       p50_latency_ms, p75_latency_ms, p90_latency_ms, p95_latency_ms, p99_latency_ms,
-      requests_per_second, status_code_distribution
+      requests_per_second, status_code_distribution, retried_attempts, throttled_responses,
+      timing_breakdown: this.computeTimingBreakdown(results)
+    };
+  }
+
+  timingPhases(breakdown: TimingBreakdown): { label: string; stats: PhaseStats }[] {
+    return [
+      { label: 'DNS', stats: breakdown.dns },
+      { label: 'TCP Connect', stats: breakdown.connect },
+      { label: 'TLS Handshake', stats: breakdown.tls },
+      { label: 'Time to First Byte', stats: breakdown.ttfb },
+      { label: 'Download', stats: breakdown.download },
+    ];
+  }
+
+  private computeTimingBreakdown(results: LoadTestResult[]): TimingBreakdown | undefined {
+    const timings = results.map(r => r.timing).filter((t): t is RequestTiming => !!t);
+    if (timings.length === 0) {
+      return undefined;
+    }
+    const fresh = timings.filter(t => !t.connection_reused);
+    const stats = (values: number[]): PhaseStats => {
+      const sorted = [...values].sort((a, b) => a - b);
+      const n = sorted.length;
+      if (n === 0) {
+        return { avg_us: 0, p50_us: 0, p90_us: 0, p95_us: 0, p99_us: 0, max_us: 0 };
+      }
+      const at = (p: number) => sorted[Math.round(p * (n - 1))];
+      return {
+        avg_us: sorted.reduce((a, b) => a + b, 0) / n,
+        p50_us: at(0.5), p90_us: at(0.9), p95_us: at(0.95), p99_us: at(0.99), max_us: sorted[n - 1]
+      };
+    };
+    return {
+      new_connections: fresh.length,
+      dns: stats(fresh.map(t => t.dns_us)),
+      connect: stats(fresh.map(t => t.connect_us)),
+      tls: stats(fresh.map(t => t.tls_us)),
+      ttfb: stats(timings.map(t => t.ttfb_us)),
+      download: stats(timings.map(t => t.download_us))
     };
   }
 
   getPassRate(results: ValidationResult[]): number {
-    if (results.length === 0) return 0;
-    return Math.round((results.filter(r => r.passed).length / results.length) * 100);
+    const counted = results.length - this.getCategorySkipCount(results);
+    if (counted === 0) return 0;
+    return Math.round((this.getCategoryPassCount(results) / counted) * 100);
   }
 
   getCategoryPassCount(results: ValidationResult[]): number {
     return results.filter(r => r.passed).length;
   }
 
+  getCategoryFailCount(results: ValidationResult[]): number {
+    return results.filter(r => r.outcome === 'failed' || r.outcome === 'error').length;
+  }
+
+  getCategorySkipCount(results: ValidationResult[]): number {
+    return results.filter(r => r.outcome === 'skipped').length;
+  }
+
+  outcomeIcon(result: ValidationResult): string {
+    switch (result.outcome) {
+      case 'passed': return 'check_circle';
+      case 'warning': return 'warning';
+      case 'skipped': return 'remove_circle';
+      case 'error': return 'error';
+      default: return 'cancel';
+    }
+  }
+
+  outcomeIconClass(result: ValidationResult): string {
+    switch (result.outcome) {
+      case 'passed': return 'pass-icon';
+      case 'warning': return 'warn-icon';
+      case 'skipped': return 'skip-icon';
+      default: return 'fail-icon';
+    }
+  }
+
   // ── Comparison ──
 
   compareSelected() {
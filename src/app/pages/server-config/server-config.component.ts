@@ -47,6 +47,21 @@ export class ServerConfigComponent implements OnInit {
     auth_password: '',
     api_key_header: '',
     api_key_value: '',
+    oauth_token_url: '',
+    oauth_client_id: '',
+    oauth_client_secret: '',
+    oauth_scope: '',
+    oauth_audience: '',
+    client_cert_format: 'pem',
+    client_cert: '',
+    client_key: '',
+    client_cert_password: '',
+    tls_mode: 'strict',
+    ca_bundle: '',
+    proxy_url: '',
+    proxy_username: '',
+    proxy_password: '',
+    custom_headers: [],
   });
 
   async ngOnInit(): Promise<void> {
@@ -63,13 +78,28 @@ export class ServerConfigComponent implements OnInit {
       auth_password: '',
       api_key_header: '',
       api_key_value: '',
+      oauth_token_url: '',
+      oauth_client_id: '',
+      oauth_client_secret: '',
+      oauth_scope: '',
+      oauth_audience: '',
+      client_cert_format: 'pem',
+      client_cert: '',
+      client_key: '',
+      client_cert_password: '',
+      tls_mode: 'strict',
+      ca_bundle: '',
+      proxy_url: '',
+      proxy_username: '',
+      proxy_password: '',
+      custom_headers: [],
     });
     this.editing.set(true);
     this.connectionResult.set(null);
   }
 
   editConfig(config: ServerConfig): void {
-    this.formData.set({ ...config });
+    this.formData.set({ ...config, custom_headers: [...(config.custom_headers ?? [])] });
     this.editing.set(true);
     this.connectionResult.set(null);
   }
@@ -90,7 +120,7 @@ export class ServerConfigComponent implements OnInit {
       this.editing.set(false);
       this.notify.success('Server configuration saved');
     } catch (e: any) {
-      this.notify.error('Failed to save: ' + e);
+      this.notify.error('Failed to save: ' + (e?.message || e));
     }
   }
 
@@ -99,7 +129,7 @@ export class ServerConfigComponent implements OnInit {
       await this.serverConfigService.deleteConfig(id);
       this.notify.success('Configuration deleted');
     } catch (e: any) {
-      this.notify.error('Failed to delete: ' + e);
+      this.notify.error('Failed to delete: ' + (e?.message || e));
     }
   }
 
@@ -128,7 +158,7 @@ export class ServerConfigComponent implements OnInit {
         this.notify.error(`Connection failed: ${result.error || 'Unknown error'}`);
       }
     } catch (e: any) {
-      this.notify.error('Connection test failed: ' + e);
+      this.notify.error('Connection test failed: ' + (e?.message || e));
     } finally {
       this.testing.set(false);
     }
@@ -142,4 +172,19 @@ export class ServerConfigComponent implements OnInit {
   updateFormField(field: string, value: any): void {
     this.formData.update(f => ({ ...f, [field]: value }));
   }
+
+  addCustomHeader(): void {
+    this.formData.update(f => ({ ...f, custom_headers: [...(f.custom_headers ?? []), { name: '', value: '' }] }));
+  }
+
+  updateCustomHeader(index: number, field: 'name' | 'value', value: string): void {
+    this.formData.update(f => ({
+      ...f,
+      custom_headers: (f.custom_headers ?? []).map((h, i) => i === index ? { ...h, [field]: value } : h),
+    }));
+  }
+
+  removeCustomHeader(index: number): void {
+    this.formData.update(f => ({ ...f, custom_headers: (f.custom_headers ?? []).filter((_, i) => i !== index) }));
+  }
 }
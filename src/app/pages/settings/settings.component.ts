@@ -13,6 +13,7 @@ import { MatProgressSpinnerModule } from '@angular/material/progress-spinner';
 import { ThemeService } from '../../services/theme.service';
 import { TauriService } from '../../services/tauri.service';
 import { NotificationService } from '../../services/notification.service';
+import { CredentialProtection } from '../../models';
 
 @Component({
   selector: 'app-settings',
@@ -40,10 +41,80 @@ export class SettingsComponent implements OnInit {
   savingKey = signal(false);
   testingKey = signal(false);
 
+  // Credential encryption
+  credentialProtection = signal<CredentialProtection | null>(null);
+  passphrase = signal('');
+  passphraseConfirm = signal('');
+  savingPassphrase = signal(false);
+
   async ngOnInit() {
+    await this.loadCredentialProtection();
     await this.loadOpenAiKey();
   }
 
+  private async loadCredentialProtection() {
+    try {
+      this.credentialProtection.set(await this.tauriService.getCredentialProtection());
+    } catch { /* ignore */ }
+  }
+
+  async unlockCredentials() {
+    const passphrase = this.passphrase();
+    if (!passphrase) {
+      this.notificationService.error('Please enter your passphrase.');
+      return;
+    }
+    this.savingPassphrase.set(true);
+    try {
+      await this.tauriService.unlockCredentials(passphrase);
+      this.passphrase.set('');
+      await this.loadCredentialProtection();
+      await this.loadOpenAiKey();
+      this.notificationService.success('Saved credentials unlocked.');
+    } catch (err: any) {
+      this.notificationService.error('Failed to unlock: ' + (err?.message || err));
+    } finally {
+      this.savingPassphrase.set(false);
+    }
+  }
+
+  async savePassphrase() {
+    const passphrase = this.passphrase();
+    if (passphrase.length < 8) {
+      this.notificationService.error('The passphrase must be at least 8 characters.');
+      return;
+    }
+    if (passphrase !== this.passphraseConfirm()) {
+      this.notificationService.error('The passphrases do not match.');
+      return;
+    }
+    this.savingPassphrase.set(true);
+    try {
+      await this.tauriService.setCredentialPassphrase(passphrase);
+      this.passphrase.set('');
+      this.passphraseConfirm.set('');
+      await this.loadCredentialProtection();
+      this.notificationService.success('Passphrase saved. You will be asked for it after the next restart.');
+    } catch (err: any) {
+      this.notificationService.error('Failed to set passphrase: ' + (err?.message || err));
+    } finally {
+      this.savingPassphrase.set(false);
+    }
+  }
+
+  async removePassphrase() {
+    this.savingPassphrase.set(true);
+    try {
+      await this.tauriService.setCredentialPassphrase(null);
+      await this.loadCredentialProtection();
+      this.notificationService.success('Passphrase removed. Credentials are now encrypted with a local key file.');
+    } catch (err: any) {
+      this.notificationService.error('Failed to remove passphrase: ' + (err?.message || err));
+    } finally {
+      this.savingPassphrase.set(false);
+    }
+  }
+
   private async loadOpenAiKey() {
     try {
       const key = await this.tauriService.getAppSetting('openai_api_key');
@@ -17,7 +17,10 @@ import { MatInputModule } from '@angular/material/input';
 import { TauriService } from '../../services/tauri.service';
 import { ServerConfigService } from '../../services/server-config.service';
 import { NotificationService } from '../../services/notification.service';
-import { ValidationResult, ValidationSummary, ValidationProgress, ValidationRunConfig, CategorySummary, DiscoveredSchemaAttribute, ExportRequest } from '../../models/interfaces';
+import { ValidationResult, ValidationSummary, ValidationProgress, ValidationRunConfig, CategorySummary, DiscoveredSchemaAttribute, ExportRequest, RequestTiming, Severity } from '../../models/interfaces';
+
+// Same weights as Severity::weight on the backend
+const SEVERITY_WEIGHTS: Record<Severity, number> = { critical: 8, high: 4, medium: 2, low: 1 };
 
 interface CategoryToggle {
   key: string;
@@ -46,14 +49,23 @@ export class ValidationComponent implements OnInit, OnDestroy {
   userJoiningProperty = signal('userName');
   groupJoiningProperty = signal('displayName');
 
+  // Retry throttled (429/503) responses instead of failing the test outright
+  retryThrottled = signal(false);
+  captureTraffic = signal(false);
+
   categories = signal<CategoryToggle[]>([
     { key: 'schema_discovery', label: 'Schema Discovery', enabled: true },
+    { key: 'tls_certificate', label: 'TLS Certificate', enabled: true },
+    { key: 'protocol', label: 'Protocol (Content-Type, Errors)', enabled: true },
     { key: 'users_crud', label: 'Users CRUD', enabled: true },
     { key: 'groups_crud', label: 'Groups CRUD', enabled: true },
     { key: 'patch_operations', label: 'PATCH Operations', enabled: true },
     { key: 'filtering_pagination', label: 'Filtering & Pagination', enabled: true },
     { key: 'duplicate_detection', label: 'Duplicate Detection (409)', enabled: true },
     { key: 'soft_delete', label: 'Soft Delete (active=false)', enabled: true },
+    { key: 'etag_versioning', label: 'ETag Versioning (If-Match)', enabled: true },
+    { key: 'resource_metadata', label: 'Resource Metadata (meta, Location)', enabled: true },
+    { key: 'bulk_operations', label: 'Bulk Operations (/Bulk)', enabled: true },
     { key: 'group_operations', label: 'Group PATCH & Membership', enabled: true },
     { key: 'field_mapping', label: 'Field Mapping Rules', enabled: true },
     { key: 'custom_schema', label: 'Custom Schema Properties', enabled: true }
@@ -69,15 +81,17 @@ export class ValidationComponent implements OnInit, OnDestroy {
   private unlistenProgress: (() => void) | null = null;
 
   // Result filtering & search
-  resultFilter = signal<'all' | 'pass' | 'fail'>('all');
+  resultFilter = signal<'all' | 'pass' | 'fail' | 'skip'>('all');
   searchQuery = signal('');
-  failCount = computed(() => this.results().filter(r => !r.passed).length);
+  failCount = computed(() => this.results().filter(r => this.isFailure(r)).length);
+  skipCount = computed(() => this.results().filter(r => r.outcome === 'skipped').length);
   groupedResults = computed(() => {
     const filter = this.resultFilter();
     const q = this.searchQuery().toLowerCase().trim();
     const filtered = this.results().filter(r => {
       if (filter === 'pass' && !r.passed) return false;
-      if (filter === 'fail' && r.passed) return false;
+      if (filter === 'fail' && !this.isFailure(r)) return false;
+      if (filter === 'skip' && r.outcome !== 'skipped') return false;
       if (q && !r.test_name.toLowerCase().includes(q) && !r.category.toLowerCase().includes(q)) return false;
       return true;
     });
@@ -183,7 +197,15 @@ export class ValidationComponent implements OnInit, OnDestroy {
         server_config_id: configId,
         categories: this.enabledCategories,
         user_joining_property: this.userJoiningProperty(),
-        group_joining_property: this.groupJoiningProperty()
+        group_joining_property: this.groupJoiningProperty(),
+        retry_policy: this.retryThrottled() ? {
+          max_attempts: 4,
+          initial_backoff_ms: 500,
+          max_backoff_ms: 30000,
+          jitter: true,
+          respect_retry_after: true
+        } : undefined,
+        capture_traffic: this.captureTraffic()
       });
       this.currentRunId.set(runId);
       this.notificationService.success('Validation completed!');
@@ -232,6 +254,7 @@ export class ValidationComponent implements OnInit, OnDestroy {
           { name: 'HTML Report (print to PDF)', extensions: ['html'] },
           { name: 'CSV',  extensions: ['csv']  },
           { name: 'JSON', extensions: ['json'] },
+          { name: 'HAR (captured traffic)', extensions: ['har'] },
         ],
       });
       if (!outputPath) return;
@@ -240,6 +263,7 @@ export class ValidationComponent implements OnInit, OnDestroy {
       const format = ext === 'xlsx' ? 'excel'
                    : ext === 'html' ? 'pdf'
                    : ext === 'csv'  ? 'csv'
+                   : ext === 'har'  ? 'har'
                    : 'json';
 
       const request: ExportRequest = { test_run_id: runId, format, output_path: outputPath };
@@ -261,6 +285,7 @@ export class ValidationComponent implements OnInit, OnDestroy {
     switch (status) {
       case 'pass': return 'check_circle';
       case 'fail': return 'cancel';
+      case 'warn': return 'warning';
       case 'skip': return 'remove_circle';
       case 'error': return 'error';
       default: return 'help';
@@ -271,7 +296,8 @@ export class ValidationComponent implements OnInit, OnDestroy {
     switch (status) {
       case 'pass': return 'green';
       case 'fail': return 'red';
-      case 'skip': return 'orange';
+      case 'warn': return 'orange';
+      case 'skip': return 'grey';
       case 'error': return 'red';
       default: return 'grey';
     }
@@ -323,6 +349,10 @@ export class ValidationComponent implements OnInit, OnDestroy {
           parts.push(`  -H '${config.api_key_header}: ${ masked ? '***' : config.api_key_value }'`);
         }
         break;
+      case 'oauth2_client_credentials':
+        // The access token is fetched at run time, so only a placeholder can be shown
+        parts.push(`  -H 'Authorization: Bearer ${ masked ? '***' : '<access_token>' }'`);
+        break;
     }
 
     if (result.request_body) {
@@ -342,13 +372,21 @@ export class ValidationComponent implements OnInit, OnDestroy {
   }
 
   getGroupFailCount(catKey: string): number {
-    return this.getGroupResults(catKey).filter(r => !r.passed).length;
+    return this.getGroupResults(catKey).filter(r => this.isFailure(r)).length;
+  }
+
+  getGroupSkipCount(catKey: string): number {
+    return this.getGroupResults(catKey).filter(r => r.outcome === 'skipped').length;
   }
 
   getGroupPassCount(catKey: string): number {
     return this.getGroupResults(catKey).filter(r => r.passed).length;
   }
 
+  getGroupViolationCount(catKey: string): number {
+    return this.getGroupResults(catKey).reduce((n, r) => n + r.schema_violations.length, 0);
+  }
+
   async copyCurl(result: ValidationResult) {
     const cmd = this.getCurlCommand(result);
     try {
@@ -359,21 +397,54 @@ export class ValidationComponent implements OnInit, OnDestroy {
     }
   }
 
+  /** Status key for getStatusIcon/getStatusColor. */
+  statusOf(result: ValidationResult): string {
+    switch (result.outcome) {
+      case 'passed': return 'pass';
+      case 'warning': return 'warn';
+      case 'skipped': return 'skip';
+      case 'error': return 'error';
+      default: return 'fail';
+    }
+  }
+
+  /** Pass rate of a category, leaving out its skipped tests. */
+  categoryScore(cat: CategorySummary): number {
+    const counted = cat.total - (cat.skipped ?? 0);
+    return counted > 0 ? (cat.passed / counted) * 100 : 0;
+  }
+
+  private isFailure(r: ValidationResult): boolean {
+    return r.outcome === 'failed' || r.outcome === 'error';
+  }
+
   private computeValidationSummary(results: ValidationResult[]): ValidationSummary {
     const total = results.length;
     const passed = results.filter(r => r.passed).length;
-    const failed = total - passed;
+    const failed = results.filter(r => this.isFailure(r)).length;
+    const skipped = results.filter(r => r.outcome === 'skipped').length;
+    const warnings = results.filter(r => r.outcome === 'warning').length;
+    const errors = results.filter(r => r.outcome === 'error').length;
     const duration_ms = results.reduce((sum, r) => sum + r.duration_ms, 0);
-    const compliance_score = total > 0 ? (passed / total) * 100 : 0;
-
-    const catMap = new Map<string, { total: number; passed: number; failed: number }>();
+    // Skipped tests say nothing about the server, so they are out of the score
+    const compliance_score = total - skipped > 0 ? (passed / (total - skipped)) * 100 : 0;
+    const counted = results.filter(r => r.outcome !== 'skipped');
+    const weightOf = (r: ValidationResult) => SEVERITY_WEIGHTS[r.severity] ?? 2;
+    // A warning earns half the weight of a clean pass
+    const creditOf = (r: ValidationResult) => r.outcome === 'warning' ? 0.5 : r.passed ? 1 : 0;
+    const totalWeight = counted.reduce((sum, r) => sum + weightOf(r), 0);
+    const earnedWeight = counted.reduce((sum, r) => sum + weightOf(r) * creditOf(r), 0);
+    const weighted_score = totalWeight > 0 ? (earnedWeight / totalWeight) * 100 : 0;
+
+    const catMap = new Map<string, { total: number; passed: number; failed: number; skipped: number }>();
     for (const r of results) {
       if (!catMap.has(r.category)) {
-        catMap.set(r.category, { total: 0, passed: 0, failed: 0 });
+        catMap.set(r.category, { total: 0, passed: 0, failed: 0, skipped: 0 });
       }
       const cat = catMap.get(r.category)!;
       cat.total++;
       if (r.passed) cat.passed++;
+      else if (r.outcome === 'skipped') cat.skipped++;
       else cat.failed++;
     }
 
@@ -382,6 +453,17 @@ export class ValidationComponent implements OnInit, OnDestroy {
       ...stats
     }));
 
-    return { total, passed, failed, skipped: 0, compliance_score, duration_ms, categories };
+    return { total, passed, failed, skipped, warnings, errors, compliance_score, weighted_score, duration_ms, categories };
+  }
+
+  timingTooltip(t?: RequestTiming): string {
+    if (!t) {
+      return '';
+    }
+    const ms = (us: number) => (us / 1000).toFixed(2) + 'ms';
+    const connection = t.connection_reused
+      ? 'Reused connection'
+      : `DNS ${ms(t.dns_us)} · Connect ${ms(t.connect_us)} · TLS ${ms(t.tls_us)}`;
+    return `${connection} · TTFB ${ms(t.ttfb_us)} · Download ${ms(t.download_us)}`;
   }
 }
@@ -9,7 +9,7 @@ import { DiscoveredSchemaAttribute } from '../models/interfaces';
  * Architecture:
  *  - Schemas are fetched once per server selection (auto + manual refresh).
  *  - Raw SCIM schemas are converted to JSON Schema draft-07 objects.
- *  - Separate JSON Schemas are built for User, Group, and PatchOp.
+ *  - Separate JSON Schemas are built for User, Group, PatchOp and SearchRequest.
  *  - Each Monaco editor uses a unique model URI (e.g. scim://sample-data/user.json).
  *  - fileMatch patterns route the correct schema to each editor automatically.
  *  - Future screens (Explorer, Validation, etc.) register their URIs via addFileMatch().
@@ -63,6 +63,7 @@ export class ScimSchemaService {
   private userFileMatches = signal<string[]>(['scim://sample-data/user.json']);
   private groupFileMatches = signal<string[]>(['scim://sample-data/group.json']);
   private patchOpFileMatches = signal<string[]>(['scim://explorer/patchop.json']);
+  private searchRequestFileMatches = signal<string[]>(['scim://explorer/searchrequest.json']);
 
   // Track whether Monaco schemas need re-registration
   private monacoRegistered = false;
@@ -123,6 +124,44 @@ export class ScimSchemaService {
     additionalProperties: false,
   };
 
+  /** JSON Schema for SCIM SearchRequest (POST /.search) */
+  searchRequestJsonSchema: any = {
+    $schema: 'http://json-schema.org/draft-07/schema#',
+    title: 'SCIM SearchRequest',
+    description: 'SCIM 2.0 query via HTTP POST (RFC 7644 §3.4.3)',
+    type: 'object',
+    required: ['schemas'],
+    properties: {
+      schemas: {
+        type: 'array',
+        items: {
+          type: 'string',
+          enum: ['urn:ietf:params:scim:api:messages:2.0:SearchRequest'],
+        },
+        description: 'Must contain "urn:ietf:params:scim:api:messages:2.0:SearchRequest"',
+      },
+      attributes: {
+        type: 'array',
+        items: { type: 'string' },
+        description: 'Attributes to return, overriding the default set',
+      },
+      excludedAttributes: {
+        type: 'array',
+        items: { type: 'string' },
+        description: 'Attributes to leave out of the default set',
+      },
+      filter: {
+        type: 'string',
+        description: 'Filter expression (e.g. "userName sw \\"j\\" and active eq true")',
+      },
+      sortBy: { type: 'string', description: 'Attribute to sort by' },
+      sortOrder: { type: 'string', enum: ['ascending', 'descending'] },
+      startIndex: { type: 'integer', minimum: 1, description: '1-based index of the first result' },
+      count: { type: 'integer', minimum: 0, description: 'Maximum number of results per page' },
+    },
+    additionalProperties: false,
+  };
+
   // ── Public methods ──
 
   /**
@@ -180,7 +219,7 @@ export class ScimSchemaService {
    * Register additional fileMatch URIs so future screens get IntelliSense automatically.
    * Example: scimSchemaService.addFileMatch('user', 'scim://explorer/user.json')
    */
-  addFileMatch(schemaType: 'user' | 'group' | 'patchop', uri: string): void {
+  addFileMatch(schemaType: 'user' | 'group' | 'patchop' | 'searchrequest', uri: string): void {
     if (schemaType === 'user') {
       const current = this.userFileMatches();
       if (!current.includes(uri)) {
@@ -199,6 +238,12 @@ export class ScimSchemaService {
         this.patchOpFileMatches.set([...current, uri]);
         this.registerMonacoSchemas();
       }
+    } else if (schemaType === 'searchrequest') {
+      const current = this.searchRequestFileMatches();
+      if (!current.includes(uri)) {
+        this.searchRequestFileMatches.set([...current, uri]);
+        this.registerMonacoSchemas();
+      }
     }
   }
 
@@ -242,6 +287,11 @@ export class ScimSchemaService {
           fileMatch: [...this.patchOpFileMatches()],
           schema: this.patchOpJsonSchema,
         },
+        {
+          uri: 'http://scim-inspector/searchrequest-schema.json',
+          fileMatch: [...this.searchRequestFileMatches()],
+          schema: this.searchRequestJsonSchema,
+        },
       ],
     });
     this.monacoRegistered = true;
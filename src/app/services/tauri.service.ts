@@ -15,6 +15,7 @@ import {
   ExplorerRequest,
   ExplorerResponse,
   SampleData,
+  CredentialProtection,
 } from '../models';
 
 // Lazy-load Tauri APIs so the import doesn't break in browsers
@@ -303,8 +304,38 @@ export class TauriService {
     lsSet('scim_app_settings', settings);
   }
 
+  // ── Credential Encryption ──
+
+  async getCredentialProtection(): Promise<CredentialProtection> {
+    await this.ready;
+    if (isTauri()) return tauriInvoke!<CredentialProtection>('get_credential_protection');
+    return { mode: 'key_file', locked: false }; // browser mode keeps everything in localStorage
+  }
+
+  async unlockCredentials(passphrase: string): Promise<void> {
+    await this.ready;
+    if (isTauri()) return tauriInvoke!<void>('unlock_credentials', { passphrase });
+  }
+
+  async setCredentialPassphrase(passphrase: string | null): Promise<void> {
+    await this.ready;
+    if (isTauri()) return tauriInvoke!<void>('set_credential_passphrase', { passphrase });
+  }
+
   // ── SCIM Explorer ──
 
+  /** Returns the parse error for an RFC 7644 filter, or null if it is valid. */
+  async validateScimFilter(filter: string): Promise<string | null> {
+    await this.ready;
+    if (!isTauri()) return null; // the parser lives in the backend
+    try {
+      await tauriInvoke!<void>('validate_scim_filter', { filter });
+      return null;
+    } catch (err: any) {
+      return err?.message || String(err);
+    }
+  }
+
   async executeScimRequest(request: ExplorerRequest): Promise<ExplorerResponse> {
     await this.ready;
     if (isTauri()) return tauriInvoke!<ExplorerResponse>('execute_scim_request', { request });
@@ -314,12 +345,17 @@ export class TauriService {
     const config = configs.find(c => c.id === request.server_config_id);
     if (!config) throw new Error('Server config not found');
 
+    // URLSearchParams encodes spaces as '+', which SCIM servers may not decode in
+    // filters, so the query is built with encodeURIComponent instead.
     const url = new URL(request.path, config.base_url.replace(/\/$/, '') + '/');
     if (request.query_params) {
-      request.query_params.split('&').filter(Boolean).forEach(p => {
-        const [k, v] = p.split('=');
-        if (k) url.searchParams.set(decodeURIComponent(k), decodeURIComponent(v ?? ''));
+      const decode = (v: string) => { try { return decodeURIComponent(v); } catch { return v; } };
+      const extra = request.query_params.split('&').filter(Boolean).map(p => {
+        const i = p.indexOf('=');
+        const [k, v] = i < 0 ? [p, ''] : [p.slice(0, i), p.slice(i + 1)];
+        return `${encodeURIComponent(decode(k))}=${encodeURIComponent(decode(v))}`;
       });
+      url.search = [url.search.replace(/^\?/, ''), ...extra].filter(Boolean).join('&');
     }
 
     const headers: Record<string, string> = { 'Content-Type': 'application/scim+json' };